@@ -0,0 +1,497 @@
+use crate::adapters::interface::{
+    classify_adapter_error, AdapterClient, AdapterError, AdapterErrorEnvelope, EmbedRequest,
+    EmbedResponse, GenerateChunk, GenerateRequest, GenerateResponse, ModelCallMeta, StreamOptions,
+    TranscribeRequest, TranscribeResponse,
+};
+use crate::adapters::retry::RetryStop;
+use crate::adapters::routing::TaskType;
+use crate::audit::event::{Actor, AuditEvent};
+use crate::audit::log::AuditLog;
+use crate::determinism::run_id::sha256_hex;
+use crate::error::{CoreError, CoreResult};
+use crate::evidence_bundle::schemas::{ModelCallSummary, RunManifest};
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+
+/// Run context attached to every model call event.
+#[derive(Debug, Clone)]
+pub struct ModelCallScope {
+    pub run_id: String,
+    pub vault_id: String,
+    pub adapter_version: String,
+    pub input_artifact_refs: Vec<String>,
+}
+
+/// Records adapter calls as MODEL_CALL_STARTED followed by MODEL_CALL_COMPLETED or
+/// MODEL_CALL_FAILED, and keeps one `ModelCallMeta` per call for the run manifest.
+///
+/// `request_hash_sha256` covers the JSON body sent to the adapter. `response_hash_sha256` is
+/// computed by core from the returned output, never taken from the adapter's `output_hash`.
+///
+/// When the client carries a `RetryControl`, each failed attempt is recorded as its own
+/// MODEL_CALL_FAILED with `meta.attempt`, under the call's single MODEL_CALL_STARTED.
+#[derive(Debug, Clone, Default)]
+pub struct ModelCallLog {
+    calls: Vec<ModelCallMeta>,
+}
+
+impl ModelCallLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn generate(
+        &mut self,
+        audit: &mut AuditLog,
+        scope: &ModelCallScope,
+        client: &dyn AdapterClient,
+        req: &GenerateRequest,
+    ) -> CoreResult<GenerateResponse> {
+        let call = CallStart::new(TaskType::LLM, &req.call_id, &req.model_id, req)?;
+        self.run(audit, scope, client, call, |_| {
+            let resp = client.generate(req)?;
+            check_generate_status(&resp.status)?;
+            let output_hash = generate_output_hash(&resp)?;
+            Ok((output_hash, resp.usage.clone(), resp))
+        })
+    }
+
+    /// Streams a TEXT generate call, passing each delta to `on_delta` as it arrives. The output
+    /// is hashed incrementally, so the recorded hash equals that of the non-streamed response.
+    /// Cancellation and `opts.timeout` are checked between chunks; an aborted stream records the
+    /// hash of the output received so far under `meta` of MODEL_CALL_FAILED.
+    pub fn generate_stream(
+        &mut self,
+        audit: &mut AuditLog,
+        scope: &ModelCallScope,
+        client: &dyn AdapterClient,
+        req: &GenerateRequest,
+        opts: &StreamOptions,
+        on_delta: &mut dyn FnMut(&str),
+    ) -> CoreResult<GenerateResponse> {
+        if req.response_mode != "TEXT" {
+            return Err(CoreError::InvalidInput(
+                "streaming supports TEXT responses only".to_string(),
+            ));
+        }
+        let mut call = CallStart::new(TaskType::LLM, &req.call_id, &req.model_id, req)?;
+        call.timeout = Some(opts.timeout);
+        self.run(audit, scope, client, call, |progress| {
+            let deadline = Instant::now() + opts.timeout;
+            let step = "generate stream";
+            opts.cancel.check(step)?;
+            let mut stream = client.generate_stream(req, opts)?;
+            let mut text = String::new();
+            loop {
+                opts.cancel.check(step)?;
+                if Instant::now() >= deadline {
+                    let mut env = classify_adapter_error(&format!(
+                        "stream timeout after {} ms",
+                        opts.timeout.as_millis()
+                    ));
+                    env.error.code = "STREAM_TIMEOUT".to_string();
+                    return Err(CoreError::Adapter(Box::new(env)));
+                }
+                match stream.next_chunk()? {
+                    Some(GenerateChunk::Delta(delta)) => {
+                        progress.update(&delta);
+                        text.push_str(&delta);
+                        on_delta(&delta);
+                    }
+                    Some(GenerateChunk::Done {
+                        status,
+                        usage,
+                        timing_ms,
+                    }) => {
+                        check_generate_status(&status)?;
+                        let resp = GenerateResponse {
+                            output_text: Some(text),
+                            output_json: None,
+                            usage: usage.clone(),
+                            timing_ms,
+                            output_hash: None,
+                            status,
+                        };
+                        return Ok((progress.hex(), usage, resp));
+                    }
+                    None => {
+                        return Err(adapter_failure(
+                            "RUNTIME_ERROR",
+                            "MODEL_ERROR",
+                            "generate stream ended without a done chunk",
+                        ))
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn embed(
+        &mut self,
+        audit: &mut AuditLog,
+        scope: &ModelCallScope,
+        client: &dyn AdapterClient,
+        req: &EmbedRequest,
+    ) -> CoreResult<EmbedResponse> {
+        let call = CallStart::new(TaskType::EMBED, &req.call_id, &req.model_id, req)?;
+        self.run(audit, scope, client, call, |_| {
+            let resp = client.embed(req)?;
+            if resp.vectors.len() != req.texts.len()
+                || resp.vectors.iter().any(|v| v.len() != resp.dim)
+            {
+                return Err(adapter_failure(
+                    "RUNTIME_ERROR",
+                    "MODEL_ERROR",
+                    "embed returned vectors that do not match texts/dim",
+                ));
+            }
+            let output_hash = sha256_hex(&serde_json::to_vec(&resp.vectors)?);
+            let usage = json!({ "texts": req.texts.len(), "dim": resp.dim });
+            Ok((output_hash, usage, resp))
+        })
+    }
+
+    pub fn transcribe(
+        &mut self,
+        audit: &mut AuditLog,
+        scope: &ModelCallScope,
+        client: &dyn AdapterClient,
+        req: &TranscribeRequest,
+    ) -> CoreResult<TranscribeResponse> {
+        let call = CallStart::new(TaskType::STT, &req.call_id, &req.model_id, req)?;
+        self.run(audit, scope, client, call, |_| {
+            let resp = client.transcribe(req)?;
+            let output_hash = sha256_hex(&serde_json::to_vec(&resp.segments)?);
+            let usage = json!({ "segments": resp.segments.len() });
+            Ok((output_hash, usage, resp))
+        })
+    }
+
+    pub fn calls(&self) -> &[ModelCallMeta] {
+        &self.calls
+    }
+
+    pub fn summaries(&self) -> Vec<ModelCallSummary> {
+        self.calls
+            .iter()
+            .map(|c| ModelCallSummary {
+                call_id: c.call_id.clone(),
+                model_id: c.model_id.clone(),
+                adapter_version: c.adapter_version.clone(),
+                status: c.status.clone(),
+                input_hash: c.input_hash.clone(),
+                output_hash: c.output_hash.clone(),
+            })
+            .collect()
+    }
+
+    /// Appends recorded calls to `manifest.model_calls`, skipping call_ids already listed.
+    pub fn fill_manifest(&self, manifest: &mut RunManifest) {
+        for summary in self.summaries() {
+            if !manifest
+                .model_calls
+                .iter()
+                .any(|c| c.call_id == summary.call_id)
+            {
+                manifest.model_calls.push(summary);
+            }
+        }
+    }
+
+    fn run<T>(
+        &mut self,
+        audit: &mut AuditLog,
+        scope: &ModelCallScope,
+        client: &dyn AdapterClient,
+        call: CallStart,
+        mut invoke: impl FnMut(&mut StreamingHash) -> CoreResult<(String, serde_json::Value, T)>,
+    ) -> CoreResult<T> {
+        if self.calls.iter().any(|c| c.call_id == call.call_id) {
+            return Err(CoreError::InvalidInput(format!(
+                "duplicate model call_id {}",
+                call.call_id
+            )));
+        }
+        let mut refs = scope.input_artifact_refs.clone();
+        refs.sort();
+        refs.dedup();
+        let timeout_ms = call
+            .timeout
+            .or_else(|| client.call_timeout())
+            .map(|t| t.as_millis() as u64);
+        emit(
+            audit,
+            scope,
+            "MODEL_CALL_STARTED",
+            json!({
+                "call_id": call.call_id,
+                "task_type": call.task_type,
+                "input_artifact_refs": refs,
+                "request_hash_sha256": call.request_hash,
+                "timeout_ms": timeout_ms.unwrap_or(0),
+                "meta": {
+                    "model_id": call.model_id,
+                    "adapter_endpoint": client.endpoint()
+                }
+            }),
+        )?;
+
+        let retry = client.retry_control();
+        let started = Instant::now();
+        let mut attempt: u32 = 1;
+        loop {
+            let mut progress = StreamingHash::new();
+            let result = invoke(&mut progress);
+            let duration_ms = started.elapsed().as_millis() as u64;
+            let err = match result {
+                Ok((output_hash, usage, resp)) => {
+                    let mut details = json!({
+                        "call_id": call.call_id,
+                        "response_hash_sha256": output_hash,
+                        "duration_ms": duration_ms
+                    });
+                    if attempt > 1 {
+                        details["meta"] = json!({ "attempts": attempt });
+                    }
+                    emit(audit, scope, "MODEL_CALL_COMPLETED", details)?;
+                    self.calls
+                        .push(call.finish(scope, output_hash, duration_ms, usage, "ok"));
+                    return Ok(resp);
+                }
+                Err(err) => err,
+            };
+
+            let (category, code) = failure_category(&err);
+            let mut details = json!({
+                "call_id": call.call_id,
+                "error_category": category,
+                "error_code": code,
+                "error_message_redacted": redacted_message(category, &code)
+            });
+            let mut meta = serde_json::Map::new();
+            if let CoreError::Adapter(env) = &err {
+                meta.insert("adapter_category".into(), json!(env.error.category));
+                meta.insert("retryable".into(), json!(env.error.retryable));
+            }
+            let partial_hash = if progress.chunks() > 0 {
+                meta.insert("partial_output_hash_sha256".into(), json!(progress.hex()));
+                meta.insert("partial_output_bytes".into(), json!(progress.bytes()));
+                meta.insert("partial_output_chunks".into(), json!(progress.chunks()));
+                progress.hex()
+            } else {
+                String::new()
+            };
+            // Deltas already handed to the caller cannot be taken back, so a stream that
+            // produced output is never retried.
+            let next = retry.map(|r| match progress.chunks() {
+                0 => r.decide(attempt, &err),
+                _ => Err(RetryStop::PARTIAL_OUTPUT),
+            });
+            if let Some(next) = &next {
+                meta.insert("attempt".into(), json!(attempt));
+                meta.insert("will_retry".into(), json!(next.is_ok()));
+                match next {
+                    Ok(backoff) => {
+                        meta.insert("backoff_ms".into(), json!(backoff.as_millis() as u64))
+                    }
+                    Err(stop) => meta.insert("retry_stop".into(), json!(stop)),
+                };
+            }
+            if !meta.is_empty() {
+                details["meta"] = serde_json::Value::Object(meta);
+            }
+            emit(audit, scope, "MODEL_CALL_FAILED", details)?;
+            if let Some(Ok(backoff)) = next {
+                std::thread::sleep(backoff);
+                attempt += 1;
+                continue;
+            }
+            let status = match &err {
+                CoreError::Cancelled(_) => "cancelled",
+                CoreError::Adapter(env) if env.error.category == "SAFETY_REFUSAL" => "refused",
+                _ => "error",
+            };
+            self.calls
+                .push(call.finish(scope, partial_hash, duration_ms, json!({}), status));
+            return Err(err);
+        }
+    }
+}
+
+/// Incremental SHA-256 over streamed text. Finishing it yields the same hash as hashing the
+/// concatenated output in one go.
+#[derive(Debug, Clone, Default)]
+pub struct StreamingHash {
+    hasher: Sha256,
+    bytes: u64,
+    chunks: u64,
+}
+
+impl StreamingHash {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, chunk: &str) {
+        self.hasher.update(chunk.as_bytes());
+        self.bytes += chunk.len() as u64;
+        self.chunks += 1;
+    }
+
+    /// Hash of everything received so far; the stream can keep going afterwards.
+    pub fn hex(&self) -> String {
+        hex::encode(self.hasher.clone().finalize())
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    pub fn chunks(&self) -> u64 {
+        self.chunks
+    }
+}
+
+struct CallStart {
+    task_type: TaskType,
+    call_id: String,
+    model_id: String,
+    request_hash: String,
+    timeout: Option<Duration>, // overrides the client's call timeout
+}
+
+impl CallStart {
+    fn new<R: Serialize>(
+        task_type: TaskType,
+        call_id: &str,
+        model_id: &str,
+        req: &R,
+    ) -> CoreResult<Self> {
+        if call_id.trim().is_empty() {
+            return Err(CoreError::InvalidInput(
+                "model call_id must not be empty".to_string(),
+            ));
+        }
+        // Same bytes the HTTP client sends; canonical JSON is not usable here because
+        // requests carry floats (temperature, top_p).
+        let request_hash = sha256_hex(&serde_json::to_vec(req)?);
+        Ok(Self {
+            task_type,
+            call_id: call_id.to_string(),
+            model_id: model_id.to_string(),
+            request_hash,
+            timeout: None,
+        })
+    }
+
+    fn finish(
+        self,
+        scope: &ModelCallScope,
+        output_hash: String,
+        duration_ms: u64,
+        usage: serde_json::Value,
+        status: &str,
+    ) -> ModelCallMeta {
+        ModelCallMeta {
+            call_id: self.call_id,
+            model_id: self.model_id,
+            adapter_version: scope.adapter_version.clone(),
+            input_hash: self.request_hash,
+            output_hash,
+            duration_ms,
+            usage,
+            status: status.to_string(),
+        }
+    }
+}
+
+fn check_generate_status(status: &str) -> CoreResult<()> {
+    match status {
+        "ok" => Ok(()),
+        "refused" => Err(adapter_failure(
+            "SAFETY_REFUSAL",
+            "SAFETY_REFUSAL",
+            "generate refused by model safety policy",
+        )),
+        other => Err(adapter_failure(
+            "RUNTIME_ERROR",
+            "MODEL_ERROR",
+            &format!("generate returned status {}", other),
+        )),
+    }
+}
+
+// Text output is hashed as raw UTF-8 so a streamed response hashes to the same value.
+fn generate_output_hash(resp: &GenerateResponse) -> CoreResult<String> {
+    match (&resp.output_text, &resp.output_json) {
+        (Some(text), _) => Ok(sha256_hex(text.as_bytes())),
+        (None, Some(value)) => Ok(sha256_hex(&serde_json::to_vec(value)?)),
+        (None, None) => Err(adapter_failure(
+            "RUNTIME_ERROR",
+            "MODEL_ERROR",
+            "generate returned neither output_text nor output_json",
+        )),
+    }
+}
+
+// Maps core/adapter errors onto the MODEL_CALL_FAILED error_category vocabulary.
+fn failure_category(err: &CoreError) -> (&'static str, String) {
+    match err {
+        CoreError::Adapter(env) => {
+            let category = match (env.error.code.as_str(), env.error.category.as_str()) {
+                ("ADAPTER_UNAVAILABLE", _) | ("CIRCUIT_OPEN", _) => "ADAPTER_UNAVAILABLE",
+                (_, "TIMEOUT") => "TIMEOUT",
+                (_, "INVALID_INPUT") | (_, "NOT_SUPPORTED") => "INVALID_REQUEST",
+                _ => "MODEL_ERROR",
+            };
+            (category, env.error.code.clone())
+        }
+        CoreError::PolicyBlocked(_) | CoreError::PolicyViolationError(_) => {
+            ("POLICY_BLOCKED", "POLICY_BLOCKED".to_string())
+        }
+        CoreError::Cancelled(_) => ("MODEL_ERROR", "CANCELLED".to_string()),
+        CoreError::InvalidInput(_) | CoreError::InputSchemaError(_) => {
+            ("INVALID_REQUEST", "INVALID_INPUT".to_string())
+        }
+        _ => ("MODEL_ERROR", "RUNTIME_ERROR".to_string()),
+    }
+}
+
+// Error text can carry adapter response bodies and prompt echoes, so none of it is audited;
+// the recorded message is built from the category and code alone.
+fn redacted_message(category: &str, code: &str) -> String {
+    format!("{} ({}); adapter message withheld", category, code)
+}
+
+fn adapter_failure(category: &str, code: &str, message: &str) -> CoreError {
+    CoreError::Adapter(Box::new(AdapterErrorEnvelope {
+        error: AdapterError {
+            code: code.to_string(),
+            message: message.to_string(),
+            retryable: false,
+            category: category.to_string(),
+            details: json!({}),
+        },
+    }))
+}
+
+fn emit(
+    audit: &mut AuditLog,
+    scope: &ModelCallScope,
+    event_type: &str,
+    details: serde_json::Value,
+) -> CoreResult<()> {
+    audit.append(AuditEvent {
+        ts_utc: String::new(),
+        event_type: event_type.to_string(),
+        run_id: scope.run_id.clone(),
+        vault_id: scope.vault_id.clone(),
+        actor: Actor::System,
+        details,
+        prev_event_hash: String::new(),
+        event_hash: String::new(),
+    })?;
+    Ok(())
+}
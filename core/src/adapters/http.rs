@@ -0,0 +1,302 @@
+use crate::adapters::interface::{
+    classify_adapter_error, classify_adapter_status, enforce_loopback_endpoint,
+    parse_adapter_error, AdapterCapabilitiesResponse, AdapterClient, AdapterHealthResponse,
+    BufferedStream, EmbedRequest, EmbedResponse, GenerateChunk, GenerateRequest, GenerateResponse,
+    GenerateStream, ModelDigestRequest, ModelDigestResponse, ResolveModelRequest,
+    ResolveModelResponse, StreamOptions, TranscribeRequest, TranscribeResponse,
+};
+use crate::error::{CoreError, CoreResult};
+use crate::policy::egress::{http_exchange, http_stream, HttpResponse, HttpStream};
+use crate::run::cancel::CancellationToken;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::sync::OnceLock;
+use std::time::Duration;
+use url::Url;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Annex B client for an adapter process listening on loopback HTTP.
+#[derive(Debug, Clone)]
+pub struct HttpAdapterClient {
+    endpoint: String,
+    timeout: Duration,
+    streaming: OnceLock<bool>, // `streaming` feature, read from capabilities on first stream
+}
+
+impl HttpAdapterClient {
+    pub fn new(endpoint: &str) -> CoreResult<Self> {
+        enforce_loopback_endpoint(endpoint)?;
+        Ok(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            streaming: OnceLock::new(),
+        })
+    }
+
+    /// Per-request bound on connect, send and receive.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn get_json<T: DeserializeOwned>(&self, path: &str) -> CoreResult<T> {
+        let resp = self.exchange("GET", path, &[])?;
+        decode(path, resp)
+    }
+
+    fn post_json<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> CoreResult<T> {
+        let resp = self.exchange("POST", path, &serde_json::to_vec(body)?)?;
+        decode(path, resp)
+    }
+
+    fn exchange(&self, method: &str, path: &str, body: &[u8]) -> CoreResult<HttpResponse> {
+        let url = self.url(path)?;
+        http_exchange(method, &url, JSON_HEADERS, body, self.timeout)
+            .map_err(|e| transport_error(path, self.timeout, e))
+    }
+
+    fn supports_streaming(&self) -> CoreResult<bool> {
+        if let Some(streaming) = self.streaming.get() {
+            return Ok(*streaming);
+        }
+        let streaming = self
+            .capabilities()?
+            .features
+            .iter()
+            .any(|f| f == "streaming");
+        Ok(*self.streaming.get_or_init(|| streaming))
+    }
+
+    fn url(&self, path: &str) -> CoreResult<Url> {
+        let url = format!("{}{}", self.endpoint, path);
+        // Checked per request so a redirected or rewritten endpoint can never leave loopback.
+        enforce_loopback_endpoint(&url)?;
+        Url::parse(&url)
+            .map_err(|_| CoreError::InvalidInput(format!("invalid adapter URL {}", url)))
+    }
+}
+
+const JSON_HEADERS: &[(&str, &str)] = &[("Content-Type", "application/json")];
+
+const GENERATE_STREAM_PATH: &str = "/v1/llm/generate/stream";
+
+/// NDJSON body of `POST /v1/llm/generate/stream`: `{"delta": "..."}` lines followed by one
+/// `{"done": true, "status": ..., "usage": ..., "timing_ms": ...}` line.
+struct NdjsonGenerateStream {
+    reader: BufReader<HttpStream>,
+    cancel: CancellationToken,
+    timeout: Duration,
+    finished: bool,
+}
+
+#[derive(Deserialize)]
+struct StreamLine {
+    #[serde(default)]
+    delta: Option<String>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    usage: serde_json::Value,
+    #[serde(default)]
+    timing_ms: u64,
+}
+
+impl GenerateStream for NdjsonGenerateStream {
+    fn next_chunk(&mut self) -> CoreResult<Option<GenerateChunk>> {
+        loop {
+            if self.finished {
+                return Ok(None);
+            }
+            let mut line = String::new();
+            let n = match self.reader.read_line(&mut line) {
+                Ok(n) => n,
+                Err(_) if self.cancel.is_cancelled() => {
+                    return Err(CoreError::Cancelled("generate stream".to_string()))
+                }
+                Err(e) if e.kind() == ErrorKind::TimedOut => {
+                    let mut env = classify_adapter_error(&format!(
+                        "stream timeout after {} ms calling {}",
+                        self.timeout.as_millis(),
+                        GENERATE_STREAM_PATH
+                    ));
+                    env.error.code = "STREAM_TIMEOUT".to_string();
+                    return Err(CoreError::Adapter(Box::new(env)));
+                }
+                Err(e) => {
+                    return Err(transport_error(
+                        GENERATE_STREAM_PATH,
+                        self.timeout,
+                        e.into(),
+                    ))
+                }
+            };
+            if n == 0 {
+                return Err(adapter_error(&format!(
+                    "{} ended before the done line",
+                    GENERATE_STREAM_PATH
+                )));
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(env) = parse_adapter_error(line.as_bytes()) {
+                self.finished = true;
+                return Err(CoreError::Adapter(Box::new(env)));
+            }
+            let parsed: StreamLine = serde_json::from_str(&line).map_err(|e| {
+                adapter_error(&format!(
+                    "invalid stream line from {}: {}",
+                    GENERATE_STREAM_PATH, e
+                ))
+            })?;
+            if parsed.done {
+                self.finished = true;
+                return Ok(Some(GenerateChunk::Done {
+                    status: parsed.status.unwrap_or_else(|| "ok".to_string()),
+                    usage: parsed.usage,
+                    timing_ms: parsed.timing_ms,
+                }));
+            }
+            if let Some(delta) = parsed.delta {
+                return Ok(Some(GenerateChunk::Delta(delta)));
+            }
+        }
+    }
+}
+
+impl AdapterClient for HttpAdapterClient {
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn health(&self) -> CoreResult<AdapterHealthResponse> {
+        self.get_json("/v1/health")
+    }
+
+    fn capabilities(&self) -> CoreResult<AdapterCapabilitiesResponse> {
+        self.get_json("/v1/capabilities")
+    }
+
+    fn resolve_model(&self, req: ResolveModelRequest) -> CoreResult<ResolveModelResponse> {
+        self.post_json("/v1/models/resolve", &req)
+    }
+
+    fn model_digest(&self, req: &ModelDigestRequest) -> CoreResult<ModelDigestResponse> {
+        self.post_json("/v1/models/digest", req)
+    }
+
+    fn call_timeout(&self) -> Option<Duration> {
+        Some(self.timeout)
+    }
+
+    fn generate(&self, req: &GenerateRequest) -> CoreResult<GenerateResponse> {
+        self.post_json("/v1/llm/generate", req)
+    }
+
+    fn generate_stream(
+        &self,
+        req: &GenerateRequest,
+        opts: &StreamOptions,
+    ) -> CoreResult<Box<dyn GenerateStream + '_>> {
+        if !self.supports_streaming()? {
+            return Ok(Box::new(BufferedStream::new(self.generate(req)?)));
+        }
+        let url = self.url(GENERATE_STREAM_PATH)?;
+        let cancel = opts.cancel.clone();
+        let mut stream = http_stream(
+            "POST",
+            &url,
+            JSON_HEADERS,
+            &serde_json::to_vec(req)?,
+            self.timeout,
+            opts.timeout,
+            Box::new(move || cancel.is_cancelled()),
+        )
+        .map_err(|e| transport_error(GENERATE_STREAM_PATH, self.timeout, e))?;
+        if !stream.is_success() {
+            let mut body = Vec::new();
+            stream.read_to_end(&mut body)?;
+            return Err(status_error(GENERATE_STREAM_PATH, stream.status, &body));
+        }
+        Ok(Box::new(NdjsonGenerateStream {
+            reader: BufReader::new(stream),
+            cancel: opts.cancel.clone(),
+            timeout: opts.timeout,
+            finished: false,
+        }))
+    }
+
+    fn embed(&self, req: &EmbedRequest) -> CoreResult<EmbedResponse> {
+        self.post_json("/v1/emb/embed", req)
+    }
+
+    fn transcribe(&self, req: &TranscribeRequest) -> CoreResult<TranscribeResponse> {
+        self.post_json("/v1/stt/transcribe", req)
+    }
+}
+
+fn decode<T: DeserializeOwned>(path: &str, resp: HttpResponse) -> CoreResult<T> {
+    if !resp.is_success() {
+        return Err(status_error(path, resp.status, &resp.body));
+    }
+    serde_json::from_slice(&resp.body).map_err(|e| match parse_adapter_error(&resp.body) {
+        // Some adapters report errors with a 200 status.
+        Some(env) => CoreError::Adapter(Box::new(env)),
+        None => adapter_error(&format!("invalid response from {}: {}", path, e)),
+    })
+}
+
+// Prefers the adapter's own error envelope; the message heuristic covers adapters that
+// answer with something else (plain text, framework error pages, `{"detail": ..}`).
+fn status_error(path: &str, status: u16, body: &[u8]) -> CoreError {
+    if let Some(env) = parse_adapter_error(body) {
+        return CoreError::Adapter(Box::new(env));
+    }
+    CoreError::Adapter(Box::new(classify_adapter_status(
+        status,
+        &format!(
+            "{} returned HTTP {}: {}",
+            path,
+            status,
+            String::from_utf8_lossy(body)
+        ),
+    )))
+}
+
+fn transport_error(path: &str, timeout: Duration, e: CoreError) -> CoreError {
+    match e {
+        CoreError::Io(io) if matches!(io.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {
+            adapter_error(&format!(
+                "timeout after {} ms calling {}",
+                timeout.as_millis(),
+                path
+            ))
+        }
+        CoreError::Io(io)
+            if matches!(
+                io.kind(),
+                ErrorKind::ConnectionRefused
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+            ) =>
+        {
+            let mut env = classify_adapter_error(&format!("{} failed: {}", path, io));
+            env.error.code = "ADAPTER_UNAVAILABLE".to_string();
+            env.error.retryable = true;
+            CoreError::Adapter(Box::new(env))
+        }
+        other => adapter_error(&format!("{} failed: {}", path, other)),
+    }
+}
+
+fn adapter_error(message: &str) -> CoreError {
+    CoreError::Adapter(Box::new(classify_adapter_error(message)))
+}
@@ -1,6 +1,10 @@
 use crate::adapters::loopback::is_loopback_endpoint;
+use crate::adapters::retry::RetryControl;
 use crate::error::{CoreError, CoreResult};
+use crate::run::cancel::CancellationToken;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdapterErrorEnvelope {
@@ -57,6 +61,22 @@ pub struct ResolveModelResponse {
     pub rationale: String,
 }
 
+/// Challenge for `POST /v1/models/digest`; the nonce keeps a recorded answer from being replayed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDigestRequest {
+    pub model_id: String,
+    pub nonce: String,
+}
+
+/// Digest of the weights the adapter has loaded, signed with the key it was provisioned with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDigestResponse {
+    pub model_id: String,
+    pub model_sha256: String,
+    pub nonce: String,
+    pub signature: String, // hex HMAC-SHA256, see verification::sign_model_digest
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelCallMeta {
     pub call_id: String,
@@ -69,11 +89,198 @@ pub struct ModelCallMeta {
     pub status: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String, // system|user|assistant
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SafetyProfile {
+    pub require_citations: bool,
+    pub forbid_untrusted_tool_use: bool,
+    pub redaction_required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerateRequest {
+    pub call_id: String,
+    pub model_id: String,
+    pub messages: Vec<ChatMessage>,
+    pub response_mode: String, // TEXT|JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_schema: Option<serde_json::Value>, // required if JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub context_chunks: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub safety_profile: SafetyProfile,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerateResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_json: Option<serde_json::Value>,
+    #[serde(default)]
+    pub usage: serde_json::Value, // tokens_in, tokens_out
+    #[serde(default)]
+    pub timing_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_hash: Option<String>, // adapter-reported; core hashes the output itself
+    pub status: String, // ok|refused|error
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbedRequest {
+    pub call_id: String,
+    pub model_id: String,
+    pub texts: Vec<String>,
+    pub normalize: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbedResponse {
+    pub vectors: Vec<Vec<f32>>,
+    pub dim: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscribeRequest {
+    pub call_id: String,
+    pub model_id: String,
+    pub audio_uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    pub timestamps: bool,
+    pub diarization: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscribeResponse {
+    pub segments: Vec<TranscriptSegment>,
+    pub full_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_hash: Option<String>,
+}
+
+/// One piece of a streamed generate response. `Done` is always the last chunk.
+#[derive(Debug, Clone)]
+pub enum GenerateChunk {
+    Delta(String),
+    Done {
+        status: String, // ok|refused|error
+        usage: serde_json::Value,
+        timing_ms: u64,
+    },
+}
+
+pub trait GenerateStream {
+    /// Blocks for the next chunk; `None` once the stream is exhausted.
+    fn next_chunk(&mut self) -> CoreResult<Option<GenerateChunk>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamOptions {
+    pub timeout: Duration, // whole stream, first byte to `Done`
+    pub cancel: CancellationToken,
+}
+
+/// Replays a complete response as a stream, for adapters without the `streaming` feature.
+pub struct BufferedStream {
+    chunks: VecDeque<GenerateChunk>,
+}
+
+impl BufferedStream {
+    pub fn new(resp: GenerateResponse) -> Self {
+        let mut chunks = VecDeque::new();
+        if let Some(text) = resp.output_text {
+            chunks.push_back(GenerateChunk::Delta(text));
+        }
+        chunks.push_back(GenerateChunk::Done {
+            status: resp.status,
+            usage: resp.usage,
+            timing_ms: resp.timing_ms,
+        });
+        Self { chunks }
+    }
+}
+
+impl GenerateStream for BufferedStream {
+    fn next_chunk(&mut self) -> CoreResult<Option<GenerateChunk>> {
+        Ok(self.chunks.pop_front())
+    }
+}
+
 pub trait AdapterClient {
     fn endpoint(&self) -> &str;
     fn health(&self) -> CoreResult<AdapterHealthResponse>;
     fn capabilities(&self) -> CoreResult<AdapterCapabilitiesResponse>;
     fn resolve_model(&self, req: ResolveModelRequest) -> CoreResult<ResolveModelResponse>;
+
+    /// Signed digest of a loaded model, used to verify CRYPTO_PINNED snapshots.
+    fn model_digest(&self, _req: &ModelDigestRequest) -> CoreResult<ModelDigestResponse> {
+        Err(not_supported("model digest"))
+    }
+
+    /// Bound applied to each call, reported as `timeout_ms` on MODEL_CALL_STARTED.
+    fn call_timeout(&self) -> Option<std::time::Duration> {
+        None
+    }
+
+    /// Retry policy and budget `ModelCallLog` applies to inference calls; none by default.
+    fn retry_control(&self) -> Option<&RetryControl> {
+        None
+    }
+
+    // Inference calls (Annex B.3-B.5). Adapters only implement the ones matching their type.
+    fn generate(&self, _req: &GenerateRequest) -> CoreResult<GenerateResponse> {
+        Err(not_supported("generate"))
+    }
+
+    fn generate_stream(
+        &self,
+        req: &GenerateRequest,
+        _opts: &StreamOptions,
+    ) -> CoreResult<Box<dyn GenerateStream + '_>> {
+        Ok(Box::new(BufferedStream::new(self.generate(req)?)))
+    }
+
+    fn embed(&self, _req: &EmbedRequest) -> CoreResult<EmbedResponse> {
+        Err(not_supported("embed"))
+    }
+
+    fn transcribe(&self, _req: &TranscribeRequest) -> CoreResult<TranscribeResponse> {
+        Err(not_supported("transcribe"))
+    }
+}
+
+fn not_supported(call: &str) -> CoreError {
+    CoreError::Adapter(Box::new(classify_adapter_error(&format!(
+        "{} unsupported by this adapter",
+        call
+    ))))
 }
 
 pub fn enforce_loopback_endpoint(endpoint: &str) -> CoreResult<()> {
@@ -85,6 +292,49 @@ pub fn enforce_loopback_endpoint(endpoint: &str) -> CoreResult<()> {
     Ok(())
 }
 
+/// `AdapterError.category` values defined by Annex B.2.
+pub const ADAPTER_ERROR_CATEGORIES: [&str; 7] = [
+    "INVALID_INPUT",
+    "MODEL_NOT_FOUND",
+    "OUT_OF_MEMORY",
+    "TIMEOUT",
+    "RUNTIME_ERROR",
+    "SAFETY_REFUSAL",
+    "NOT_SUPPORTED",
+];
+
+/// Reads an Annex B error envelope from an adapter response body, keeping the adapter's code,
+/// retryable flag and details as sent. Returns None when the body is not a conforming envelope
+/// (missing fields, empty code, unknown category).
+pub fn parse_adapter_error(body: &[u8]) -> Option<AdapterErrorEnvelope> {
+    let env: AdapterErrorEnvelope = serde_json::from_slice(body).ok()?;
+    let known = ADAPTER_ERROR_CATEGORIES.contains(&env.error.category.as_str());
+    (known && !env.error.code.is_empty()).then_some(env)
+}
+
+/// Envelope for a non-2xx response that carried no conforming envelope. Statuses with a clear
+/// Annex B category decide it; anything else falls back to `classify_adapter_error`.
+pub fn classify_adapter_status(status: u16, err: &str) -> AdapterErrorEnvelope {
+    let (category, retryable) = match status {
+        400 | 413 | 422 => ("INVALID_INPUT", false),
+        408 | 504 => ("TIMEOUT", true),
+        501 => ("NOT_SUPPORTED", false),
+        507 => ("OUT_OF_MEMORY", false),
+        _ => return classify_adapter_error(err),
+    };
+    AdapterErrorEnvelope {
+        error: AdapterError {
+            code: category.to_string(),
+            message: err.to_string(),
+            retryable,
+            category: category.to_string(),
+            details: serde_json::json!({ "http_status": status }),
+        },
+    }
+}
+
+/// Guesses an envelope from a message. Only used when the adapter sent no parseable envelope,
+/// or for failures on our side of the connection.
 pub fn classify_adapter_error(err: &str) -> AdapterErrorEnvelope {
     let (category, code, retryable) = if err.contains("timeout") {
         ("TIMEOUT", "ADAPTER_TIMEOUT", true)
@@ -1,4 +1,10 @@
+pub mod calls;
+pub mod http;
 pub mod interface;
 pub mod loopback;
 pub mod pinning;
+pub mod retry;
+pub mod routing;
 pub mod runtime;
+pub mod supervisor;
+pub mod verification;
@@ -1,3 +1,4 @@
+use crate::policy::types::PolicyMode;
 use serde::{Deserialize, Serialize};
 
 #[allow(non_camel_case_types)]
@@ -8,6 +9,29 @@ pub enum PinningLevel {
     NAME_ONLY,
 }
 
+impl PinningLevel {
+    /// Strength order: NAME_ONLY < VERSION_PINNED < CRYPTO_PINNED.
+    pub fn rank(self) -> u8 {
+        match self {
+            PinningLevel::NAME_ONLY => 0,
+            PinningLevel::VERSION_PINNED => 1,
+            PinningLevel::CRYPTO_PINNED => 2,
+        }
+    }
+
+    /// Weakest level the policy accepts (lock addendum §7).
+    pub fn minimum_for(policy: PolicyMode) -> PinningLevel {
+        match policy {
+            PolicyMode::STRICT | PolicyMode::BALANCED => PinningLevel::VERSION_PINNED,
+            PolicyMode::DRAFT_ONLY => PinningLevel::NAME_ONLY,
+        }
+    }
+
+    pub fn satisfies(self, policy: PolicyMode) -> bool {
+        self.rank() >= PinningLevel::minimum_for(policy).rank()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelSnapshot {
     pub adapter_id: String,
@@ -19,6 +43,8 @@ pub struct ModelSnapshot {
     pub pinning_level: PinningLevel,
 }
 
+/// Level a model claims from what it reports. A `model_sha256` is only a claim: `route_model`
+/// keeps CRYPTO_PINNED after verifying it and falls back to this function with `None` otherwise.
 pub fn classify_pinning_level(
     model_sha256: Option<&str>,
     adapter_id: &str,
@@ -0,0 +1,290 @@
+use crate::adapters::interface::{
+    classify_adapter_error, AdapterCapabilitiesResponse, AdapterClient, AdapterHealthResponse,
+    EmbedRequest, EmbedResponse, GenerateRequest, GenerateResponse, GenerateStream,
+    ModelDigestRequest, ModelDigestResponse, ResolveModelRequest, ResolveModelResponse,
+    StreamOptions, TranscribeRequest, TranscribeResponse,
+};
+use crate::error::{CoreError, CoreResult};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Exponential backoff without jitter, so retried runs replay with the same timing.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32, // including the first
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before attempt `attempt + 1`, where `attempt` is the 1-based attempt that failed.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1)
+            .saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Retries shared by every call of one run. Clones draw from the same pool.
+#[derive(Debug, Clone)]
+pub struct RetryBudget(Arc<AtomicU32>);
+
+impl RetryBudget {
+    pub fn new(max_retries: u32) -> Self {
+        Self(Arc::new(AtomicU32::new(max_retries)))
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    fn try_consume(&self) -> bool {
+        self.0
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RetryStop {
+    NOT_RETRYABLE,
+    MAX_ATTEMPTS,
+    BUDGET_EXHAUSTED,
+    PARTIAL_OUTPUT,
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryControl {
+    pub policy: RetryPolicy,
+    pub budget: RetryBudget,
+}
+
+impl RetryControl {
+    /// Backoff before the next attempt, or why the call stops after `attempt` failed with `err`.
+    /// Only errors the adapter marked `retryable` are retried; each retry consumes the budget.
+    pub fn decide(&self, attempt: u32, err: &CoreError) -> Result<Duration, RetryStop> {
+        let retryable = matches!(err, CoreError::Adapter(env) if env.error.retryable);
+        if !retryable {
+            return Err(RetryStop::NOT_RETRYABLE);
+        }
+        if attempt >= self.policy.max_attempts {
+            return Err(RetryStop::MAX_ATTEMPTS);
+        }
+        if !self.budget.try_consume() {
+            return Err(RetryStop::BUDGET_EXHAUSTED);
+        }
+        Ok(self.policy.backoff(attempt))
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CircuitState {
+    CLOSED,
+    OPEN,
+    HALF_OPEN,
+}
+
+struct BreakerState {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+/// Opens after `failure_threshold` consecutive adapter faults and fails calls fast until
+/// `cool_down` has passed; then one trial call decides whether it closes again.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cool_down: Duration,
+    inner: Arc<Mutex<BreakerState>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cool_down: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cool_down,
+            inner: Arc::new(Mutex::new(BreakerState {
+                state: CircuitState::CLOSED,
+                consecutive_failures: 0,
+                opened_at: None,
+            })),
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.lock().state
+    }
+
+    fn allow(&self) -> bool {
+        let mut st = self.lock();
+        match st.state {
+            CircuitState::CLOSED => true,
+            CircuitState::HALF_OPEN => false, // trial call in flight
+            CircuitState::OPEN => {
+                if st.opened_at.is_some_and(|t| t.elapsed() >= self.cool_down) {
+                    st.state = CircuitState::HALF_OPEN;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn record<T>(&self, result: &CoreResult<T>) {
+        let mut st = self.lock();
+        match result {
+            Err(e) if is_adapter_fault(e) => {
+                st.consecutive_failures += 1;
+                if st.state == CircuitState::HALF_OPEN
+                    || st.consecutive_failures >= self.failure_threshold
+                {
+                    st.state = CircuitState::OPEN;
+                    st.opened_at = Some(Instant::now());
+                }
+            }
+            // Errors caused by the request itself say nothing about adapter health.
+            Err(_) if st.state != CircuitState::HALF_OPEN => {}
+            _ => {
+                st.state = CircuitState::CLOSED;
+                st.consecutive_failures = 0;
+                st.opened_at = None;
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BreakerState> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Failures that point at the adapter rather than at the request.
+fn is_adapter_fault(err: &CoreError) -> bool {
+    match err {
+        CoreError::Adapter(env) => !matches!(
+            env.error.category.as_str(),
+            "INVALID_INPUT" | "MODEL_NOT_FOUND" | "NOT_SUPPORTED" | "SAFETY_REFUSAL"
+        ),
+        CoreError::Io(_) => true,
+        _ => false,
+    }
+}
+
+/// Wraps a client with a retry policy, a run-wide retry budget and a circuit breaker. Retries
+/// happen in `ModelCallLog`, which audits every failed attempt; the wrapper itself only guards
+/// calls with the breaker. While the circuit is open `health()` fails too, so the adapter
+/// drops out of the `AdapterRuntime` registry on its next refresh.
+pub struct RetryingClient<C: AdapterClient> {
+    inner: C,
+    control: RetryControl,
+    breaker: CircuitBreaker,
+}
+
+impl<C: AdapterClient> RetryingClient<C> {
+    pub fn new(
+        inner: C,
+        policy: RetryPolicy,
+        budget: RetryBudget,
+        breaker: CircuitBreaker,
+    ) -> Self {
+        Self {
+            inner,
+            control: RetryControl { policy, budget },
+            breaker,
+        }
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    pub fn breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
+    fn guarded<T>(&self, call: impl FnOnce() -> CoreResult<T>) -> CoreResult<T> {
+        if !self.breaker.allow() {
+            let mut env = classify_adapter_error(&format!(
+                "circuit open for adapter {}",
+                self.inner.endpoint()
+            ));
+            env.error.code = "CIRCUIT_OPEN".to_string();
+            return Err(CoreError::Adapter(Box::new(env)));
+        }
+        let result = call();
+        self.breaker.record(&result);
+        result
+    }
+}
+
+impl<C: AdapterClient> AdapterClient for RetryingClient<C> {
+    fn endpoint(&self) -> &str {
+        self.inner.endpoint()
+    }
+
+    fn health(&self) -> CoreResult<AdapterHealthResponse> {
+        self.guarded(|| self.inner.health())
+    }
+
+    fn capabilities(&self) -> CoreResult<AdapterCapabilitiesResponse> {
+        self.guarded(|| self.inner.capabilities())
+    }
+
+    fn resolve_model(&self, req: ResolveModelRequest) -> CoreResult<ResolveModelResponse> {
+        self.guarded(|| self.inner.resolve_model(req))
+    }
+
+    fn model_digest(&self, req: &ModelDigestRequest) -> CoreResult<ModelDigestResponse> {
+        self.guarded(|| self.inner.model_digest(req))
+    }
+
+    fn call_timeout(&self) -> Option<Duration> {
+        self.inner.call_timeout()
+    }
+
+    fn retry_control(&self) -> Option<&RetryControl> {
+        Some(&self.control)
+    }
+
+    fn generate(&self, req: &GenerateRequest) -> CoreResult<GenerateResponse> {
+        self.guarded(|| self.inner.generate(req))
+    }
+
+    // Only opening the stream is guarded; failures mid-stream are not seen by the breaker.
+    fn generate_stream(
+        &self,
+        req: &GenerateRequest,
+        opts: &StreamOptions,
+    ) -> CoreResult<Box<dyn GenerateStream + '_>> {
+        self.guarded(|| self.inner.generate_stream(req, opts))
+    }
+
+    fn embed(&self, req: &EmbedRequest) -> CoreResult<EmbedResponse> {
+        self.guarded(|| self.inner.embed(req))
+    }
+
+    fn transcribe(&self, req: &TranscribeRequest) -> CoreResult<TranscribeResponse> {
+        self.guarded(|| self.inner.transcribe(req))
+    }
+}
@@ -0,0 +1,417 @@
+use crate::adapters::interface::{AdapterClient, AdapterModel, ResolveModelRequest};
+use crate::adapters::pinning::{classify_pinning_level, ModelSnapshot, PinningLevel};
+use crate::adapters::runtime::{AdapterRuntime, RegisteredAdapter};
+use crate::adapters::verification::{
+    verify_model_pinning, DigestSources, PinningVerification, VerificationOutcome,
+};
+use crate::audit::event::{Actor, AuditEvent};
+use crate::audit::log::AuditLog;
+use crate::error::{CoreError, CoreResult};
+use crate::policy::model_catalog::{CatalogViolationReason, ModelCatalog, ModelUse};
+use crate::policy::types::PolicyMode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Task types of the audit taxonomy, each served by one adapter type.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TaskType {
+    LLM,
+    EMBED,
+    STT,
+    VLM,
+}
+
+impl TaskType {
+    /// `adapter_type` reported by `GET /v1/capabilities` for adapters serving this task.
+    pub fn adapter_type(self) -> &'static str {
+        match self {
+            TaskType::LLM => "LLM",
+            TaskType::EMBED => "EMB",
+            TaskType::STT => "STT",
+            TaskType::VLM => "VLM",
+        }
+    }
+}
+
+/// Interpreted form of `ResolveModelRequest.constraints`. Unknown keys are rejected so a
+/// misspelled constraint cannot silently widen the selection.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ModelConstraints {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_context_window: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_quantizations: Vec<String>, // empty = any
+    #[serde(default)]
+    pub require_model_sha256: bool,
+}
+
+impl ModelConstraints {
+    pub fn from_value(v: &serde_json::Value) -> CoreResult<Self> {
+        if v.is_null() {
+            return Ok(Self::default());
+        }
+        serde_json::from_value(v.clone())
+            .map_err(|e| CoreError::InvalidInput(format!("invalid model constraints: {}", e)))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RoutingRequest {
+    pub task_type: TaskType,
+    pub policy_mode: PolicyMode,
+    pub preferred_model: Option<String>,
+    pub constraints: ModelConstraints,
+    pub catalog: Option<CatalogScope>,
+}
+
+/// Model catalog of the active policy pack, applied to the product pack `pack_id`.
+#[derive(Debug, Clone)]
+pub struct CatalogScope {
+    pub catalog: ModelCatalog,
+    pub pack_id: String,
+}
+
+impl RoutingRequest {
+    pub fn from_resolve_request(
+        task_type: TaskType,
+        policy_mode: PolicyMode,
+        req: &ResolveModelRequest,
+    ) -> CoreResult<Self> {
+        Ok(Self {
+            task_type,
+            policy_mode,
+            preferred_model: Some(req.preferred_model.clone()).filter(|m| !m.is_empty()),
+            constraints: ModelConstraints::from_value(&req.constraints)?,
+            catalog: None,
+        })
+    }
+
+    /// Restricts candidates to the models `catalog` permits for `pack_id`.
+    pub fn with_catalog(mut self, catalog: ModelCatalog, pack_id: &str) -> Self {
+        self.catalog = Some(CatalogScope {
+            catalog,
+            pack_id: pack_id.to_string(),
+        });
+        self
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RejectReason {
+    CONTEXT_WINDOW_TOO_SMALL,
+    QUANTIZATION_NOT_ALLOWED,
+    MODEL_SHA256_MISSING,
+    PINNING_BELOW_POLICY,
+    MODEL_DENIED_BY_CATALOG,
+    MODEL_NOT_IN_CATALOG,
+    TASK_TYPE_NOT_IN_CATALOG,
+    PINNING_BELOW_CATALOG,
+}
+
+impl RejectReason {
+    /// Rejections imposed by policy rather than by the caller's constraints.
+    pub fn is_policy(self) -> bool {
+        !matches!(
+            self,
+            RejectReason::CONTEXT_WINDOW_TOO_SMALL
+                | RejectReason::QUANTIZATION_NOT_ALLOWED
+                | RejectReason::MODEL_SHA256_MISSING
+        )
+    }
+}
+
+impl From<CatalogViolationReason> for RejectReason {
+    fn from(r: CatalogViolationReason) -> Self {
+        match r {
+            CatalogViolationReason::MODEL_DENIED => RejectReason::MODEL_DENIED_BY_CATALOG,
+            CatalogViolationReason::MODEL_NOT_IN_CATALOG => RejectReason::MODEL_NOT_IN_CATALOG,
+            CatalogViolationReason::TASK_TYPE_NOT_IN_CATALOG => {
+                RejectReason::TASK_TYPE_NOT_IN_CATALOG
+            }
+            CatalogViolationReason::PINNING_BELOW_CATALOG => RejectReason::PINNING_BELOW_CATALOG,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CandidateEvaluation {
+    pub adapter_id: String,
+    pub model_id: String,
+    pub pinning_level: PinningLevel,
+    pub rejected: Vec<RejectReason>, // empty = eligible
+}
+
+/// Machine-readable account of a routing decision, recorded under `meta.rationale`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SelectionRationale {
+    pub adapter_type: String,
+    pub policy_mode: PolicyMode,
+    pub min_pinning_level: PinningLevel,
+    pub preferred_model: Option<String>,
+    pub preferred_model_selected: bool,
+    pub constraints: ModelConstraints,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_catalog_hash_sha256: Option<String>,
+    pub candidates: Vec<CandidateEvaluation>, // sorted by (adapter_id, model_id)
+    pub ranking: Vec<String>,                 // tie-break order among eligible candidates
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelSelection {
+    pub task_type: TaskType,
+    pub adapter: RegisteredAdapter,
+    pub model: AdapterModel,
+    pub pinning_level: PinningLevel,
+    pub rationale: SelectionRationale,
+}
+
+impl ModelSelection {
+    /// Snapshot for `inputs_snapshot/model_snapshot.json`.
+    pub fn snapshot(&self) -> ModelSnapshot {
+        ModelSnapshot {
+            adapter_id: self.adapter.adapter_id.clone(),
+            adapter_version: self.adapter.adapter_version.clone(),
+            adapter_endpoint: self.adapter.endpoint.clone(),
+            model_id: self.model.model_id.clone(),
+            model_sha256: self.model.model_sha256.clone(),
+            pinning_level: self.pinning_level,
+        }
+    }
+}
+
+const RANKING: [&str; 5] = [
+    "preferred_model",
+    "pinning_level",
+    "context_window",
+    "adapter_id",
+    "model_id",
+];
+
+/// Picks an adapter and model for `req` from registered adapters. Candidates are filtered by
+/// adapter type, the constraints, the policy's minimum pinning level and the model catalog,
+/// then ranked by `RANKING`. Fails with `PolicyBlocked` when only policy excluded models.
+pub fn select_model(
+    adapters: &[RegisteredAdapter],
+    req: &RoutingRequest,
+) -> CoreResult<ModelSelection> {
+    let adapter_type = req.task_type.adapter_type();
+    let min_pinning = PinningLevel::minimum_for(req.policy_mode);
+    let mut evaluated: Vec<(CandidateEvaluation, &RegisteredAdapter, &AdapterModel)> = adapters
+        .iter()
+        .filter(|a| a.adapter_type == adapter_type)
+        .flat_map(|a| a.models.iter().map(move |m| (a, m)))
+        .map(|(a, m)| (evaluate(a, m, req, min_pinning), a, m))
+        .collect();
+    evaluated
+        .sort_by(|x, y| (&x.0.adapter_id, &x.0.model_id).cmp(&(&y.0.adapter_id, &y.0.model_id)));
+
+    let best = evaluated
+        .iter()
+        .filter(|(c, _, _)| c.rejected.is_empty())
+        .min_by(|x, y| {
+            let key = |(c, _, m): &&(CandidateEvaluation, &RegisteredAdapter, &AdapterModel)| {
+                (
+                    req.preferred_model.as_deref() != Some(m.model_id.as_str()),
+                    std::cmp::Reverse(c.pinning_level.rank()),
+                    std::cmp::Reverse(m.context_window.unwrap_or(0)),
+                )
+            };
+            key(x).cmp(&key(y))
+        })
+        .map(|(c, a, m)| (c.pinning_level, (*a).clone(), (*m).clone()));
+
+    let rationale = SelectionRationale {
+        adapter_type: adapter_type.to_string(),
+        policy_mode: req.policy_mode,
+        min_pinning_level: min_pinning,
+        preferred_model: req.preferred_model.clone(),
+        preferred_model_selected: false,
+        constraints: req.constraints.clone(),
+        model_catalog_hash_sha256: match &req.catalog {
+            Some(scope) => Some(scope.catalog.hash_sha256()?),
+            None => None,
+        },
+        candidates: evaluated.iter().map(|(c, _, _)| c.clone()).collect(),
+        ranking: RANKING.iter().map(|r| r.to_string()).collect(),
+    };
+
+    let Some((pinning_level, adapter, model)) = best else {
+        let policy_only = rationale
+            .candidates
+            .iter()
+            .any(|c| c.rejected.iter().all(|r| r.is_policy()));
+        let summary = serde_json::to_string(&rationale.candidates)?;
+        if policy_only {
+            return Err(CoreError::PolicyBlocked(format!(
+                "no {} model meets pinning level {:?} and the model catalog of {:?}: {}",
+                adapter_type, min_pinning, req.policy_mode, summary
+            )));
+        }
+        return Err(CoreError::InvalidInput(format!(
+            "no {} model satisfies constraints: {}",
+            adapter_type, summary
+        )));
+    };
+    Ok(ModelSelection {
+        task_type: req.task_type,
+        rationale: SelectionRationale {
+            preferred_model_selected: req.preferred_model.as_deref()
+                == Some(model.model_id.as_str()),
+            ..rationale
+        },
+        adapter,
+        model,
+        pinning_level,
+    })
+}
+
+/// Routes over the runtime's registry and records MODEL_SELECTION_RESOLVED. A CRYPTO_PINNED
+/// pick is only kept when `digests` verifies its `model_sha256`; otherwise the model loses
+/// the hash claim and selection runs again, so policy and catalog see its real level. Every
+/// check is recorded under `meta.pinning_verifications`, also on MODEL_SELECTION_FAILED when
+/// no model is left after a failed check.
+pub fn route_model<C: AdapterClient>(
+    runtime: &AdapterRuntime<C>,
+    req: &RoutingRequest,
+    digests: &dyn DigestSources,
+    audit: &mut AuditLog,
+    run_id: &str,
+    vault_id: &str,
+) -> CoreResult<ModelSelection> {
+    let mut adapters = runtime.adapters_of_type(req.task_type.adapter_type())?;
+    let mut verifications = Vec::new();
+    let selection = match select_verified(&mut adapters, req, digests, &mut verifications) {
+        Ok(selection) => selection,
+        Err(e) => {
+            audit.append(AuditEvent {
+                ts_utc: String::new(),
+                event_type: "MODEL_SELECTION_FAILED".to_string(),
+                run_id: run_id.to_string(),
+                vault_id: vault_id.to_string(),
+                actor: Actor::System,
+                details: json!({
+                    "task_type": req.task_type,
+                    "policy_mode": req.policy_mode,
+                    "reason": e.to_string(),
+                    "meta": {
+                        "preferred_model": req.preferred_model,
+                        "pinning_verifications": verifications
+                    }
+                }),
+                prev_event_hash: String::new(),
+                event_hash: String::new(),
+            })?;
+            return Err(e);
+        }
+    };
+    audit.append(AuditEvent {
+        ts_utc: String::new(),
+        event_type: "MODEL_SELECTION_RESOLVED".to_string(),
+        run_id: run_id.to_string(),
+        vault_id: vault_id.to_string(),
+        actor: Actor::System,
+        details: json!({
+            "task_type": selection.task_type,
+            "selected_model_id": selection.model.model_id,
+            "pinning_level": selection.pinning_level,
+            "adapter_id": selection.adapter.adapter_id,
+            "adapter_endpoint": selection.adapter.endpoint,
+            "meta": {
+                "adapter_version": selection.adapter.adapter_version,
+                "model_sha256": selection.model.model_sha256,
+                "rationale": selection.rationale,
+                "pinning_verifications": verifications
+            }
+        }),
+        prev_event_hash: String::new(),
+        event_hash: String::new(),
+    })?;
+    Ok(selection)
+}
+
+/// Selection loop of `route_model`; checks are pushed to `verifications` as they complete so
+/// the caller can record them whichever way routing ends.
+fn select_verified(
+    adapters: &mut [RegisteredAdapter],
+    req: &RoutingRequest,
+    digests: &dyn DigestSources,
+    verifications: &mut Vec<PinningVerification>,
+) -> CoreResult<ModelSelection> {
+    loop {
+        let selection = select_model(adapters, req)?;
+        if selection.pinning_level != PinningLevel::CRYPTO_PINNED {
+            return Ok(selection);
+        }
+        let source = digests.source_for(&selection.adapter, &selection.model);
+        let verification = verify_model_pinning(&selection.snapshot(), source.as_ref())?;
+        let verified = verification.outcome == VerificationOutcome::VERIFIED;
+        verifications.push(verification);
+        if verified {
+            return Ok(selection);
+        }
+        adapters
+            .iter_mut()
+            .filter(|a| a.adapter_id == selection.adapter.adapter_id)
+            .flat_map(|a| a.models.iter_mut())
+            .filter(|m| m.model_id == selection.model.model_id)
+            .for_each(|m| m.model_sha256 = None);
+    }
+}
+
+fn evaluate(
+    adapter: &RegisteredAdapter,
+    model: &AdapterModel,
+    req: &RoutingRequest,
+    min_pinning: PinningLevel,
+) -> CandidateEvaluation {
+    let c = &req.constraints;
+    let pinning_level = classify_pinning_level(
+        model.model_sha256.as_deref(),
+        &adapter.adapter_id,
+        &adapter.adapter_version,
+    );
+    let mut rejected = Vec::new();
+    if let Some(min) = c.min_context_window {
+        if model.context_window.unwrap_or(0) < min {
+            rejected.push(RejectReason::CONTEXT_WINDOW_TOO_SMALL);
+        }
+    }
+    if !c.allowed_quantizations.is_empty()
+        && !model
+            .quantization
+            .as_ref()
+            .is_some_and(|q| c.allowed_quantizations.contains(q))
+    {
+        rejected.push(RejectReason::QUANTIZATION_NOT_ALLOWED);
+    }
+    if c.require_model_sha256 && model.model_sha256.is_none() {
+        rejected.push(RejectReason::MODEL_SHA256_MISSING);
+    }
+    if pinning_level.rank() < min_pinning.rank() {
+        rejected.push(RejectReason::PINNING_BELOW_POLICY);
+    }
+    if let Some(scope) = &req.catalog {
+        let model_use = ModelUse {
+            source: "routing".to_string(),
+            model_id: model.model_id.clone(),
+            task_type: Some(req.task_type),
+            pinning_level: Some(pinning_level),
+        };
+        rejected.extend(
+            scope
+                .catalog
+                .check(&scope.pack_id, req.policy_mode, &model_use)
+                .into_iter()
+                .map(RejectReason::from),
+        );
+    }
+    CandidateEvaluation {
+        adapter_id: adapter.adapter_id.clone(),
+        model_id: model.model_id.clone(),
+        pinning_level,
+        rejected,
+    }
+}
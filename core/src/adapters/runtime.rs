@@ -1,24 +1,57 @@
 use crate::adapters::interface::{
     enforce_loopback_endpoint, AdapterCapabilitiesResponse, AdapterClient, AdapterHealthResponse,
-    ResolveModelRequest, ResolveModelResponse,
+    AdapterModel, EmbedRequest, EmbedResponse, GenerateChunk, GenerateRequest, GenerateResponse,
+    GenerateStream, ModelDigestRequest, ModelDigestResponse, ResolveModelRequest,
+    ResolveModelResponse, StreamOptions, TranscribeRequest, TranscribeResponse,
 };
+use crate::adapters::retry::RetryControl;
 use crate::error::{CoreError, CoreResult};
 use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+const DEFAULT_REGISTRY_TTL: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegisteredAdapter {
     pub adapter_id: String,
     pub endpoint: String,
-    pub adapter_type: String,
+    pub adapter_type: String, // LLM|VLM|STT|EMB
+    #[serde(default)]
+    pub adapter_version: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub models: Vec<AdapterModel>,
+}
+
+// Registry snapshot: entries pair each registration with the index of its client.
+struct RegistryCache {
+    entries: Vec<(RegisteredAdapter, usize)>,
+    built_at: Instant,
+    stale: bool,
+    miss_refresh: bool, // rebuilt for an unknown id; further misses wait for the TTL
 }
 
 pub struct AdapterRuntime<C: AdapterClient> {
     clients: Vec<C>,
+    ttl: Duration,
+    registry: Mutex<Option<RegistryCache>>,
 }
 
 impl<C: AdapterClient> AdapterRuntime<C> {
     pub fn new(clients: Vec<C>) -> Self {
-        Self { clients }
+        Self {
+            clients,
+            ttl: DEFAULT_REGISTRY_TTL,
+            registry: Mutex::new(None),
+        }
+    }
+
+    /// How long a registry built from health/capabilities probes is reused.
+    pub fn with_registry_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
     }
 
     pub fn validate_loopback_only(&self) -> CoreResult<()> {
@@ -44,20 +77,244 @@ impl<C: AdapterClient> AdapterRuntime<C> {
         Ok(out)
     }
 
+    /// Probes every client once and replaces the cached registry. Clients that fail their
+    /// health or capabilities probe are left out until the next refresh.
+    pub fn refresh_registry(&self) -> CoreResult<Vec<RegisteredAdapter>> {
+        self.rebuild_registry(false)
+    }
+
+    fn rebuild_registry(&self, miss_refresh: bool) -> CoreResult<Vec<RegisteredAdapter>> {
+        let mut entries: Vec<(RegisteredAdapter, usize)> = Vec::new();
+        for (idx, c) in self.clients.iter().enumerate() {
+            enforce_loopback_endpoint(c.endpoint())?;
+            let Ok(health) = c.health() else { continue };
+            let Ok(caps) = c.capabilities() else { continue };
+            if entries
+                .iter()
+                .any(|(r, _)| r.adapter_id == health.adapter_id)
+            {
+                return Err(CoreError::InvalidInput(format!(
+                    "duplicate adapter_id: {}",
+                    health.adapter_id
+                )));
+            }
+            entries.push((
+                RegisteredAdapter {
+                    adapter_id: health.adapter_id,
+                    endpoint: c.endpoint().to_string(),
+                    adapter_type: caps.adapter_type,
+                    adapter_version: health.adapter_version,
+                    features: caps.features,
+                    models: caps.models,
+                },
+                idx,
+            ));
+        }
+        entries.sort_by(|a, b| a.0.adapter_id.cmp(&b.0.adapter_id));
+        let out = entries.iter().map(|(r, _)| r.clone()).collect();
+        *self.lock() = Some(RegistryCache {
+            entries,
+            built_at: Instant::now(),
+            stale: false,
+            miss_refresh,
+        });
+        Ok(out)
+    }
+
+    /// Marks the cached registry stale; the next lookup re-probes all clients.
+    pub fn invalidate_registry(&self) {
+        if let Some(cache) = self.lock().as_mut() {
+            cache.stale = true;
+        }
+    }
+
+    /// Registered adapters sorted by adapter_id, refreshed when the TTL has passed.
+    pub fn registered(&self) -> CoreResult<Vec<RegisteredAdapter>> {
+        self.ensure_fresh()?;
+        Ok(self.lookup(|_| true).into_iter().map(|(r, _)| r).collect())
+    }
+
+    pub fn adapter(&self, adapter_id: &str) -> CoreResult<Option<RegisteredAdapter>> {
+        Ok(self.find(adapter_id)?.map(|(r, _)| r))
+    }
+
+    pub fn adapters_of_type(&self, adapter_type: &str) -> CoreResult<Vec<RegisteredAdapter>> {
+        self.ensure_fresh()?;
+        Ok(self
+            .lookup(|r| r.adapter_type == adapter_type)
+            .into_iter()
+            .map(|(r, _)| r)
+            .collect())
+    }
+
+    /// Adapters whose capabilities list `feature` (e.g. json_schema, streaming, timestamps).
+    pub fn adapters_with_feature(&self, feature: &str) -> CoreResult<Vec<RegisteredAdapter>> {
+        self.ensure_fresh()?;
+        Ok(self
+            .lookup(|r| r.features.iter().any(|f| f == feature))
+            .into_iter()
+            .map(|(r, _)| r)
+            .collect())
+    }
+
+    /// Client registered under `adapter_id`.
+    pub fn client(&self, adapter_id: &str) -> CoreResult<&C> {
+        match self.find(adapter_id)? {
+            Some((_, idx)) => Ok(&self.clients[idx]),
+            None => Err(CoreError::InvalidInput(format!(
+                "adapter not found: {}",
+                adapter_id
+            ))),
+        }
+    }
+
+    /// Client for calls made through `ModelCallLog`. An adapter fault on a call (timeout,
+    /// runtime or connection error) marks the registry stale, as a failed `resolve_model_for`
+    /// does, so the next lookup sees whether the adapter is still up.
+    pub fn call_client(&self, adapter_id: &str) -> CoreResult<RuntimeClient<'_, C>> {
+        Ok(RuntimeClient {
+            runtime: self,
+            inner: self.client(adapter_id)?,
+        })
+    }
+
     pub fn resolve_model_for(
         &self,
         adapter_id: &str,
         req: ResolveModelRequest,
     ) -> CoreResult<ResolveModelResponse> {
-        for c in &self.clients {
-            let h = c.health()?;
-            if h.adapter_id == adapter_id {
-                return c.resolve_model(req);
-            }
+        let client = self.client(adapter_id)?;
+        client.resolve_model(req).inspect_err(|_| {
+            // The adapter may have restarted or gone away; re-probe on the next lookup.
+            self.invalidate_registry();
+        })
+    }
+
+    // Cached lookup; an unknown id triggers a refresh in case the adapter came up since, at
+    // most once per TTL so repeated lookups of a missing id do not re-probe every adapter.
+    fn find(&self, adapter_id: &str) -> CoreResult<Option<(RegisteredAdapter, usize)>> {
+        let refreshed = self.ensure_fresh()?;
+        let hit = self.lookup(|r| r.adapter_id == adapter_id).pop();
+        let miss_refreshed = self.lock().as_ref().is_some_and(|c| c.miss_refresh);
+        if hit.is_some() || refreshed || miss_refreshed {
+            return Ok(hit);
+        }
+        self.rebuild_registry(true)?;
+        Ok(self.lookup(|r| r.adapter_id == adapter_id).pop())
+    }
+
+    // Returns whether the registry had to be rebuilt.
+    fn ensure_fresh(&self) -> CoreResult<bool> {
+        let fresh = self
+            .lock()
+            .as_ref()
+            .is_some_and(|c| !c.stale && c.built_at.elapsed() < self.ttl);
+        if !fresh {
+            self.refresh_registry()?;
+        }
+        Ok(!fresh)
+    }
+
+    fn lookup(&self, pred: impl Fn(&RegisteredAdapter) -> bool) -> Vec<(RegisteredAdapter, usize)> {
+        self.lock()
+            .as_ref()
+            .map(|c| c.entries.iter().filter(|(r, _)| pred(r)).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<RegistryCache>> {
+        self.registry.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Client of an `AdapterRuntime` that reports adapter faults back to its registry.
+pub struct RuntimeClient<'a, C: AdapterClient> {
+    runtime: &'a AdapterRuntime<C>,
+    inner: &'a C,
+}
+
+impl<C: AdapterClient> RuntimeClient<'_, C> {
+    fn watched<T>(&self, result: CoreResult<T>) -> CoreResult<T> {
+        if result.as_ref().is_err_and(is_adapter_fault) {
+            self.runtime.invalidate_registry();
+        }
+        result
+    }
+}
+
+// Failures that say something about the adapter process rather than the request.
+fn is_adapter_fault(err: &CoreError) -> bool {
+    match err {
+        CoreError::Adapter(env) => {
+            matches!(env.error.category.as_str(), "TIMEOUT" | "RUNTIME_ERROR")
         }
-        Err(CoreError::InvalidInput(format!(
-            "adapter not found: {}",
-            adapter_id
-        )))
+        CoreError::Io(_) => true,
+        _ => false,
+    }
+}
+
+impl<C: AdapterClient> AdapterClient for RuntimeClient<'_, C> {
+    fn endpoint(&self) -> &str {
+        self.inner.endpoint()
+    }
+
+    fn health(&self) -> CoreResult<AdapterHealthResponse> {
+        self.watched(self.inner.health())
+    }
+
+    fn capabilities(&self) -> CoreResult<AdapterCapabilitiesResponse> {
+        self.watched(self.inner.capabilities())
+    }
+
+    fn resolve_model(&self, req: ResolveModelRequest) -> CoreResult<ResolveModelResponse> {
+        self.watched(self.inner.resolve_model(req))
+    }
+
+    fn model_digest(&self, req: &ModelDigestRequest) -> CoreResult<ModelDigestResponse> {
+        self.watched(self.inner.model_digest(req))
+    }
+
+    fn call_timeout(&self) -> Option<Duration> {
+        self.inner.call_timeout()
+    }
+
+    fn retry_control(&self) -> Option<&RetryControl> {
+        self.inner.retry_control()
+    }
+
+    fn generate(&self, req: &GenerateRequest) -> CoreResult<GenerateResponse> {
+        self.watched(self.inner.generate(req))
+    }
+
+    fn generate_stream(
+        &self,
+        req: &GenerateRequest,
+        opts: &StreamOptions,
+    ) -> CoreResult<Box<dyn GenerateStream + '_>> {
+        let stream = self.watched(self.inner.generate_stream(req, opts))?;
+        Ok(Box::new(WatchedStream {
+            client: self,
+            stream,
+        }))
+    }
+
+    fn embed(&self, req: &EmbedRequest) -> CoreResult<EmbedResponse> {
+        self.watched(self.inner.embed(req))
+    }
+
+    fn transcribe(&self, req: &TranscribeRequest) -> CoreResult<TranscribeResponse> {
+        self.watched(self.inner.transcribe(req))
+    }
+}
+
+// Faults can also surface mid-stream, after the stream was opened.
+struct WatchedStream<'s, 'a, C: AdapterClient> {
+    client: &'s RuntimeClient<'a, C>,
+    stream: Box<dyn GenerateStream + 's>,
+}
+
+impl<C: AdapterClient> GenerateStream for WatchedStream<'_, '_, C> {
+    fn next_chunk(&mut self) -> CoreResult<Option<GenerateChunk>> {
+        self.client.watched(self.stream.next_chunk())
     }
 }
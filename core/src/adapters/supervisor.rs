@@ -0,0 +1,306 @@
+use crate::adapters::http::HttpAdapterClient;
+use crate::adapters::interface::{classify_adapter_error, AdapterClient, AdapterHealthResponse};
+use crate::adapters::pinning::ModelSnapshot;
+use crate::audit::event::{Actor, AuditEvent};
+use crate::audit::log::AuditLog;
+use crate::error::{CoreError, CoreResult};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+/// Environment variable giving a launched adapter the `host:port` it must listen on.
+pub const ADAPTER_LISTEN_ENV: &str = "AIGC_ADAPTER_LISTEN";
+
+/// How to launch a local adapter binary.
+#[derive(Debug, Clone)]
+pub struct AdapterLaunchConfig {
+    pub adapter_id: String, // must match what `/v1/health` reports
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub endpoint: String, // loopback; passed to the process as ADAPTER_LISTEN_ENV
+    pub startup_timeout: Duration,
+    pub health_poll_interval: Duration,
+    pub max_restarts: u32, // over the supervisor's lifetime
+}
+
+impl AdapterLaunchConfig {
+    pub fn new(adapter_id: &str, program: impl Into<PathBuf>, endpoint: &str) -> Self {
+        Self {
+            adapter_id: adapter_id.to_string(),
+            program: program.into(),
+            args: Vec::new(),
+            env: Vec::new(),
+            endpoint: endpoint.to_string(),
+            startup_timeout: Duration::from_secs(30),
+            health_poll_interval: Duration::from_millis(100),
+            max_restarts: 3,
+        }
+    }
+}
+
+/// Run the supervised adapter belongs to.
+#[derive(Debug, Clone)]
+pub struct SupervisorScope {
+    pub run_id: String,
+    pub vault_id: String,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AdapterProcessAction {
+    LAUNCHED,
+    EXITED, // ended on its own
+    STOPPED,
+}
+
+impl AdapterProcessAction {
+    pub fn event_type(self) -> &'static str {
+        match self {
+            AdapterProcessAction::LAUNCHED => "ADAPTER_PROCESS_LAUNCHED",
+            AdapterProcessAction::EXITED => "ADAPTER_PROCESS_EXITED",
+            AdapterProcessAction::STOPPED => "ADAPTER_PROCESS_STOPPED",
+        }
+    }
+}
+
+/// Owns a locally launched adapter process for the duration of a run. Each launch, exit and
+/// stop is recorded as ADAPTER_PROCESS_LAUNCHED, ADAPTER_PROCESS_EXITED or
+/// ADAPTER_PROCESS_STOPPED on the run's log.
+///
+/// A crash is only noticed when `ensure_running` is called, which then restarts the adapter up
+/// to `max_restarts` times; `RunManager` calls it before each model call and after a failed
+/// one. A restarted adapter must report the version captured at launch, since that version
+/// is what `model_snapshot.json` pins.
+///
+/// Attached to a `RunManager`, the adapter is shut down with the run. Dropping a supervisor
+/// that was never shut down kills the process without an audit record.
+pub struct AdapterSupervisor {
+    config: AdapterLaunchConfig,
+    scope: SupervisorScope,
+    client: HttpAdapterClient,
+    child: Option<Child>,
+    health: AdapterHealthResponse,
+    restarts: u32,
+}
+
+impl AdapterSupervisor {
+    /// Launches the adapter and waits until `/v1/health` reports it ready.
+    pub fn launch(
+        config: AdapterLaunchConfig,
+        scope: SupervisorScope,
+        audit: &mut AuditLog,
+    ) -> CoreResult<Self> {
+        let client = HttpAdapterClient::new(&config.endpoint)?;
+        let (child, health) = start(&config, &client)?;
+        let supervisor = Self {
+            config,
+            scope,
+            client,
+            child: Some(child),
+            health,
+            restarts: 0,
+        };
+        supervisor.record(audit, AdapterProcessAction::LAUNCHED, None)?;
+        Ok(supervisor)
+    }
+
+    pub fn client(&self) -> &HttpAdapterClient {
+        &self.client
+    }
+
+    pub fn adapter_id(&self) -> &str {
+        &self.config.adapter_id
+    }
+
+    /// `adapter_version` from the health check at launch.
+    pub fn adapter_version(&self) -> &str {
+        &self.health.adapter_version
+    }
+
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    pub fn pid(&self) -> Option<u32> {
+        self.child.as_ref().map(Child::id)
+    }
+
+    /// Fills the adapter identity of `snapshot` from the supervised process.
+    pub fn fill_snapshot(&self, snapshot: &mut ModelSnapshot) {
+        snapshot.adapter_id = self.config.adapter_id.clone();
+        snapshot.adapter_version = self.health.adapter_version.clone();
+        snapshot.adapter_endpoint = self.client.endpoint().to_string();
+    }
+
+    /// Restarts the adapter if its process has exited. Fails once `max_restarts` is used up,
+    /// after `shutdown`, or when the restarted adapter reports a different version.
+    pub fn ensure_running(&mut self, audit: &mut AuditLog) -> CoreResult<()> {
+        let Some(child) = self.child.as_mut() else {
+            return Err(supervisor_error(
+                "ADAPTER_STOPPED",
+                &format!("adapter {} was shut down", self.config.adapter_id),
+            ));
+        };
+        let pid = child.id();
+        let Some(status) = child.try_wait()? else {
+            return Ok(());
+        };
+        self.child = None;
+        self.record(audit, AdapterProcessAction::EXITED, Some((pid, status)))?;
+        if self.restarts >= self.config.max_restarts {
+            return Err(supervisor_error(
+                "ADAPTER_RESTART_LIMIT",
+                &format!(
+                    "adapter {} exited ({}) after {} restarts",
+                    self.config.adapter_id, status, self.restarts
+                ),
+            ));
+        }
+        self.restarts += 1;
+        let (child, health) = start(&self.config, &self.client)?;
+        self.child = Some(child);
+        self.record(audit, AdapterProcessAction::LAUNCHED, None)?;
+        if health.adapter_version != self.health.adapter_version {
+            self.shutdown(audit)?;
+            return Err(supervisor_error(
+                "ADAPTER_VERSION_CHANGED",
+                &format!(
+                    "adapter {} restarted as version {}, launched as {}",
+                    self.config.adapter_id, health.adapter_version, self.health.adapter_version
+                ),
+            ));
+        }
+        self.health = health;
+        Ok(())
+    }
+
+    /// Stops the adapter process and records STOPPED. Later calls do nothing.
+    pub fn shutdown(&mut self, audit: &mut AuditLog) -> CoreResult<()> {
+        let Some(mut child) = self.child.take() else {
+            return Ok(());
+        };
+        let pid = child.id();
+        let status = match child.try_wait()? {
+            Some(status) => status,
+            None => {
+                child.kill()?;
+                child.wait()?
+            }
+        };
+        self.record(audit, AdapterProcessAction::STOPPED, Some((pid, status)))
+    }
+
+    fn record(
+        &self,
+        audit: &mut AuditLog,
+        action: AdapterProcessAction,
+        exited: Option<(u32, ExitStatus)>,
+    ) -> CoreResult<()> {
+        let pid = exited.map(|(pid, _)| pid).or_else(|| self.pid());
+        let exit_code = exited.and_then(|(_, status)| status.code());
+        audit.append(AuditEvent {
+            ts_utc: String::new(),
+            event_type: action.event_type().to_string(),
+            run_id: self.scope.run_id.clone(),
+            vault_id: self.scope.vault_id.clone(),
+            actor: Actor::System,
+            details: json!({
+                "adapter_id": self.config.adapter_id,
+                "adapter_version": self.health.adapter_version,
+                "adapter_endpoint": self.client.endpoint(),
+                "pid": pid,
+                "exit_code": exit_code,
+                "restarts": self.restarts,
+                "meta": { "program": self.config.program.to_string_lossy() }
+            }),
+            prev_event_hash: String::new(),
+            event_hash: String::new(),
+        })?;
+        Ok(())
+    }
+}
+
+impl Drop for AdapterSupervisor {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+// Spawns the adapter and polls `/v1/health` until it answers `ok` for the configured id.
+fn start(
+    config: &AdapterLaunchConfig,
+    client: &HttpAdapterClient,
+) -> CoreResult<(Child, AdapterHealthResponse)> {
+    let url = url::Url::parse(client.endpoint())
+        .map_err(|_| CoreError::InvalidInput("invalid adapter endpoint URL".to_string()))?;
+    let listen = format!(
+        "{}:{}",
+        url.host_str().unwrap_or_default(),
+        url.port_or_known_default().unwrap_or_default()
+    );
+    let mut child = Command::new(&config.program)
+        .args(&config.args)
+        .envs(config.env.iter().map(|(k, v)| (k, v)))
+        .env(ADAPTER_LISTEN_ENV, listen)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let deadline = Instant::now() + config.startup_timeout;
+    let failure = loop {
+        if let Some(status) = child.try_wait()? {
+            return Err(supervisor_error(
+                "ADAPTER_EXITED",
+                &format!(
+                    "adapter {} exited during startup ({})",
+                    config.adapter_id, status
+                ),
+            ));
+        }
+        let last = match client.health() {
+            Ok(h) if h.adapter_id != config.adapter_id => {
+                break supervisor_error(
+                    "ADAPTER_ID_MISMATCH",
+                    &format!(
+                        "{} reports adapter_id {}, expected {}",
+                        client.endpoint(),
+                        h.adapter_id,
+                        config.adapter_id
+                    ),
+                );
+            }
+            Ok(h) if h.status == "ok" => return Ok((child, h)),
+            Ok(h) => format!("status {}", h.status),
+            Err(e) => e.to_string(),
+        };
+        if Instant::now() >= deadline {
+            break supervisor_error(
+                "ADAPTER_START_FAILED",
+                &format!(
+                    "adapter {} not healthy within {} ms: {}",
+                    config.adapter_id,
+                    config.startup_timeout.as_millis(),
+                    last
+                ),
+            );
+        }
+        std::thread::sleep(config.health_poll_interval);
+    };
+    let _ = child.kill();
+    let _ = child.wait();
+    Err(failure)
+}
+
+fn supervisor_error(code: &str, message: &str) -> CoreError {
+    let mut env = classify_adapter_error(message);
+    env.error.category = "RUNTIME_ERROR".to_string();
+    env.error.code = code.to_string();
+    env.error.retryable = false;
+    CoreError::Adapter(Box::new(env))
+}
@@ -0,0 +1,247 @@
+use crate::adapters::interface::{AdapterClient, AdapterModel, ModelDigestRequest};
+use crate::adapters::pinning::{classify_pinning_level, ModelSnapshot, PinningLevel};
+use crate::adapters::runtime::{AdapterRuntime, RegisteredAdapter};
+use crate::error::{CoreError, CoreResult};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Where the observed digest came from.
+pub enum DigestSource<'a> {
+    /// Weight file, or a directory of weight files, on local disk.
+    LocalFiles(&'a Path),
+    /// Digest reported by the adapter, signed with `key` over a fresh nonce.
+    Adapter {
+        client: &'a dyn AdapterClient,
+        key: &'a [u8],
+    },
+}
+
+/// Where routing gets the digest for a model that claims a `model_sha256`.
+pub trait DigestSources {
+    /// `None` when nothing can vouch for the model's hash; it then cannot be CRYPTO_PINNED.
+    fn source_for(
+        &self,
+        adapter: &RegisteredAdapter,
+        model: &AdapterModel,
+    ) -> Option<DigestSource<'_>>;
+}
+
+/// No digest source: every claimed `model_sha256` stays unverified.
+pub struct NoDigestSources;
+
+impl DigestSources for NoDigestSources {
+    fn source_for(&self, _: &RegisteredAdapter, _: &AdapterModel) -> Option<DigestSource<'_>> {
+        None
+    }
+}
+
+/// Local weight files or directories, by model id.
+#[derive(Debug, Clone, Default)]
+pub struct LocalWeights {
+    pub paths: BTreeMap<String, PathBuf>,
+}
+
+impl DigestSources for LocalWeights {
+    fn source_for(&self, _: &RegisteredAdapter, model: &AdapterModel) -> Option<DigestSource<'_>> {
+        self.paths
+            .get(&model.model_id)
+            .map(|p| DigestSource::LocalFiles(p))
+    }
+}
+
+/// Signed digests from the adapter that lists the model, checked with the provisioned key.
+pub struct SignedAdapterDigests<'a, C: AdapterClient> {
+    pub runtime: &'a AdapterRuntime<C>,
+    pub key: Vec<u8>,
+}
+
+impl<C: AdapterClient> DigestSources for SignedAdapterDigests<'_, C> {
+    fn source_for(
+        &self,
+        adapter: &RegisteredAdapter,
+        _: &AdapterModel,
+    ) -> Option<DigestSource<'_>> {
+        let client = self.runtime.client(&adapter.adapter_id).ok()?;
+        Some(DigestSource::Adapter {
+            client,
+            key: &self.key,
+        })
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DigestMethod {
+    LOCAL_FILES,
+    ADAPTER_SIGNED_DIGEST,
+    NO_SOURCE,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum VerificationOutcome {
+    VERIFIED,
+    MISMATCH,
+    UNVERIFIABLE, // no digest could be obtained, or its signature did not check out
+}
+
+/// Result of checking a snapshot's `model_sha256`, recorded by `route_model` under
+/// `meta.pinning_verifications` of MODEL_SELECTION_RESOLVED or MODEL_SELECTION_FAILED.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PinningVerification {
+    pub model_id: String,
+    pub method: DigestMethod,
+    pub outcome: VerificationOutcome,
+    pub expected_sha256: String,
+    pub observed_sha256: Option<String>,
+    pub claimed_level: PinningLevel,
+    pub effective_level: PinningLevel,
+    pub detail: String,
+}
+
+/// SHA-256 of a weight file. For a directory, the SHA-256 of its `sha256sum`-style listing:
+/// one `<hex>  <relative/path>\n` line per file, sorted by path.
+pub fn hash_model_files(path: &Path) -> CoreResult<String> {
+    if path.is_file() {
+        return hash_file(path);
+    }
+    let mut lines = Vec::new();
+    for entry in WalkDir::new(path).follow_links(false) {
+        let entry = entry.map_err(|e| CoreError::InvalidInput(e.to_string()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry
+            .path()
+            .strip_prefix(path)
+            .map_err(|e| CoreError::InvalidInput(e.to_string()))?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        lines.push((rel, hash_file(entry.path())?));
+    }
+    if lines.is_empty() {
+        return Err(CoreError::InvalidInput(format!(
+            "no model weight files under {}",
+            path.display()
+        )));
+    }
+    lines.sort();
+    let mut hasher = Sha256::new();
+    for (rel, hex) in lines {
+        hasher.update(format!("{}  {}\n", hex, rel).as_bytes());
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Signature an adapter returns from `POST /v1/models/digest`: hex HMAC-SHA256 under its
+/// provisioned key over `model_id \n model_sha256 \n nonce`.
+pub fn sign_model_digest(key: &[u8], model_id: &str, model_sha256: &str, nonce: &str) -> String {
+    hex::encode(
+        digest_mac(key, model_id, model_sha256, nonce)
+            .finalize()
+            .into_bytes(),
+    )
+}
+
+fn digest_mac(key: &[u8], model_id: &str, model_sha256: &str, nonce: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}\n{}\n{}", model_id, model_sha256, nonce).as_bytes());
+    mac
+}
+
+/// Checks `snapshot.model_sha256` against a digest from `source`. Anything short of a match
+/// downgrades the effective level to what adapter id and version alone support.
+pub fn verify_model_pinning(
+    snapshot: &ModelSnapshot,
+    source: Option<&DigestSource>,
+) -> CoreResult<PinningVerification> {
+    let Some(expected) = snapshot.model_sha256.as_deref() else {
+        return Err(CoreError::InvalidInput(format!(
+            "model {} has no model_sha256 to verify",
+            snapshot.model_id
+        )));
+    };
+    let (method, observed) = match source {
+        Some(DigestSource::LocalFiles(path)) => (DigestMethod::LOCAL_FILES, hash_model_files(path)),
+        Some(DigestSource::Adapter { client, key }) => (
+            DigestMethod::ADAPTER_SIGNED_DIGEST,
+            adapter_digest(*client, key, &snapshot.model_id),
+        ),
+        None => (
+            DigestMethod::NO_SOURCE,
+            Err(CoreError::InvalidInput(
+                "no digest source for this model".to_string(),
+            )),
+        ),
+    };
+    let (outcome, observed, detail) = match observed {
+        Ok(hex) if hex.eq_ignore_ascii_case(expected) => {
+            (VerificationOutcome::VERIFIED, Some(hex), String::new())
+        }
+        Ok(hex) => (
+            VerificationOutcome::MISMATCH,
+            Some(hex),
+            "observed digest differs from model_sha256".to_string(),
+        ),
+        Err(e) => (VerificationOutcome::UNVERIFIABLE, None, e.to_string()),
+    };
+    let effective_level = match outcome {
+        VerificationOutcome::VERIFIED => snapshot.pinning_level,
+        _ => classify_pinning_level(None, &snapshot.adapter_id, &snapshot.adapter_version),
+    };
+    Ok(PinningVerification {
+        model_id: snapshot.model_id.clone(),
+        method,
+        outcome,
+        expected_sha256: expected.to_string(),
+        observed_sha256: observed,
+        claimed_level: snapshot.pinning_level,
+        effective_level,
+        detail,
+    })
+}
+
+fn adapter_digest(client: &dyn AdapterClient, key: &[u8], model_id: &str) -> CoreResult<String> {
+    let nonce = hex::encode(rand::random::<[u8; 16]>());
+    let resp = client.model_digest(&ModelDigestRequest {
+        model_id: model_id.to_string(),
+        nonce: nonce.clone(),
+    })?;
+    if resp.model_id != model_id || resp.nonce != nonce {
+        return Err(CoreError::InvalidInput(
+            "adapter digest does not answer this challenge".to_string(),
+        ));
+    }
+    let signature = hex::decode(&resp.signature).unwrap_or_default();
+    if digest_mac(key, &resp.model_id, &resp.model_sha256, &nonce)
+        .verify_slice(&signature)
+        .is_err()
+    {
+        return Err(CoreError::InvalidInput(
+            "adapter digest signature is invalid".to_string(),
+        ));
+    }
+    Ok(resp.model_sha256)
+}
+
+fn hash_file(path: &Path) -> CoreResult<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
@@ -67,6 +67,7 @@ fn validate_event_taxonomy(event: &AuditEvent) -> CoreResult<()> {
         "EVAL_GATE_RESULT",
         "EVAL_COMPLETED",
         "EXPORT_REQUESTED",
+        "EXPORT_RESUMED",
         "EXPORT_BLOCKED",
         "EXPORT_COMPLETED",
         "RUN_COMPLETED",
@@ -75,6 +76,7 @@ fn validate_event_taxonomy(event: &AuditEvent) -> CoreResult<()> {
         "EGRESS_REQUEST_ALLOWED",
         "EGRESS_REQUEST_BLOCKED",
         "MODEL_SELECTION_RESOLVED",
+        "MODEL_SELECTION_FAILED",
         "MODEL_CALL_STARTED",
         "MODEL_CALL_COMPLETED",
         "MODEL_CALL_FAILED",
@@ -94,6 +96,9 @@ fn validate_event_taxonomy(event: &AuditEvent) -> CoreResult<()> {
         "DELETION_REQUESTED",
         "DELETION_COMPLETED",
         "EXPORT_FAILED",
+        "ADAPTER_PROCESS_LAUNCHED",
+        "ADAPTER_PROCESS_EXITED",
+        "ADAPTER_PROCESS_STOPPED",
     ];
     if !allowed.contains(&event.event_type.as_str()) {
         return Err(CoreError::InvalidInput(format!(
@@ -143,6 +148,7 @@ fn required_detail_keys(event_type: &str) -> &'static [&'static str] {
             "adapter_id",
             "adapter_endpoint",
         ],
+        "MODEL_SELECTION_FAILED" => &["task_type", "policy_mode", "reason"],
         "MODEL_CALL_STARTED" => &[
             "call_id",
             "task_type",
@@ -188,6 +194,14 @@ fn required_detail_keys(event_type: &str) -> &'static [&'static str] {
             "gates_failed_total",
         ],
         "EXPORT_REQUESTED" => &["requested_by", "export_targets", "policy_mode"],
+        "EXPORT_RESUMED" => &[
+            "requested_by",
+            "export_targets",
+            "requested_event_hash",
+            "resumed_from_step",
+            "completed_steps",
+            "checkpoint_event_hash",
+        ],
         "EXPORT_BLOCKED" => &["block_reason", "failed_gate_ids"],
         "EXPORT_COMPLETED" => &[
             "bundle_path",
@@ -205,6 +219,14 @@ fn required_detail_keys(event_type: &str) -> &'static [&'static str] {
             "sqlite_compaction_attempted",
             "result",
         ],
+        "ADAPTER_PROCESS_LAUNCHED" | "ADAPTER_PROCESS_EXITED" | "ADAPTER_PROCESS_STOPPED" => &[
+            "adapter_id",
+            "adapter_version",
+            "adapter_endpoint",
+            "pid",
+            "exit_code",
+            "restarts",
+        ],
         _ => &[],
     }
 }
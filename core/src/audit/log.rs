@@ -1,13 +1,17 @@
 use crate::audit::event::{finalize_event, AuditEvent, ZERO_HASH_64};
+use crate::determinism::clock::{Clock, SystemClock};
 use crate::error::{CoreError, CoreResult};
 use serde_json::Value;
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
+use std::sync::Arc;
 
 pub struct AuditLog {
     path: std::path::PathBuf,
     last_hash: String,
+    last_ts_utc: Option<String>,
+    clock: Arc<dyn Clock>,
 }
 
 impl AuditLog {
@@ -21,12 +25,15 @@ impl AuditLog {
             return Ok(Self {
                 path,
                 last_hash: ZERO_HASH_64.to_string(),
+                last_ts_utc: None,
+                clock: Arc::new(SystemClock),
             });
         }
 
         let file = File::open(&path)?;
         let reader = BufReader::new(file);
         let mut last_hash = ZERO_HASH_64.to_string();
+        let mut last_ts_utc = None;
         for line in reader.lines() {
             let line = line?;
             if line.trim().is_empty() {
@@ -40,11 +47,43 @@ impl AuditLog {
                     CoreError::InvalidInput("audit_log line missing event_hash".to_string())
                 })?;
             last_hash = eh.to_string();
+            last_ts_utc = v.get("ts_utc").and_then(|x| x.as_str()).map(str::to_string);
         }
-        Ok(Self { path, last_hash })
+        Ok(Self {
+            path,
+            last_hash,
+            last_ts_utc,
+            clock: Arc::new(SystemClock),
+        })
     }
 
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        self.clock.clone()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn last_hash(&self) -> &str {
+        &self.last_hash
+    }
+
+    /// `ts_utc` of the last event in the log, if any.
+    pub fn last_ts_utc(&self) -> Option<&str> {
+        self.last_ts_utc.as_deref()
+    }
+
+    /// Appends to the hash chain. An empty `ts_utc` is stamped from the log's clock.
     pub fn append(&mut self, mut event: AuditEvent) -> CoreResult<AuditEvent> {
+        if event.ts_utc.is_empty() {
+            event.ts_utc = self.clock.now_rfc3339_utc();
+        }
         event.prev_event_hash = self.last_hash.clone();
         let event = finalize_event(event)?;
         let line = serde_json::to_string(&event)?; // already canonical rules for hashing; log bytes can be compact JSON
@@ -52,6 +91,7 @@ impl AuditLog {
         f.write_all(line.as_bytes())?;
         f.write_all(b"\n")?;
         self.last_hash = event.event_hash.clone();
+        self.last_ts_utc = Some(event.ts_utc.clone());
         Ok(event)
     }
 }
@@ -0,0 +1,104 @@
+use crate::error::{CoreError, CoreResult};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+// Origin for deterministic runs; matches the fixed self-audit timestamp used by gate_runner.
+pub const DETERMINISTIC_EPOCH_RFC3339: &str = "2026-02-10T00:00:00Z";
+
+/// Source of `ts_utc` values for audit events.
+pub trait Clock: Send + Sync {
+    fn now_rfc3339_utc(&self) -> String;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_rfc3339_utc(&self) -> String {
+        format_rfc3339(OffsetDateTime::now_utc())
+    }
+}
+
+/// Always returns the same timestamp.
+pub struct FixedClock {
+    ts_utc: String,
+}
+
+impl FixedClock {
+    pub fn new(ts_rfc3339: &str) -> CoreResult<Self> {
+        Ok(Self {
+            ts_utc: format_rfc3339(parse_rfc3339(ts_rfc3339)?),
+        })
+    }
+}
+
+impl Clock for FixedClock {
+    fn now_rfc3339_utc(&self) -> String {
+        self.ts_utc.clone()
+    }
+}
+
+/// Starts at a fixed instant and advances by `step` on every read, so event order stays strictly increasing.
+pub struct SteppedClock {
+    start: OffsetDateTime,
+    step: time::Duration,
+    ticks: AtomicU64,
+}
+
+impl SteppedClock {
+    pub fn new(start_rfc3339: &str, step: time::Duration) -> CoreResult<Self> {
+        Ok(Self {
+            start: parse_rfc3339(start_rfc3339)?,
+            step,
+            ticks: AtomicU64::new(0),
+        })
+    }
+}
+
+impl Clock for SteppedClock {
+    fn now_rfc3339_utc(&self) -> String {
+        let n = self.ticks.fetch_add(1, Ordering::SeqCst) as i128;
+        let offset_ns =
+            (self.step.whole_nanoseconds() * n).clamp(i64::MIN as i128, i64::MAX as i128);
+        format_rfc3339(self.start + time::Duration::nanoseconds(offset_ns as i64))
+    }
+}
+
+/// Deterministic profiles get a stepped clock from a fixed epoch; otherwise wall-clock time is used.
+pub fn clock_for_profile(determinism_enabled: bool) -> Arc<dyn Clock> {
+    if determinism_enabled {
+        Arc::new(
+            SteppedClock::new(DETERMINISTIC_EPOCH_RFC3339, time::Duration::SECOND)
+                .expect("deterministic epoch must parse"),
+        )
+    } else {
+        Arc::new(SystemClock)
+    }
+}
+
+/// Clock for a run reopened after `last_ts_utc`, the timestamp of its latest audit event.
+/// Deterministic profiles keep stepping from there instead of restarting at the epoch.
+pub fn resumed_clock_for_profile(
+    determinism_enabled: bool,
+    last_ts_utc: Option<&str>,
+) -> CoreResult<Arc<dyn Clock>> {
+    match (determinism_enabled, last_ts_utc) {
+        (true, Some(ts)) => Ok(Arc::new(SteppedClock {
+            start: parse_rfc3339(ts)? + time::Duration::SECOND,
+            step: time::Duration::SECOND,
+            ticks: AtomicU64::new(0),
+        })),
+        _ => Ok(clock_for_profile(determinism_enabled)),
+    }
+}
+
+fn parse_rfc3339(ts: &str) -> CoreResult<OffsetDateTime> {
+    let dt = OffsetDateTime::parse(ts, &Rfc3339)
+        .map_err(|e| CoreError::InvalidInput(format!("invalid RFC3339 timestamp {}: {}", ts, e)))?;
+    Ok(dt.to_offset(time::UtcOffset::UTC))
+}
+
+fn format_rfc3339(dt: OffsetDateTime) -> String {
+    dt.format(&Rfc3339).unwrap()
+}
@@ -1,3 +1,4 @@
+pub mod clock;
 pub mod json_canonical;
 pub mod run_id;
 pub mod zip;
@@ -15,6 +15,21 @@ pub fn run_id_from_manifest_inputs_fingerprint_hex32(
     Ok(format!("r_{}", hex[..32].to_ascii_lowercase()))
 }
 
+/// Run ids name vault blobs and workspace directories, so they are limited to `[A-Za-z0-9_-]+`.
+pub fn validate_run_id(run_id: &str) -> CoreResult<()> {
+    if run_id.is_empty()
+        || !run_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(CoreError::InvalidInput(format!(
+            "run_id must match [A-Za-z0-9_-]+: {:?}",
+            run_id
+        )));
+    }
+    Ok(())
+}
+
 pub fn run_id_ulid() -> String {
     format!("r_{}", Ulid::new().to_string())
 }
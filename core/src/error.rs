@@ -1,3 +1,4 @@
+use crate::adapters::interface::AdapterErrorEnvelope;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -35,6 +36,12 @@ pub enum CoreError {
     #[error("evidenceos validation failed: {0}")]
     EvidenceOsValidation(String),
 
+    #[error("cancelled during {0}")]
+    Cancelled(String),
+
+    #[error("adapter error {}: {}", .0.error.category, .0.error.message)]
+    Adapter(Box<AdapterErrorEnvelope>),
+
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
 
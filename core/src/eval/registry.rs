@@ -1,4 +1,5 @@
 use crate::error::{CoreError, CoreResult};
+use crate::validator::checklist::Severity;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,7 +13,7 @@ pub struct GateRegistry {
 pub struct GateDef {
     pub gate_id: String,
     pub category: String,
-    pub severity: String,
+    pub severity: Severity,
     pub applies_to_policies: Vec<String>,
     pub pass_criteria: serde_json::Value,
     pub evidence_required: Vec<String>,
@@ -1,9 +1,14 @@
+use crate::adapters::pinning::ModelSnapshot;
 use crate::error::CoreResult;
 use crate::eval::registry::{registry_v3, GateRegistry};
+use crate::policy::model_catalog::{collect_model_uses, ModelCatalogSnapshot};
+use crate::policy::network_snapshot::NetworkSnapshot;
 use crate::policy::types::PolicyMode;
-use crate::validator::{BundleValidator, ValidationSummary};
+use crate::validator::checklist::Severity;
+use crate::validator::{BundleValidator, CheckStatus, ValidationSummary};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
@@ -12,12 +17,29 @@ use zip::ZipArchive;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GateRunResult {
     pub gate_id: String,
-    pub result: String, // PASS|FAIL|NOT_APPLICABLE
-    pub severity: String,
+    pub result: GateStatus,
+    pub severity: Severity,
     pub message: String,
     pub evidence_pointers: Vec<String>,
 }
 
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GateStatus {
+    PASS,
+    FAIL,
+    NOT_APPLICABLE,
+}
+
+impl From<CheckStatus> for GateStatus {
+    fn from(s: CheckStatus) -> Self {
+        match s {
+            CheckStatus::PASS => GateStatus::PASS,
+            CheckStatus::FAIL => GateStatus::FAIL,
+        }
+    }
+}
+
 pub struct EvalRunner {
     pub registry: GateRegistry,
 }
@@ -33,15 +55,32 @@ impl EvalRunner {
         &self,
         bundle_zip: &std::path::Path,
         policy: PolicyMode,
+    ) -> CoreResult<Vec<GateRunResult>> {
+        self.run_all_for_bundle_checked(bundle_zip, policy, &|_| Ok(()))
+    }
+
+    /// Like `run_all_for_bundle`, but calls `check` before each gate evaluation so callers can
+    /// abort (e.g. on cancellation) by returning an error.
+    pub fn run_all_for_bundle_checked(
+        &self,
+        bundle_zip: &std::path::Path,
+        policy: PolicyMode,
+        check: &dyn Fn(&str) -> CoreResult<()>,
     ) -> CoreResult<Vec<GateRunResult>> {
         // Phase 2: gates are currently implemented by reusing the bundle validator and mapping to gate IDs.
         // This keeps gate outputs stable and enforces the checklist semantics.
+        check("BUNDLE_VALIDATOR")?;
         let summary = BundleValidator::new_v3().validate_zip(bundle_zip, policy)?;
+        check("OFFLINE_ENFORCEMENT.ALLOWLIST_MATCH_V1")?;
         let (allowlist_result, allowlist_msg) = evaluate_offline_allowlist_gate(bundle_zip)?;
+        check("EVIDENCEOS.OUTPUTS_PRESENT_V1")?;
         let (evidence_outputs_result, evidence_outputs_msg) =
             evaluate_evidenceos_outputs_gate(bundle_zip)?;
+        check("EVIDENCEOS.MAPPING_REVIEW_PRESENT_V1")?;
         let (mapping_review_result, mapping_review_msg) =
             evaluate_evidenceos_mapping_review_gate(bundle_zip)?;
+        check("MODEL_CATALOG.ALLOWED_MODELS_V1")?;
+        let model_catalog_gate = evaluate_model_catalog_gate(bundle_zip, policy)?;
         Ok(map_validator_to_gates(
             &summary,
             &self.registry,
@@ -49,6 +88,7 @@ impl EvalRunner {
             (allowlist_result, allowlist_msg),
             (evidence_outputs_result, evidence_outputs_msg),
             (mapping_review_result, mapping_review_msg),
+            model_catalog_gate,
         ))
     }
 }
@@ -57,9 +97,10 @@ fn map_validator_to_gates(
     summary: &ValidationSummary,
     registry: &GateRegistry,
     policy: PolicyMode,
-    allowlist_gate: (String, String),
-    evidence_outputs_gate: (String, String),
-    mapping_review_gate: (String, String),
+    allowlist_gate: (GateStatus, String),
+    evidence_outputs_gate: (GateStatus, String),
+    mapping_review_gate: (GateStatus, String),
+    model_catalog_gate: (GateStatus, String),
 ) -> Vec<GateRunResult> {
     let policy_str = match policy {
         PolicyMode::STRICT => "STRICT",
@@ -67,6 +108,7 @@ fn map_validator_to_gates(
         PolicyMode::DRAFT_ONLY => "DRAFT_ONLY",
     };
 
+    let checked = |(result, msg): (CheckStatus, String)| (GateStatus::from(result), msg);
     let mut results = Vec::new();
     for g in &registry.gates {
         if !g.applies_to_policies.iter().any(|p| p == policy_str) {
@@ -74,32 +116,41 @@ fn map_validator_to_gates(
         }
         // Minimal mapping based on category/check IDs.
         let (result, msg) = match g.gate_id.as_str() {
-            "BUNDLE_FORMAT.REQUIRED_FILES_V1" => summary.result_for_checks_prefix("CHK.BUNDLE."),
+            "BUNDLE_FORMAT.REQUIRED_FILES_V1" => {
+                checked(summary.result_for_checks_prefix("CHK.BUNDLE."))
+            }
             "AUDIT_HASH_CHAIN.VERIFY_V1" => {
-                summary.result_for_check("CHK.AUDIT.REQUIRED_KEYS_AND_CHAIN")
+                checked(summary.result_for_check("CHK.AUDIT.REQUIRED_KEYS_AND_CHAIN"))
             }
             "OFFLINE_ENFORCEMENT.MODE_PROOF_V1" => {
-                summary.result_for_check("CHK.NETWORK.SNAPSHOT_PRESENT")
+                checked(summary.result_for_check("CHK.NETWORK.SNAPSHOT_PRESENT"))
             }
             "OFFLINE_ENFORCEMENT.ALLOWLIST_MATCH_V1" => allowlist_gate.clone(),
-            "CITATIONS.STRICT_ENFORCED_V1" => summary.result_for_check("CHK.CITATIONS.STRICT"),
+            "CITATIONS.STRICT_ENFORCED_V1" => {
+                checked(summary.result_for_check("CHK.CITATIONS.STRICT"))
+            }
             "REDACTION.REQUIRED_APPLIED_V1" => {
-                summary.result_for_check("CHK.REDACTION.POLICY_GATE")
+                checked(summary.result_for_check("CHK.REDACTION.POLICY_GATE"))
             }
-            "MODEL_PINNING.MIN_LEVEL_V1" => summary.result_for_check("CHK.MODEL.PINNING_LEVEL"),
+            "MODEL_PINNING.MIN_LEVEL_V1" => {
+                checked(summary.result_for_check("CHK.MODEL.PINNING_LEVEL"))
+            }
+            "MODEL_CATALOG.ALLOWED_MODELS_V1" => model_catalog_gate.clone(),
             "VAULT_CRYPTO.ENCRYPTION_AT_REST_V1" => (
-                summary.vault_crypto_gate_result(),
+                summary.vault_crypto_gate_result().into(),
                 summary.vault_crypto_message(),
             ),
-            "DETERMINISM.ZIP_PACKAGING_V1" => summary.result_for_check("CHK.DETERMINISM.ZIP_RULES"),
+            "DETERMINISM.ZIP_PACKAGING_V1" => {
+                checked(summary.result_for_check("CHK.DETERMINISM.ZIP_RULES"))
+            }
             "DETERMINISM.PDF_CAPABLE_V1" => (
-                "NOT_APPLICABLE".to_string(),
+                GateStatus::NOT_APPLICABLE,
                 "No PDFs in self-audit bundle".to_string(),
             ),
             "EVIDENCEOS.OUTPUTS_PRESENT_V1" => evidence_outputs_gate.clone(),
             "EVIDENCEOS.MAPPING_REVIEW_PRESENT_V1" => mapping_review_gate.clone(),
             _ => (
-                "NOT_APPLICABLE".to_string(),
+                GateStatus::NOT_APPLICABLE,
                 "Gate not implemented in Phase 2 runner".to_string(),
             ),
         };
@@ -107,7 +158,7 @@ fn map_validator_to_gates(
         results.push(GateRunResult {
             gate_id: g.gate_id.clone(),
             result,
-            severity: g.severity.clone(),
+            severity: g.severity,
             message: msg,
             evidence_pointers: g.evidence_required.clone(),
         });
@@ -116,18 +167,22 @@ fn map_validator_to_gates(
     results
 }
 
-fn evaluate_offline_allowlist_gate(bundle_zip: &Path) -> CoreResult<(String, String)> {
+fn evaluate_offline_allowlist_gate(bundle_zip: &Path) -> CoreResult<(GateStatus, String)> {
     let file = File::open(bundle_zip)?;
     let mut zip = ZipArchive::new(file).map_err(|e| crate::error::CoreError::Zip(e.to_string()))?;
-    let mut f = zip
-        .by_name("audit_log.ndjson")
-        .map_err(|e| crate::error::CoreError::Zip(e.to_string()))?;
     let mut ndjson = String::new();
-    f.read_to_string(&mut ndjson)?;
-    Ok(evaluate_offline_allowlist_from_ndjson(&ndjson))
+    zip.by_name("audit_log.ndjson")
+        .map_err(|e| crate::error::CoreError::Zip(e.to_string()))?
+        .read_to_string(&mut ndjson)?;
+    let recorded = zip_json(&mut zip, "inputs_snapshot/network_snapshot.json");
+    let snapshot: NetworkSnapshot = match recorded.and_then(|v| Ok(serde_json::from_value(v)?)) {
+        Ok(v) => v,
+        Err(e) => return Ok((GateStatus::FAIL, format!("invalid network snapshot: {}", e))),
+    };
+    Ok(evaluate_offline_allowlist_from_ndjson(&ndjson, &snapshot))
 }
 
-fn evaluate_evidenceos_outputs_gate(bundle_zip: &Path) -> CoreResult<(String, String)> {
+fn evaluate_evidenceos_outputs_gate(bundle_zip: &Path) -> CoreResult<(GateStatus, String)> {
     let required = [
         "exports/evidenceos/deliverables/evidence_index.csv",
         "exports/evidenceos/deliverables/evidence_index.md",
@@ -138,29 +193,29 @@ fn evaluate_evidenceos_outputs_gate(bundle_zip: &Path) -> CoreResult<(String, St
     let file = File::open(bundle_zip)?;
     let mut zip = ZipArchive::new(file).map_err(|e| crate::error::CoreError::Zip(e.to_string()))?;
     if !is_evidenceos_pack(&mut zip) {
-        return Ok(("NOT_APPLICABLE".to_string(), "not evidenceos pack".to_string()));
+        return Ok((GateStatus::NOT_APPLICABLE, "not evidenceos pack".to_string()));
     }
     for path in required {
         if zip.by_name(path).is_err() {
             return Ok((
-                "FAIL".to_string(),
+                GateStatus::FAIL,
                 format!("missing required EvidenceOS deliverable {}", path),
             ));
         }
     }
-    Ok(("PASS".to_string(), "ok".to_string()))
+    Ok((GateStatus::PASS, "ok".to_string()))
 }
 
-fn evaluate_evidenceos_mapping_review_gate(bundle_zip: &Path) -> CoreResult<(String, String)> {
+fn evaluate_evidenceos_mapping_review_gate(bundle_zip: &Path) -> CoreResult<(GateStatus, String)> {
     let path = "exports/evidenceos/deliverables/evidence_mapping_review.json";
     let file = File::open(bundle_zip)?;
     let mut zip = ZipArchive::new(file).map_err(|e| crate::error::CoreError::Zip(e.to_string()))?;
     if !is_evidenceos_pack(&mut zip) {
-        return Ok(("NOT_APPLICABLE".to_string(), "not evidenceos pack".to_string()));
+        return Ok((GateStatus::NOT_APPLICABLE, "not evidenceos pack".to_string()));
     }
     let mut f = match zip.by_name(path) {
         Ok(v) => v,
-        Err(_) => return Ok(("FAIL".to_string(), format!("missing {}", path))),
+        Err(_) => return Ok((GateStatus::FAIL, format!("missing {}", path))),
     };
     let mut body = String::new();
     f.read_to_string(&mut body)?;
@@ -168,7 +223,7 @@ fn evaluate_evidenceos_mapping_review_gate(bundle_zip: &Path) -> CoreResult<(Str
         Ok(v) => v,
         Err(e) => {
             return Ok((
-                "FAIL".to_string(),
+                GateStatus::FAIL,
                 format!("invalid JSON in {}: {}", path, e),
             ))
         }
@@ -179,11 +234,97 @@ fn evaluate_evidenceos_mapping_review_gate(bundle_zip: &Path) -> CoreResult<(Str
         .unwrap_or("");
     if schema != "EVIDENCE_MAPPING_REVIEW_V1" {
         return Ok((
-            "FAIL".to_string(),
+            GateStatus::FAIL,
             format!("unexpected schema_version {} in {}", schema, path),
         ));
     }
-    Ok(("PASS".to_string(), "ok".to_string()))
+    Ok((GateStatus::PASS, "ok".to_string()))
+}
+
+// Every model the bundle records must be one the policy pack's catalog permits for its pack.
+// Reported only: the export gate is where catalog violations block export.
+fn evaluate_model_catalog_gate(
+    bundle_zip: &Path,
+    policy: PolicyMode,
+) -> CoreResult<(GateStatus, String)> {
+    let file = File::open(bundle_zip)?;
+    let mut zip = ZipArchive::new(file).map_err(|e| crate::error::CoreError::Zip(e.to_string()))?;
+    let policy_snapshot = match zip_json(&mut zip, "inputs_snapshot/policy_snapshot.json") {
+        Ok(v) => v,
+        Err(e) => return Ok((GateStatus::FAIL, format!("invalid policy snapshot: {}", e))),
+    };
+    let Some(recorded) = policy_snapshot
+        .get("model_catalog")
+        .filter(|v| !v.is_null())
+    else {
+        return Ok((
+            GateStatus::NOT_APPLICABLE,
+            "no model catalog in policy snapshot".to_string(),
+        ));
+    };
+    let snapshot: ModelCatalogSnapshot = match serde_json::from_value(recorded.clone()) {
+        Ok(v) => v,
+        Err(e) => return Ok((GateStatus::FAIL, format!("invalid model catalog: {}", e))),
+    };
+    if let Err(e) = snapshot.verify() {
+        return Ok((GateStatus::FAIL, e.to_string()));
+    }
+    let pack_id = match zip_json(&mut zip, "BUNDLE_INFO.json") {
+        Ok(v) => v
+            .get("pack_id")
+            .and_then(|x| x.as_str())
+            .unwrap_or("")
+            .to_string(),
+        Err(e) => return Ok((GateStatus::FAIL, format!("invalid BUNDLE_INFO.json: {}", e))),
+    };
+    let model_snapshot: Option<ModelSnapshot> =
+        match zip_json(&mut zip, "inputs_snapshot/model_snapshot.json") {
+            Ok(v) => serde_json::from_value(v).ok(),
+            Err(e) => return Ok((GateStatus::FAIL, format!("invalid model snapshot: {}", e))),
+        };
+    let mut ndjson = String::new();
+    let read_log = zip
+        .by_name("audit_log.ndjson")
+        .map_err(|e| crate::error::CoreError::Zip(e.to_string()))
+        .and_then(|mut f| Ok(f.read_to_string(&mut ndjson)?));
+    if let Err(e) = read_log {
+        return Ok((GateStatus::FAIL, format!("unreadable audit log: {}", e)));
+    }
+    let uses = match collect_model_uses(model_snapshot.as_ref(), &ndjson) {
+        Ok(v) => v,
+        Err(e) => return Ok((GateStatus::FAIL, format!("unreadable audit log: {}", e))),
+    };
+    let violations = snapshot.catalog.violations(&pack_id, policy, &uses);
+    if violations.is_empty() {
+        return Ok((GateStatus::PASS, "ok".to_string()));
+    }
+    let listed: Vec<String> = violations
+        .iter()
+        .map(|v| {
+            format!(
+                "{} {:?} ({})",
+                v.model_use.model_id, v.reason, v.model_use.source
+            )
+        })
+        .collect();
+    Ok((
+        GateStatus::FAIL,
+        format!(
+            "{} model catalog violation(s) for pack {}: {}",
+            violations.len(),
+            pack_id,
+            listed.join(", ")
+        ),
+    ))
+}
+
+fn zip_json<R: Read + std::io::Seek>(zip: &mut ZipArchive<R>, path: &str) -> CoreResult<Value> {
+    let mut f = zip
+        .by_name(path)
+        .map_err(|e| crate::error::CoreError::Zip(e.to_string()))?;
+    let mut body = String::new();
+    f.read_to_string(&mut body)?;
+    Ok(serde_json::from_str(&body)?)
 }
 
 fn is_evidenceos_pack<R: Read + std::io::Seek>(zip: &mut ZipArchive<R>) -> bool {
@@ -206,16 +347,24 @@ fn is_evidenceos_pack<R: Read + std::io::Seek>(zip: &mut ZipArchive<R>) -> bool
         .unwrap_or(false)
 }
 
-fn evaluate_offline_allowlist_from_ndjson(ndjson: &str) -> (String, String) {
+// Allowed requests must name a rule of the bundled network snapshot by its content-derived id.
+fn evaluate_offline_allowlist_from_ndjson(
+    ndjson: &str,
+    snapshot: &NetworkSnapshot,
+) -> (GateStatus, String) {
     let mut seen_allowlist_updated = false;
     let mut blocked_count: usize = 0;
     let mut blocked_invalid_reasons: Vec<String> = Vec::new();
     let mut allowed_missing_rule_count: usize = 0;
+    let mut allowed_unknown_rule_ids: Vec<String> = Vec::new();
     let allowed_block_reasons = [
         "OFFLINE_MODE",
         "NOT_ALLOWLISTED",
         "UI_DIRECT_EGRESS_BLOCKED",
+        "QUOTA_EXCEEDED",
     ];
+    // (requests, body bytes) allowed per rule id, checked against the rule's quotas.
+    let mut allowed_usage: BTreeMap<String, (u64, u64)> = BTreeMap::new();
 
     for (line_no, line) in ndjson.lines().enumerate() {
         if line.trim().is_empty() {
@@ -225,7 +374,7 @@ fn evaluate_offline_allowlist_from_ndjson(ndjson: &str) -> (String, String) {
             Ok(v) => v,
             Err(e) => {
                 return (
-                    "FAIL".to_string(),
+                    GateStatus::FAIL,
                     format!("invalid audit_log.ndjson at line {}: {}", line_no + 1, e),
                 )
             }
@@ -254,6 +403,16 @@ fn evaluate_offline_allowlist_from_ndjson(ndjson: &str) -> (String, String) {
                     .unwrap_or_default();
                 if rule_id.is_empty() {
                     allowed_missing_rule_count += 1;
+                } else if snapshot.allowlist_rule(rule_id).is_none() {
+                    allowed_unknown_rule_ids.push(rule_id.to_string());
+                } else {
+                    let sent = v
+                        .pointer("/details/meta/request_bytes")
+                        .and_then(|x| x.as_u64())
+                        .unwrap_or(0);
+                    let usage = allowed_usage.entry(rule_id.to_string()).or_default();
+                    usage.0 += 1;
+                    usage.1 = usage.1.saturating_add(sent);
                 }
             }
             _ => {}
@@ -262,13 +421,13 @@ fn evaluate_offline_allowlist_from_ndjson(ndjson: &str) -> (String, String) {
 
     if !seen_allowlist_updated {
         return (
-            "FAIL".to_string(),
+            GateStatus::FAIL,
             "missing ALLOWLIST_UPDATED event".to_string(),
         );
     }
     if blocked_count == 0 {
         return (
-            "FAIL".to_string(),
+            GateStatus::FAIL,
             "no EGRESS_REQUEST_BLOCKED events recorded".to_string(),
         );
     }
@@ -276,7 +435,7 @@ fn evaluate_offline_allowlist_from_ndjson(ndjson: &str) -> (String, String) {
         blocked_invalid_reasons.sort();
         blocked_invalid_reasons.dedup();
         return (
-            "FAIL".to_string(),
+            GateStatus::FAIL,
             format!(
                 "invalid blocked reasons: {}",
                 blocked_invalid_reasons.join(", ")
@@ -285,35 +444,162 @@ fn evaluate_offline_allowlist_from_ndjson(ndjson: &str) -> (String, String) {
     }
     if allowed_missing_rule_count > 0 {
         return (
-            "FAIL".to_string(),
+            GateStatus::FAIL,
             format!(
                 "{} EGRESS_REQUEST_ALLOWED events missing allowlist_rule_id",
                 allowed_missing_rule_count
             ),
         );
     }
+    if !allowed_unknown_rule_ids.is_empty() {
+        allowed_unknown_rule_ids.sort();
+        allowed_unknown_rule_ids.dedup();
+        return (
+            GateStatus::FAIL,
+            format!(
+                "allowlist_rule_id not in network snapshot: {}",
+                allowed_unknown_rule_ids.join(", ")
+            ),
+        );
+    }
+    let over_quota: Vec<&str> = allowed_usage
+        .iter()
+        .filter(|(rule_id, (requests, bytes))| {
+            snapshot.allowlist_rule(rule_id).is_some_and(|e| {
+                e.max_requests_per_run.is_some_and(|max| *requests > max)
+                    || e.max_bytes_per_run.is_some_and(|max| *bytes > max)
+            })
+        })
+        .map(|(rule_id, _)| rule_id.as_str())
+        .collect();
+    if !over_quota.is_empty() {
+        return (
+            GateStatus::FAIL,
+            format!(
+                "allowed egress exceeds the quota of rule(s): {}",
+                over_quota.join(", ")
+            ),
+        );
+    }
 
-    ("PASS".to_string(), "ok".to_string())
+    (GateStatus::PASS, "ok".to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::evaluate_offline_allowlist_from_ndjson;
+    use super::{evaluate_offline_allowlist_from_ndjson, GateStatus};
+    use crate::policy::allowlist::AllowlistEntry;
+    use crate::policy::network_snapshot::NetworkSnapshot;
+    use crate::policy::types::{NetworkMode, ProofLevel};
+
+    fn snapshot(allowlist: Vec<AllowlistEntry>) -> NetworkSnapshot {
+        NetworkSnapshot {
+            network_mode: NetworkMode::ONLINE_ALLOWLISTED,
+            proof_level: ProofLevel::OFFLINE_STRICT,
+            allowlist,
+            ui_remote_fetch_disabled: true,
+            adapter_endpoints: vec![],
+        }
+    }
+
+    fn entry(host: &str) -> AllowlistEntry {
+        AllowlistEntry {
+            scheme: "https".to_string(),
+            host: host.to_string(),
+            port: 443,
+            path_prefix: None,
+            purpose: "updates".to_string(),
+            policy_pack_id: "default".to_string(),
+            policy_pack_version: "1.0.0".to_string(),
+            max_requests_per_run: None,
+            max_bytes_per_run: None,
+        }
+    }
 
     #[test]
     fn allowlist_gate_passes_when_blocked_and_allowlist_events_present() {
         let ndjson = r#"{"ts_utc":"2026-01-01T00:00:00Z","event_type":"ALLOWLIST_UPDATED","run_id":"r1","vault_id":"v1","actor":"system","details":{"allowlist_hash_sha256":"abc","allowlist_count":0},"prev_event_hash":"0000000000000000000000000000000000000000000000000000000000000000","event_hash":"1111111111111111111111111111111111111111111111111111111111111111"}
 {"ts_utc":"2026-01-01T00:00:01Z","event_type":"EGRESS_REQUEST_BLOCKED","run_id":"r1","vault_id":"v1","actor":"system","details":{"destination":{},"block_reason":"OFFLINE_MODE","request_hash_sha256":"abc"},"prev_event_hash":"1111111111111111111111111111111111111111111111111111111111111111","event_hash":"2222222222222222222222222222222222222222222222222222222222222222"}"#;
-        let (result, msg) = evaluate_offline_allowlist_from_ndjson(ndjson);
-        assert_eq!(result, "PASS");
+        let (result, msg) = evaluate_offline_allowlist_from_ndjson(ndjson, &snapshot(vec![]));
+        assert_eq!(result, GateStatus::PASS);
         assert_eq!(msg, "ok");
     }
 
     #[test]
     fn allowlist_gate_fails_when_no_blocked_events() {
         let ndjson = r#"{"ts_utc":"2026-01-01T00:00:00Z","event_type":"ALLOWLIST_UPDATED","run_id":"r1","vault_id":"v1","actor":"system","details":{"allowlist_hash_sha256":"abc","allowlist_count":0},"prev_event_hash":"0000000000000000000000000000000000000000000000000000000000000000","event_hash":"1111111111111111111111111111111111111111111111111111111111111111"}"#;
-        let (result, msg) = evaluate_offline_allowlist_from_ndjson(ndjson);
-        assert_eq!(result, "FAIL");
+        let (result, msg) = evaluate_offline_allowlist_from_ndjson(ndjson, &snapshot(vec![]));
+        assert_eq!(result, GateStatus::FAIL);
         assert!(msg.contains("no EGRESS_REQUEST_BLOCKED events"));
     }
+
+    #[test]
+    fn allowlist_gate_checks_allowed_rule_ids_against_snapshot() {
+        let updated = r#"{"event_type":"ALLOWLIST_UPDATED","details":{"allowlist_hash_sha256":"abc","allowlist_count":1}}"#;
+        let blocked = r#"{"event_type":"EGRESS_REQUEST_BLOCKED","details":{"block_reason":"NOT_ALLOWLISTED"}}"#;
+        let rule = entry("updates.example.com");
+        let allowed = |rule_id: &str| {
+            serde_json::json!({
+                "event_type": "EGRESS_REQUEST_ALLOWED",
+                "details": {"allowlist_rule_id": rule_id}
+            })
+            .to_string()
+        };
+        let ndjson = [updated, blocked, &allowed(&rule.rule_id().unwrap())].join("\n");
+
+        // Another rule ahead of it in the snapshot does not change the audited id.
+        let with_rule = snapshot(vec![entry("a.example.com"), rule.clone()]);
+        let (result, msg) = evaluate_offline_allowlist_from_ndjson(&ndjson, &with_rule);
+        assert_eq!(result, GateStatus::PASS, "{}", msg);
+
+        let (result, msg) = evaluate_offline_allowlist_from_ndjson(&ndjson, &snapshot(vec![]));
+        assert_eq!(result, GateStatus::FAIL);
+        assert!(msg.contains(&rule.rule_id().unwrap()), "{}", msg);
+
+        let stale = [updated, blocked, &allowed("ALW0000")].join("\n");
+        let (result, msg) = evaluate_offline_allowlist_from_ndjson(&stale, &with_rule);
+        assert_eq!(result, GateStatus::FAIL);
+        assert!(msg.contains("not in network snapshot: ALW0000"), "{}", msg);
+    }
+
+    #[test]
+    fn allowlist_gate_accepts_quota_blocks_but_not_allowed_requests_over_quota() {
+        let mut rule = entry("updates.example.com");
+        rule.max_requests_per_run = Some(1);
+        let rule_id = rule.rule_id().unwrap();
+        let event = |event_type: &str, details: serde_json::Value| {
+            serde_json::json!({"event_type": event_type, "details": details}).to_string()
+        };
+        let updated = event(
+            "ALLOWLIST_UPDATED",
+            serde_json::json!({"allowlist_hash_sha256": "abc", "allowlist_count": 1}),
+        );
+        let allowed = event(
+            "EGRESS_REQUEST_ALLOWED",
+            serde_json::json!({"allowlist_rule_id": rule_id, "meta": {"request_bytes": 10}}),
+        );
+        let quota_blocked = event(
+            "EGRESS_REQUEST_BLOCKED",
+            serde_json::json!({"block_reason": "QUOTA_EXCEEDED"}),
+        );
+        let with_rule = snapshot(vec![rule.clone()]);
+
+        let ndjson = [updated.as_str(), &allowed, &quota_blocked].join("\n");
+        let (result, msg) = evaluate_offline_allowlist_from_ndjson(&ndjson, &with_rule);
+        assert_eq!(result, GateStatus::PASS, "{}", msg);
+
+        let ndjson = [updated.as_str(), &allowed, &allowed, &quota_blocked].join("\n");
+        let (result, msg) = evaluate_offline_allowlist_from_ndjson(&ndjson, &with_rule);
+        assert_eq!(result, GateStatus::FAIL);
+        assert!(msg.contains("exceeds the quota"), "{}", msg);
+
+        // Quotas are not part of the id, so the same events now count against a byte quota.
+        rule.max_requests_per_run = None;
+        rule.max_bytes_per_run = Some(15);
+        assert_eq!(rule.rule_id().unwrap(), rule_id);
+        let ndjson = [updated.as_str(), &allowed, &allowed, &quota_blocked].join("\n");
+        let (result, msg) = evaluate_offline_allowlist_from_ndjson(&ndjson, &snapshot(vec![rule]));
+        assert_eq!(result, GateStatus::FAIL);
+        assert!(msg.contains(&rule_id), "{}", msg);
+    }
 }
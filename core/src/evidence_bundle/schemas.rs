@@ -1,4 +1,5 @@
 use crate::adapters::pinning::ModelSnapshot;
+use crate::policy::model_catalog::ModelCatalogSnapshot;
 use crate::policy::network_snapshot::NetworkSnapshot;
 use crate::policy::types::{InputExportProfile, PolicyMode};
 use serde::{Deserialize, Serialize};
@@ -78,6 +79,8 @@ pub struct PolicySnapshot {
     pub export_profile: ExportProfile,
     pub encryption_at_rest: bool,
     pub encryption_algorithm: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_catalog: Option<ModelCatalogSnapshot>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,18 +1,37 @@
+use crate::determinism::json_canonical;
+use crate::determinism::run_id::sha256_hex;
 use crate::error::{CoreError, CoreResult};
 use idna::domain_to_ascii;
 use serde::{Deserialize, Serialize};
-use url::Url;
+use std::net::IpAddr;
+use url::{Host, Url};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// One allowlisted destination. `host` takes one of four forms:
+///
+/// - a domain, `api.example.com`, matching only that host;
+/// - a wildcard, `*.example.com`, matching any subdomain of `example.com` but not
+///   `example.com` itself; the suffix needs at least two labels;
+/// - an IP address, `10.0.0.5` or `fd00::5`, matching only that address;
+/// - a CIDR block, `10.20.0.0/16` or `fd00::/8`, matching addresses inside it. Host bits
+///   must be zero.
+///
+/// Domains never match IP hosts and vice versa, and IPv4 rules do not match IPv4-mapped
+/// IPv6 hosts. When several rules match a request, `best_match` picks one by precedence.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct AllowlistEntry {
     pub scheme: String, // http|https
-    pub host: String,   // ASCII; punycode normalized
+    pub host: String,   // ASCII; punycode normalized; IPv6 without brackets
     pub port: u16,      // explicit
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path_prefix: Option<String>,
     pub purpose: String,
     pub policy_pack_id: String,
     pub policy_pack_version: String,
+    // Per-run limits on requests this rule allows and on their total body size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_requests_per_run: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes_per_run: Option<u64>,
 }
 
 impl AllowlistEntry {
@@ -25,9 +44,7 @@ impl AllowlistEntry {
         }
         self.scheme = scheme;
 
-        let host_ascii = domain_to_ascii(&self.host)
-            .map_err(|_| CoreError::InvalidInput("invalid allowlist host".to_string()))?;
-        self.host = host_ascii.to_ascii_lowercase();
+        self.host = parse_host_rule(&self.host)?.to_string();
 
         if self.port == 0 {
             self.port = if self.scheme == "https" { 443 } else { 80 };
@@ -46,22 +63,60 @@ impl AllowlistEntry {
             self.path_prefix = Some(p);
         }
 
+        if self.max_requests_per_run == Some(0) || self.max_bytes_per_run == Some(0) {
+            return Err(CoreError::InvalidInput(
+                "allowlist quotas must be positive; remove the rule instead".to_string(),
+            ));
+        }
+
         Ok(self)
     }
 
+    /// Id recorded as `allowlist_rule_id` when this rule allows a request: `ALW-` followed by the
+    /// first 16 hex digits of the SHA-256 of the canonical entry without its quotas. It depends
+    /// only on what the rule allows, so adding or removing other rules never changes it, and
+    /// neither does adjusting its quotas: usage already counted against the id carries over.
+    pub fn rule_id(&self) -> CoreResult<String> {
+        let mut canonical = self.clone().canonicalize()?;
+        canonical.max_requests_per_run = None;
+        canonical.max_bytes_per_run = None;
+        let digest = sha256_hex(&json_canonical::to_canonical_bytes(&canonical)?);
+        Ok(format!("ALW-{}", &digest[..16]))
+    }
+
+    /// Whether this rule allows `url`. URLs carrying userinfo never match, so
+    /// `https://api.example.com@evil.example/` cannot pass for the rule's host.
     pub fn matches_url(&self, url: &Url) -> bool {
+        if !url.username().is_empty() || url.password().is_some() {
+            return false;
+        }
         let scheme = url.scheme().to_ascii_lowercase();
-        let host = match url.host_str() {
-            Some(h) => match domain_to_ascii(h) {
-                Ok(x) => x.to_ascii_lowercase(),
-                Err(_) => return false,
-            },
-            None => return false,
-        };
         let port = url
             .port_or_known_default()
             .unwrap_or(if scheme == "https" { 443 } else { 80 });
-        if scheme != self.scheme || host != self.host || port != self.port {
+        if scheme != self.scheme || port != self.port {
+            return false;
+        }
+        let Ok(rule) = parse_host_rule(&self.host) else {
+            return false;
+        };
+        let host_matches = match (&rule, url.host()) {
+            (HostRule::Domain(d), Some(Host::Domain(h))) => {
+                normalize_domain(h).is_some_and(|h| h == *d)
+            }
+            (HostRule::Wildcard(suffix), Some(Host::Domain(h))) => {
+                normalize_domain(h).is_some_and(|h| {
+                    h.strip_suffix(suffix.as_str())
+                        .is_some_and(|l| !l.is_empty())
+                })
+            }
+            (HostRule::Ip(ip), Some(Host::Ipv4(a))) => *ip == IpAddr::V4(a),
+            (HostRule::Ip(ip), Some(Host::Ipv6(a))) => *ip == IpAddr::V6(a),
+            (HostRule::Cidr(net, len), Some(Host::Ipv4(a))) => in_cidr(*net, *len, IpAddr::V4(a)),
+            (HostRule::Cidr(net, len), Some(Host::Ipv6(a))) => in_cidr(*net, *len, IpAddr::V6(a)),
+            _ => false,
+        };
+        if !host_matches {
             return false;
         }
         if let Some(pp) = &self.path_prefix {
@@ -69,4 +124,133 @@ impl AllowlistEntry {
         }
         true
     }
+
+    // Higher is more specific: exact hosts, then CIDR blocks by prefix length, then wildcards
+    // by suffix length; a longer `path_prefix` breaks ties.
+    fn precedence(&self) -> (u8, usize, usize) {
+        let host = match parse_host_rule(&self.host) {
+            Ok(HostRule::Domain(_)) | Ok(HostRule::Ip(_)) => (3, 0),
+            Ok(HostRule::Cidr(_, len)) => (2, usize::from(len)),
+            Ok(HostRule::Wildcard(suffix)) => (1, suffix.split('.').count()),
+            Err(_) => (0, 0),
+        };
+        let path = self.path_prefix.as_deref().map_or(0, str::len);
+        (host.0, host.1, path)
+    }
+}
+
+/// The rule that allows `url`: the most specific match by host kind and `path_prefix`, and
+/// the earliest in `entries` among equally specific ones.
+pub fn best_match<'a>(entries: &'a [AllowlistEntry], url: &Url) -> Option<&'a AllowlistEntry> {
+    let mut best: Option<&AllowlistEntry> = None;
+    for e in entries.iter().filter(|e| e.matches_url(url)) {
+        if best.is_none_or(|b| e.precedence() > b.precedence()) {
+            best = Some(e);
+        }
+    }
+    best
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostRule {
+    Domain(String),
+    Wildcard(String), // the suffix, with its leading dot: ".example.com"
+    Ip(IpAddr),
+    Cidr(IpAddr, u8),
+}
+
+impl std::fmt::Display for HostRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HostRule::Domain(d) => write!(f, "{}", d),
+            HostRule::Wildcard(suffix) => write!(f, "*{}", suffix),
+            HostRule::Ip(ip) => write!(f, "{}", ip),
+            HostRule::Cidr(net, len) => write!(f, "{}/{}", net, len),
+        }
+    }
+}
+
+fn parse_host_rule(host: &str) -> CoreResult<HostRule> {
+    let invalid =
+        |why: &str| CoreError::InvalidInput(format!("invalid allowlist host {}: {}", host, why));
+    let trimmed = host.trim();
+    if let Some((addr, len)) = trimmed.split_once('/') {
+        let net: IpAddr = unbracket(addr)
+            .parse()
+            .map_err(|_| invalid("CIDR base is not an IP address"))?;
+        let max = if net.is_ipv4() { 32 } else { 128 };
+        let len: u8 = len
+            .parse()
+            .ok()
+            .filter(|l| *l <= max)
+            .ok_or_else(|| invalid("bad CIDR prefix length"))?;
+        if mask(net, len) != net {
+            return Err(invalid("CIDR block has host bits set"));
+        }
+        return Ok(HostRule::Cidr(net, len));
+    }
+    if let Ok(ip) = unbracket(trimmed).parse::<IpAddr>() {
+        return Ok(HostRule::Ip(ip));
+    }
+    let (wildcard, domain) = match trimmed.strip_prefix("*.") {
+        Some(rest) => (true, rest),
+        None => (false, trimmed),
+    };
+    if domain.contains('*') {
+        return Err(invalid("wildcards are only allowed as a leading *. label"));
+    }
+    let domain = normalize_domain(domain).ok_or_else(|| invalid("not a domain name"))?;
+    if domain.parse::<IpAddr>().is_ok() {
+        return Err(invalid("wildcards do not apply to IP addresses"));
+    }
+    if !wildcard {
+        return Ok(HostRule::Domain(domain));
+    }
+    if domain.split('.').count() < 2 {
+        return Err(invalid("wildcard suffix needs at least two labels"));
+    }
+    Ok(HostRule::Wildcard(format!(".{}", domain)))
+}
+
+// Punycode-normalized, lowercase, no empty labels.
+fn normalize_domain(domain: &str) -> Option<String> {
+    let ascii = domain_to_ascii(domain).ok()?.to_ascii_lowercase();
+    if ascii.is_empty() || ascii.split('.').any(str::is_empty) {
+        return None;
+    }
+    Some(ascii)
+}
+
+fn unbracket(host: &str) -> &str {
+    host.strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host)
+}
+
+fn mask(addr: IpAddr, len: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(a) => {
+            let bits = u32::from(a) & u32::MAX.checked_shl(32 - u32::from(len)).unwrap_or(0);
+            IpAddr::V4(bits.into())
+        }
+        IpAddr::V6(a) => {
+            let bits = u128::from(a) & u128::MAX.checked_shl(128 - u32::from(len)).unwrap_or(0);
+            IpAddr::V6(bits.into())
+        }
+    }
+}
+
+fn in_cidr(net: IpAddr, len: u8, addr: IpAddr) -> bool {
+    net.is_ipv4() == addr.is_ipv4() && mask(addr, len) == net
+}
+
+/// `allowlist_hash_sha256` of ALLOWLIST_UPDATED: the SHA-256 of the canonical JSON of the
+/// canonicalized entries, sorted.
+pub fn allowlist_hash_sha256(entries: &[AllowlistEntry]) -> CoreResult<String> {
+    let mut canonical = entries
+        .iter()
+        .map(|e| e.clone().canonicalize())
+        .collect::<CoreResult<Vec<_>>>()?;
+    canonical.sort();
+    Ok(sha256_hex(&json_canonical::to_canonical_bytes(&canonical)?))
 }
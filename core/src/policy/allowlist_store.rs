@@ -0,0 +1,245 @@
+use crate::audit::event::{Actor, AuditEvent};
+use crate::audit::log::AuditLog;
+use crate::determinism::json_canonical;
+use crate::error::{CoreError, CoreResult};
+use crate::policy::allowlist::{allowlist_hash_sha256, AllowlistEntry};
+use crate::storage::vault::VaultStorage;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeSet;
+
+// Kept in the vault blob store next to the run registry, so it is encrypted at rest.
+const ALLOWLIST_BLOB_ID: &str = "egress_allowlist";
+const ALLOWLIST_SCHEMA_VERSION: &str = "EGRESS_ALLOWLIST_V1";
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AllowlistChange {
+    ADDED,
+    REMOVED,
+    REPLACED,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AllowlistDocument {
+    schema_version: String,
+    entries: Vec<AllowlistEntry>, // canonical; sorted
+}
+
+/// The vault's egress allowlist.
+///
+/// Every change is recorded as ALLOWLIST_UPDATED with the hash and count of the resulting
+/// allowlist, then persisted; a change that cannot be audited is not made. Which rules changed,
+/// and the policy packs they belong to, go under `meta.allowlist_change`.
+pub struct AllowlistStore {
+    vault: VaultStorage,
+    entries: Vec<AllowlistEntry>,
+}
+
+impl AllowlistStore {
+    pub fn open(vault: VaultStorage) -> CoreResult<Self> {
+        let mut entries = Vec::new();
+        if vault.blob_exists(ALLOWLIST_BLOB_ID) {
+            let doc: AllowlistDocument =
+                serde_json::from_slice(&vault.read_blob(ALLOWLIST_BLOB_ID)?)?;
+            if doc.schema_version != ALLOWLIST_SCHEMA_VERSION {
+                return Err(CoreError::InputSchemaError(format!(
+                    "unsupported allowlist schema_version {}",
+                    doc.schema_version
+                )));
+            }
+            entries = normalize(doc.entries)?;
+        }
+        Ok(Self { vault, entries })
+    }
+
+    pub fn vault(&self) -> &VaultStorage {
+        &self.vault
+    }
+
+    /// Canonical entries, sorted; what an `EgressPolicy` or `NetworkSnapshot` should carry.
+    pub fn entries(&self) -> &[AllowlistEntry] {
+        &self.entries
+    }
+
+    pub fn hash_sha256(&self) -> CoreResult<String> {
+        allowlist_hash_sha256(&self.entries)
+    }
+
+    /// Adds `entry` and returns its rule id. Fails if a rule with the same id is already present;
+    /// use `replace` to change an existing rule's quotas.
+    pub fn add(
+        &mut self,
+        entry: AllowlistEntry,
+        audit: &mut AuditLog,
+        run_id: &str,
+    ) -> CoreResult<String> {
+        let entry = attributed(entry)?;
+        let rule_id = entry.rule_id()?;
+        if self
+            .entries
+            .iter()
+            .any(|e| e.rule_id().is_ok_and(|id| id == rule_id))
+        {
+            return Err(CoreError::InvalidInput(format!(
+                "allowlist rule {} already present",
+                rule_id
+            )));
+        }
+        let mut entries = self.entries.clone();
+        entries.push(entry.clone());
+        self.commit(
+            entries,
+            AllowlistChange::ADDED,
+            &[entry],
+            &[],
+            audit,
+            run_id,
+        )?;
+        Ok(rule_id)
+    }
+
+    /// Removes the rule with `rule_id` and returns it.
+    pub fn remove(
+        &mut self,
+        rule_id: &str,
+        audit: &mut AuditLog,
+        run_id: &str,
+    ) -> CoreResult<AllowlistEntry> {
+        let mut entries = self.entries.clone();
+        let idx = entries
+            .iter()
+            .position(|e| e.rule_id().is_ok_and(|id| id == rule_id))
+            .ok_or_else(|| {
+                CoreError::InvalidInput(format!("allowlist rule not found: {}", rule_id))
+            })?;
+        let removed = entries.remove(idx);
+        self.commit(
+            entries,
+            AllowlistChange::REMOVED,
+            &[],
+            std::slice::from_ref(&removed),
+            audit,
+            run_id,
+        )?;
+        Ok(removed)
+    }
+
+    /// Replaces the whole allowlist, e.g. when a policy pack is installed or upgraded.
+    pub fn replace(
+        &mut self,
+        entries: Vec<AllowlistEntry>,
+        audit: &mut AuditLog,
+        run_id: &str,
+    ) -> CoreResult<()> {
+        let entries = normalize(entries)?;
+        let added: Vec<AllowlistEntry> = entries
+            .iter()
+            .filter(|e| !self.entries.contains(e))
+            .cloned()
+            .collect();
+        let removed: Vec<AllowlistEntry> = self
+            .entries
+            .iter()
+            .filter(|e| !entries.contains(e))
+            .cloned()
+            .collect();
+        self.commit(
+            entries,
+            AllowlistChange::REPLACED,
+            &added,
+            &removed,
+            audit,
+            run_id,
+        )
+    }
+
+    fn commit(
+        &mut self,
+        mut entries: Vec<AllowlistEntry>,
+        change: AllowlistChange,
+        added: &[AllowlistEntry],
+        removed: &[AllowlistEntry],
+        audit: &mut AuditLog,
+        run_id: &str,
+    ) -> CoreResult<()> {
+        entries.sort();
+        let doc = AllowlistDocument {
+            schema_version: ALLOWLIST_SCHEMA_VERSION.to_string(),
+            entries,
+        };
+        let bytes = json_canonical::to_canonical_bytes(&doc)?;
+
+        let rule_ids = |list: &[AllowlistEntry]| -> CoreResult<Vec<String>> {
+            let mut ids = list
+                .iter()
+                .map(AllowlistEntry::rule_id)
+                .collect::<CoreResult<Vec<_>>>()?;
+            ids.sort();
+            Ok(ids)
+        };
+        let policy_packs: BTreeSet<(&str, &str)> = added
+            .iter()
+            .chain(removed)
+            .map(|e| (e.policy_pack_id.as_str(), e.policy_pack_version.as_str()))
+            .collect();
+        let policy_packs: Vec<_> = policy_packs
+            .into_iter()
+            .map(|(id, version)| json!({"policy_pack_id": id, "policy_pack_version": version}))
+            .collect();
+        audit.append(AuditEvent {
+            ts_utc: String::new(),
+            event_type: "ALLOWLIST_UPDATED".to_string(),
+            run_id: run_id.to_string(),
+            vault_id: self.vault.vault_id().to_string(),
+            actor: Actor::User,
+            details: json!({
+                "allowlist_hash_sha256": allowlist_hash_sha256(&doc.entries)?,
+                "allowlist_count": doc.entries.len(),
+                "meta": {
+                    "allowlist_change": {
+                        "change": change,
+                        "added_rule_ids": rule_ids(added)?,
+                        "removed_rule_ids": rule_ids(removed)?,
+                        "policy_packs": policy_packs
+                    }
+                }
+            }),
+            prev_event_hash: String::new(),
+            event_hash: String::new(),
+        })?;
+        self.vault.write_blob(ALLOWLIST_BLOB_ID, &bytes)?;
+        self.entries = doc.entries;
+        Ok(())
+    }
+}
+
+// Rules must say which policy pack they come from.
+fn attributed(entry: AllowlistEntry) -> CoreResult<AllowlistEntry> {
+    let entry = entry.canonicalize()?;
+    if entry.policy_pack_id.is_empty() || entry.policy_pack_version.is_empty() {
+        return Err(CoreError::InvalidInput(
+            "allowlist entry must name its policy pack and version".to_string(),
+        ));
+    }
+    Ok(entry)
+}
+
+fn normalize(entries: Vec<AllowlistEntry>) -> CoreResult<Vec<AllowlistEntry>> {
+    let mut entries = entries
+        .into_iter()
+        .map(attributed)
+        .collect::<CoreResult<Vec<_>>>()?;
+    entries.sort();
+    // Entries differing only in their quotas share a rule id, and sort next to each other.
+    for pair in entries.windows(2) {
+        let rule_id = pair[0].rule_id()?;
+        if pair[1].rule_id()? == rule_id {
+            return Err(CoreError::InvalidInput(format!(
+                "duplicate allowlist rule {}",
+                rule_id
+            )));
+        }
+    }
+    Ok(entries)
+}
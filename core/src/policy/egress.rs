@@ -1,10 +1,17 @@
 use crate::audit::event::{Actor, AuditEvent};
 use crate::audit::log::AuditLog;
-use crate::error::CoreResult;
-use crate::policy::allowlist::AllowlistEntry;
+use crate::error::{CoreError, CoreResult};
+use crate::policy::allowlist::{best_match, AllowlistEntry};
 use crate::policy::types::{NetworkMode, ProofLevel};
-use serde_json::json;
+use rustls::pki_types::{CertificateDer, ServerName};
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use url::Url;
 
 #[derive(Debug, Clone)]
@@ -20,26 +27,92 @@ pub enum EgressDecision {
     Blocked { reason: String },
 }
 
+/// Gate for outbound requests. `send` is the way to reach a network destination: it decides
+/// the request against the policy and records the attempt before anything leaves the process.
 pub struct EgressClient<'a> {
     pub policy: EgressPolicy,
     pub audit: &'a mut AuditLog,
     pub run_id: String,
     pub vault_id: String,
+    extra_roots: Vec<Vec<u8>>,
+    usage: Option<BTreeMap<String, (u64, u64)>>, // requests and bytes per rule id
 }
 
 impl<'a> EgressClient<'a> {
-    pub fn decide(&self, url: &Url) -> CoreResult<EgressDecision> {
+    pub fn new(
+        policy: EgressPolicy,
+        audit: &'a mut AuditLog,
+        run_id: &str,
+        vault_id: &str,
+    ) -> Self {
+        Self {
+            policy,
+            audit,
+            run_id: run_id.to_string(),
+            vault_id: vault_id.to_string(),
+            extra_roots: Vec::new(),
+            usage: None,
+        }
+    }
+
+    /// Trusts the DER-encoded CA certificate `der` for https, next to the bundled web PKI roots.
+    pub fn with_root_certificate(mut self, der: Vec<u8>) -> Self {
+        self.extra_roots.push(der);
+        self
+    }
+
+    /// Decides the request, records the attempt as EGRESS_REQUEST_ALLOWED/BLOCKED, then performs
+    /// it. Blocked requests fail with `PolicyBlocked` without opening a connection. Requests that
+    /// could never be sent (unsupported scheme, malformed method or headers) fail with
+    /// `InvalidInput` before they are decided, so they are neither recorded nor counted against
+    /// a quota.
+    pub fn send(
+        &mut self,
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: &[u8],
+        timeout: Duration,
+    ) -> CoreResult<HttpResponse> {
+        let url = Url::parse(url)
+            .map_err(|_| CoreError::InvalidInput(format!("invalid egress URL {}", url)))?;
+        check_request(method, &url, headers)?;
+        let decision = self.decide(&url, body)?;
+        self.record_attempt(&url, &decision, body)?;
+        match decision {
+            EgressDecision::Allowed { .. } => {
+                exchange(method, &url, headers, body, timeout, &self.extra_roots)
+            }
+            EgressDecision::Blocked { reason } => Err(CoreError::PolicyBlocked(format!(
+                "egress to {} blocked: {}",
+                url, reason
+            ))),
+        }
+    }
+
+    /// Decides a request to `url` carrying `request_bytes`. The most specific matching rule
+    /// allows it unless the rule's per-run quota is used up, which blocks it as QUOTA_EXCEEDED.
+    pub fn decide(&mut self, url: &Url, request_bytes: &[u8]) -> CoreResult<EgressDecision> {
         match self.policy.network_mode {
             NetworkMode::OFFLINE => Ok(EgressDecision::Blocked {
                 reason: "OFFLINE_MODE".to_string(),
             }),
             NetworkMode::ONLINE_ALLOWLISTED => {
-                for (idx, e) in self.policy.allowlist.iter().enumerate() {
-                    if e.matches_url(url) {
-                        return Ok(EgressDecision::Allowed {
-                            allowlist_rule_id: format!("ALW{:04}", idx),
-                        });
+                if let Some(e) = best_match(&self.policy.allowlist, url) {
+                    let allowlist_rule_id = e.rule_id()?;
+                    let (max_requests, max_bytes) = (e.max_requests_per_run, e.max_bytes_per_run);
+                    if max_requests.is_some() || max_bytes.is_some() {
+                        let (requests, bytes) = self.rule_usage(&allowlist_rule_id)?;
+                        let sent = bytes.saturating_add(request_bytes.len() as u64);
+                        if max_requests.is_some_and(|max| requests >= max)
+                            || max_bytes.is_some_and(|max| sent > max)
+                        {
+                            return Ok(EgressDecision::Blocked {
+                                reason: "QUOTA_EXCEEDED".to_string(),
+                            });
+                        }
                     }
+                    return Ok(EgressDecision::Allowed { allowlist_rule_id });
                 }
                 Ok(EgressDecision::Blocked {
                     reason: "NOT_ALLOWLISTED".to_string(),
@@ -68,7 +141,7 @@ impl<'a> EgressClient<'a> {
         match decision {
             EgressDecision::Allowed { allowlist_rule_id } => {
                 self.audit.append(AuditEvent {
-                    ts_utc: now_rfc3339_utc(),
+                    ts_utc: String::new(),
                     event_type: "EGRESS_REQUEST_ALLOWED".to_string(),
                     run_id: self.run_id.clone(),
                     vault_id: self.vault_id.clone(),
@@ -76,15 +149,21 @@ impl<'a> EgressClient<'a> {
                     details: json!({
                         "destination": destination,
                         "allowlist_rule_id": allowlist_rule_id,
-                        "request_hash_sha256": request_hash_sha256
+                        "request_hash_sha256": request_hash_sha256,
+                        "meta": {"request_bytes": request_bytes.len()}
                     }),
                     prev_event_hash: "".to_string(),
                     event_hash: "".to_string(),
                 })?;
+                if let Some(usage) = self.usage.as_mut() {
+                    let (requests, bytes) = usage.entry(allowlist_rule_id.clone()).or_default();
+                    *requests += 1;
+                    *bytes = bytes.saturating_add(request_bytes.len() as u64);
+                }
             }
             EgressDecision::Blocked { reason } => {
                 self.audit.append(AuditEvent {
-                    ts_utc: now_rfc3339_utc(),
+                    ts_utc: String::new(),
                     event_type: "EGRESS_REQUEST_BLOCKED".to_string(),
                     run_id: self.run_id.clone(),
                     vault_id: self.vault_id.clone(),
@@ -101,12 +180,477 @@ impl<'a> EgressClient<'a> {
         }
         Ok(())
     }
+
+    // Requests and body bytes this run has sent under `rule_id` so far. The audit log is the
+    // only record that outlives the client, so quotas hold across clients and restarts of the
+    // same run: its EGRESS_REQUEST_ALLOWED events seed the counters once, and attempts recorded
+    // through this client keep them current.
+    fn rule_usage(&mut self, rule_id: &str) -> CoreResult<(u64, u64)> {
+        if self.usage.is_none() {
+            self.usage = Some(self.usage_from_log()?);
+        }
+        Ok(self
+            .usage
+            .as_ref()
+            .and_then(|u| u.get(rule_id))
+            .copied()
+            .unwrap_or_default())
+    }
+
+    fn usage_from_log(&self) -> CoreResult<BTreeMap<String, (u64, u64)>> {
+        let ndjson = std::fs::read_to_string(self.audit.path())?;
+        let mut usage: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+        for line in ndjson.lines().filter(|l| !l.trim().is_empty()) {
+            let v: Value = serde_json::from_str(line)?;
+            if v["event_type"] != "EGRESS_REQUEST_ALLOWED" || v["run_id"] != self.run_id.as_str() {
+                continue;
+            }
+            let Some(rule_id) = v["details"]["allowlist_rule_id"].as_str() else {
+                continue;
+            };
+            let (requests, bytes) = usage.entry(rule_id.to_string()).or_default();
+            *requests += 1;
+            let sent = v.pointer("/details/meta/request_bytes");
+            *bytes = bytes.saturating_add(sent.and_then(Value::as_u64).unwrap_or(0));
+        }
+        Ok(usage)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// HTTP/1.1 exchange over a fresh connection, with TLS for https URLs. This is the only place in
+/// core that opens sockets. Callers either target a loopback adapter (Annex B.1, outside the
+/// egress boundary) or have decided and recorded the attempt through `EgressClient` first.
+pub(crate) fn http_exchange(
+    method: &str,
+    url: &Url,
+    headers: &[(&str, &str)],
+    body: &[u8],
+    timeout: Duration,
+) -> CoreResult<HttpResponse> {
+    exchange(method, url, headers, body, timeout, &[])
+}
+
+fn exchange(
+    method: &str,
+    url: &Url,
+    headers: &[(&str, &str)],
+    body: &[u8],
+    timeout: Duration,
+    extra_roots: &[Vec<u8>],
+) -> CoreResult<HttpResponse> {
+    let mut conn = send_request(method, url, headers, body, timeout, extra_roots)?;
+    conn.socket().set_read_timeout(Some(timeout))?;
+    let mut raw = Vec::new();
+    match conn.read_to_end(&mut raw) {
+        Ok(_) => {}
+        // Many servers close TLS without close_notify; the body framing check below still
+        // rejects a truncated response.
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof && !raw.is_empty() => {}
+        Err(e) => return Err(e.into()),
+    }
+    parse_http_response(&raw)
+}
+
+/// Plain TCP, or TLS for https URLs.
+enum Connection {
+    Plain(TcpStream),
+    Tls(Box<StreamOwned<ClientConnection, TcpStream>>),
+}
+
+impl Connection {
+    fn socket(&self) -> &TcpStream {
+        match self {
+            Connection::Plain(s) => s,
+            Connection::Tls(s) => &s.sock,
+        }
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Connection::Plain(s) => s.read(buf),
+            Connection::Tls(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Connection::Plain(s) => s.write(buf),
+            Connection::Tls(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Connection::Plain(s) => s.flush(),
+            Connection::Tls(s) => s.flush(),
+        }
+    }
+}
+
+// Web PKI roots plus `extra_roots`, with the ring provider's default protocol versions.
+fn tls_config(extra_roots: &[Vec<u8>]) -> CoreResult<Arc<ClientConfig>> {
+    let mut roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    for der in extra_roots {
+        roots
+            .add(CertificateDer::from(der.clone()))
+            .map_err(|e| CoreError::InvalidInput(format!("invalid TLS root certificate: {}", e)))?;
+    }
+    let config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| CoreError::InvalidInput(format!("TLS configuration: {}", e)))?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+    Ok(Arc::new(config))
+}
+
+// Upper bound on a single blocking read while streaming, so cancellation is noticed promptly.
+const STREAM_POLL: Duration = Duration::from_millis(50);
+
+/// Response whose body is read incrementally as the peer sends it. Reads fail with
+/// `ErrorKind::TimedOut` once the stream deadline passes, and with an `Other` error as soon as
+/// `cancelled` returns true.
+pub struct HttpStream {
+    pub status: u16,
+    reader: BufReader<DeadlineSocket>,
+    framing: BodyFraming,
+}
+
+impl HttpStream {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+enum BodyFraming {
+    Chunked {
+        remaining: usize,
+        started: bool,
+        done: bool,
+    },
+    Length(usize),
+    UntilClose,
+}
+
+impl Read for HttpStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match &mut self.framing {
+            BodyFraming::UntilClose => self.reader.read(buf),
+            BodyFraming::Length(left) => {
+                let n = buf.len().min(*left);
+                if n == 0 {
+                    return Ok(0);
+                }
+                let n = self.reader.read(&mut buf[..n])?;
+                *left -= n;
+                Ok(n)
+            }
+            BodyFraming::Chunked {
+                remaining,
+                started,
+                done,
+            } => {
+                if *done {
+                    return Ok(0);
+                }
+                if *remaining == 0 {
+                    if *started {
+                        read_line(&mut self.reader)?; // CRLF closing the previous chunk
+                    }
+                    *started = true;
+                    let line = read_line(&mut self.reader)?;
+                    let size = line
+                        .split(';')
+                        .next()
+                        .and_then(|h| usize::from_str_radix(h.trim(), 16).ok())
+                        .ok_or_else(|| invalid_data("malformed chunk size"))?;
+                    if size == 0 {
+                        *done = true;
+                        return Ok(0);
+                    }
+                    *remaining = size;
+                }
+                let n = buf.len().min(*remaining);
+                let n = self.reader.read(&mut buf[..n])?;
+                if n == 0 {
+                    return Err(std::io::Error::from(ErrorKind::UnexpectedEof));
+                }
+                *remaining -= n;
+                Ok(n)
+            }
+        }
+    }
+}
+
+struct DeadlineSocket {
+    stream: Connection,
+    deadline: Instant,
+    cancelled: Box<dyn Fn() -> bool + Send>,
+}
+
+impl Read for DeadlineSocket {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if (self.cancelled)() {
+                return Err(std::io::Error::other("stream cancelled"));
+            }
+            let left = self.deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Err(std::io::Error::new(
+                    ErrorKind::TimedOut,
+                    "stream deadline exceeded",
+                ));
+            }
+            self.stream
+                .socket()
+                .set_read_timeout(Some(left.min(STREAM_POLL)))?;
+            match self.stream.read(buf) {
+                Err(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {}
+                other => return other,
+            }
+        }
+    }
+}
+
+/// Streaming variant of `http_exchange` with the same connection rules. `connect_timeout` bounds
+/// connecting and sending; `stream_timeout` bounds everything from sending to the last body byte.
+pub(crate) fn http_stream(
+    method: &str,
+    url: &Url,
+    headers: &[(&str, &str)],
+    body: &[u8],
+    connect_timeout: Duration,
+    stream_timeout: Duration,
+    cancelled: Box<dyn Fn() -> bool + Send>,
+) -> CoreResult<HttpStream> {
+    let stream = send_request(method, url, headers, body, connect_timeout, &[])?;
+    let mut reader = BufReader::new(DeadlineSocket {
+        stream,
+        deadline: Instant::now() + stream_timeout,
+        cancelled,
+    });
+    let mut head = String::new();
+    loop {
+        let line = read_line(&mut reader)?;
+        if line.is_empty() {
+            break;
+        }
+        head.push_str(&line);
+        head.push_str("\r\n");
+    }
+    let (status, chunked, content_length) = parse_head(&head)?;
+    let framing = match (chunked, content_length) {
+        (true, _) => BodyFraming::Chunked {
+            remaining: 0,
+            started: false,
+            done: false,
+        },
+        (false, Some(n)) => BodyFraming::Length(n),
+        (false, None) => BodyFraming::UntilClose,
+    };
+    Ok(HttpStream {
+        status,
+        reader,
+        framing,
+    })
+}
+
+fn send_request(
+    method: &str,
+    url: &Url,
+    headers: &[(&str, &str)],
+    body: &[u8],
+    timeout: Duration,
+    extra_roots: &[Vec<u8>],
+) -> CoreResult<Connection> {
+    check_request(method, url, headers)?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| CoreError::InvalidInput("egress URL missing host".to_string()))?;
+    let addr = url
+        .socket_addrs(|| None)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| CoreError::InvalidInput(format!("cannot resolve {}", host)))?;
+
+    let stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_write_timeout(Some(timeout))?;
+    let mut stream = if url.scheme() == "https" {
+        // The handshake reads during the first write, so it needs a read bound too.
+        stream.set_read_timeout(Some(timeout))?;
+        let name = ServerName::try_from(host.trim_matches(['[', ']']).to_string())
+            .map_err(|_| CoreError::InvalidInput(format!("invalid TLS server name {}", host)))?;
+        let tls = ClientConnection::new(tls_config(extra_roots)?, name)
+            .map_err(|e| CoreError::InvalidInput(format!("TLS setup failed: {}", e)))?;
+        Connection::Tls(Box::new(StreamOwned::new(tls, stream)))
+    } else {
+        Connection::Plain(stream)
+    };
+
+    let mut target = url.path().to_string();
+    if let Some(q) = url.query() {
+        target.push('?');
+        target.push_str(q);
+    }
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Length: {}\r\n",
+        method,
+        target,
+        url.port()
+            .map_or(host.to_string(), |p| format!("{}:{}", host, p)),
+        body.len()
+    );
+    for (k, v) in headers {
+        head.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body)?;
+    stream.flush()?;
+    Ok(stream)
 }
 
-fn now_rfc3339_utc() -> String {
-    // Determinism rules forbid volatile timestamps in deliverables, but audit_log is allowed to include timestamps.
-    // For deterministic test mode we will inject a fixed clock; this is a minimal runtime default.
-    time::OffsetDateTime::now_utc()
-        .format(&time::format_description::well_known::Rfc3339)
-        .unwrap()
+// Rejects requests `send_request` cannot put on the wire as given: schemes other than http and
+// https, and a method or headers that would break out of the request head.
+fn check_request(method: &str, url: &Url, headers: &[(&str, &str)]) -> CoreResult<()> {
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(CoreError::InvalidInput(format!(
+            "unsupported egress scheme {}",
+            url.scheme()
+        )));
+    }
+    let is_token = |s: &str| {
+        !s.is_empty()
+            && s.bytes()
+                .all(|b| b.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?={}".contains(&b))
+    };
+    if !is_token(method) {
+        return Err(CoreError::InvalidInput(format!(
+            "invalid HTTP method {:?}",
+            method
+        )));
+    }
+    for (k, v) in headers {
+        if !is_token(k) || v.bytes().any(|b| matches!(b, b'\r' | b'\n' | 0)) {
+            return Err(CoreError::InvalidInput(format!(
+                "invalid HTTP header {:?}",
+                k
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Reads one CRLF-terminated line without its terminator.
+fn read_line(reader: &mut impl BufRead) -> std::io::Result<String> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(std::io::Error::from(ErrorKind::UnexpectedEof));
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn invalid_data(msg: &str) -> std::io::Error {
+    std::io::Error::new(ErrorKind::InvalidData, msg.to_string())
+}
+
+// Status code, chunked transfer encoding and Content-Length from a response head.
+fn parse_head(head: &str) -> CoreResult<(u16, bool, Option<usize>)> {
+    let malformed = || CoreError::InvalidInput("malformed HTTP response".to_string());
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|c| c.parse::<u16>().ok())
+        .ok_or_else(malformed)?;
+    let mut chunked = false;
+    let mut content_length = None;
+    for line in lines {
+        let Some((k, v)) = line.split_once(':') else {
+            continue;
+        };
+        let v = v.trim();
+        if k.eq_ignore_ascii_case("transfer-encoding") && v.eq_ignore_ascii_case("chunked") {
+            chunked = true;
+        } else if k.eq_ignore_ascii_case("content-length") {
+            content_length = Some(v.parse::<usize>().map_err(|_| malformed())?);
+        }
+    }
+    Ok((status, chunked, content_length))
+}
+
+fn parse_http_response(raw: &[u8]) -> CoreResult<HttpResponse> {
+    let malformed = || CoreError::InvalidInput("malformed HTTP response".to_string());
+    let split = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(malformed)?;
+    let head = std::str::from_utf8(&raw[..split]).map_err(|_| malformed())?;
+    let (status, chunked, content_length) = parse_head(head)?;
+
+    let rest = &raw[split + 4..];
+    let body = if chunked {
+        decode_chunked(rest).ok_or_else(malformed)?
+    } else if let Some(n) = content_length {
+        rest.get(..n).ok_or_else(malformed)?.to_vec()
+    } else {
+        rest.to_vec()
+    };
+    Ok(HttpResponse { status, body })
+}
+
+fn decode_chunked(mut rest: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    loop {
+        let eol = rest.windows(2).position(|w| w == b"\r\n")?;
+        let size_line = std::str::from_utf8(&rest[..eol]).ok()?;
+        let size_hex = size_line.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_hex, 16).ok()?;
+        rest = &rest[eol + 2..];
+        if size == 0 {
+            return Some(out);
+        }
+        out.extend_from_slice(rest.get(..size)?);
+        rest = rest.get(size + 2..)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_http_response;
+
+    #[test]
+    fn parses_content_length_and_chunked_bodies() {
+        let r = parse_http_response(b"HTTP/1.1 201 Created\r\nContent-Length: 2\r\n\r\nokIGNORED")
+            .unwrap();
+        assert_eq!(r.status, 201);
+        assert_eq!(r.body, b"ok");
+
+        let r = parse_http_response(
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n2;x=1\r\nde\r\n0\r\n\r\n",
+        )
+        .unwrap();
+        assert!(r.is_success());
+        assert_eq!(r.body, b"abcde");
+
+        assert!(parse_http_response(b"garbage").is_err());
+    }
 }
@@ -1,6 +1,8 @@
 use crate::adapters::pinning::PinningLevel;
+use crate::policy::model_catalog::{CatalogViolation, CatalogViolationReason};
 use crate::policy::types::{NetworkMode, PolicyMode, ProofLevel};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -9,6 +11,7 @@ pub enum ExportBlockReason {
     MISSING_CITATIONS,
     MISSING_REDACTIONS,
     INSUFFICIENT_PINNING,
+    MODEL_CATALOG_VIOLATION,
     OFFLINE_PROOF_INSUFFICIENT,
     DETERMINISM_FAILED,
     BUNDLE_VALIDATION_FAILED,
@@ -24,35 +27,90 @@ pub struct ExportGateInputs {
     pub determinism_passed: bool,
     pub network_mode: NetworkMode,
     pub proof_level: ProofLevel,
+    #[serde(default)]
+    pub model_catalog_violations: Vec<CatalogViolation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_catalog_error: Option<String>, // catalog could not be trusted (e.g. hash mismatch)
 }
 
+/// One failed export condition together with the gate inputs that tripped it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportBlock {
+    pub reason: ExportBlockReason,
+    pub inputs: serde_json::Value,
+}
+
+/// First failing condition, in the precedence order of `evaluate_export_gate_all`.
 pub fn evaluate_export_gate(i: &ExportGateInputs) -> Result<(), ExportBlockReason> {
+    match evaluate_export_gate_all(i).into_iter().next() {
+        Some(block) => Err(block.reason),
+        None => Ok(()),
+    }
+}
+
+/// Evaluates every export condition and returns all that fail; empty means export may proceed.
+pub fn evaluate_export_gate_all(i: &ExportGateInputs) -> Vec<ExportBlock> {
+    let mut blocks = Vec::new();
     if !i.blocker_gate_failures.is_empty() {
-        return Err(ExportBlockReason::EVAL_FAILED);
+        blocks.push(ExportBlock {
+            reason: ExportBlockReason::EVAL_FAILED,
+            inputs: json!({ "blocker_gate_failures": i.blocker_gate_failures }),
+        });
     }
     if !i.determinism_passed {
-        return Err(ExportBlockReason::DETERMINISM_FAILED);
+        blocks.push(ExportBlock {
+            reason: ExportBlockReason::DETERMINISM_FAILED,
+            inputs: json!({ "determinism_passed": false }),
+        });
+    }
+
+    // Model catalog of the policy pack, enforced here only (MODEL_CATALOG.ALLOWED_MODELS_V1
+    // just reports). A catalog that fails its hash check or a use outside the catalog is a
+    // catalog violation; pinning below the catalog minimum is a pinning block.
+    let (pinning, other): (Vec<&CatalogViolation>, Vec<&CatalogViolation>) = i
+        .model_catalog_violations
+        .iter()
+        .partition(|v| v.reason == CatalogViolationReason::PINNING_BELOW_CATALOG);
+    if i.model_catalog_error.is_some() || !other.is_empty() {
+        let mut inputs = json!({ "policy_mode": i.policy_mode });
+        if let Some(error) = &i.model_catalog_error {
+            inputs["model_catalog_error"] = json!(error);
+        }
+        if !other.is_empty() {
+            inputs["model_catalog_violations"] = json!(other);
+        }
+        blocks.push(ExportBlock {
+            reason: ExportBlockReason::MODEL_CATALOG_VIOLATION,
+            inputs,
+        });
     }
 
-    // Pinning rules from lock addendum §7.
-    let pin_ok = match i.policy_mode {
-        PolicyMode::STRICT | PolicyMode::BALANCED => {
-            i.pinning_level == PinningLevel::CRYPTO_PINNED
-                || i.pinning_level == PinningLevel::VERSION_PINNED
+    // Pinning rules from lock addendum §7, together with the catalog minimums.
+    let below_policy = !i.pinning_level.satisfies(i.policy_mode);
+    if below_policy || !pinning.is_empty() {
+        let mut inputs = json!({ "policy_mode": i.policy_mode, "pinning_level": i.pinning_level });
+        if !pinning.is_empty() {
+            inputs["model_catalog_violations"] = json!(pinning);
         }
-        PolicyMode::DRAFT_ONLY => true,
-    };
-    if !pin_ok {
-        return Err(ExportBlockReason::INSUFFICIENT_PINNING);
+        blocks.push(ExportBlock {
+            reason: ExportBlockReason::INSUFFICIENT_PINNING,
+            inputs,
+        });
     }
 
     if i.policy_mode == PolicyMode::STRICT && !i.citations_required_passed {
-        return Err(ExportBlockReason::MISSING_CITATIONS);
+        blocks.push(ExportBlock {
+            reason: ExportBlockReason::MISSING_CITATIONS,
+            inputs: json!({ "policy_mode": i.policy_mode, "citations_required_passed": false }),
+        });
     }
     if (i.policy_mode == PolicyMode::STRICT || i.policy_mode == PolicyMode::BALANCED)
         && !i.redactions_required_passed
     {
-        return Err(ExportBlockReason::MISSING_REDACTIONS);
+        blocks.push(ExportBlock {
+            reason: ExportBlockReason::MISSING_REDACTIONS,
+            inputs: json!({ "policy_mode": i.policy_mode, "redactions_required_passed": false }),
+        });
     }
 
     // Offline proof sufficiency check (strictest interpretation preserving privacy):
@@ -60,8 +118,15 @@ pub fn evaluate_export_gate(i: &ExportGateInputs) -> Result<(), ExportBlockReaso
     if i.policy_mode == PolicyMode::STRICT
         && (i.network_mode != NetworkMode::OFFLINE || i.proof_level != ProofLevel::OFFLINE_STRICT)
     {
-        return Err(ExportBlockReason::OFFLINE_PROOF_INSUFFICIENT);
+        blocks.push(ExportBlock {
+            reason: ExportBlockReason::OFFLINE_PROOF_INSUFFICIENT,
+            inputs: json!({
+                "policy_mode": i.policy_mode,
+                "network_mode": i.network_mode,
+                "proof_level": i.proof_level
+            }),
+        });
     }
 
-    Ok(())
+    blocks
 }
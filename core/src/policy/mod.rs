@@ -1,5 +1,7 @@
 pub mod allowlist;
+pub mod allowlist_store;
 pub mod egress;
 pub mod export_gate;
+pub mod model_catalog;
 pub mod network_snapshot;
 pub mod types;
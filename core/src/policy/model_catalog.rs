@@ -0,0 +1,272 @@
+use crate::adapters::pinning::{ModelSnapshot, PinningLevel};
+use crate::adapters::routing::TaskType;
+use crate::determinism::json_canonical;
+use crate::determinism::run_id::sha256_hex;
+use crate::error::{CoreError, CoreResult};
+use crate::policy::types::PolicyMode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+pub const MODEL_CATALOG_VERSION: &str = "model_catalog_v1";
+
+/// Models a policy pack permits, per product pack and policy mode. Packs without an applicable
+/// section are unrestricted apart from `denied_model_ids`, which apply everywhere.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ModelCatalog {
+    pub catalog_version: String,
+    pub policy_pack_id: String,
+    pub policy_pack_version: String,
+    #[serde(default)]
+    pub denied_model_ids: Vec<String>, // sorted
+    #[serde(default)]
+    pub packs: Vec<PackModelCatalog>, // sorted by pack_id
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PackModelCatalog {
+    pub pack_id: String,
+    #[serde(default)]
+    pub policy_modes: Vec<PolicyMode>, // empty = every mode
+    pub models: Vec<CatalogModel>, // sorted by model_id
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CatalogModel {
+    pub model_id: String,
+    pub task_types: Vec<TaskType>,
+    pub min_pinning_level: PinningLevel,
+}
+
+/// Catalog as recorded in `policy_snapshot.json`, with the hash it was enforced under.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModelCatalogSnapshot {
+    pub model_catalog_hash_sha256: String,
+    pub catalog: ModelCatalog,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CatalogViolationReason {
+    MODEL_DENIED,
+    MODEL_NOT_IN_CATALOG,
+    TASK_TYPE_NOT_IN_CATALOG,
+    PINNING_BELOW_CATALOG,
+}
+
+/// One use of a model in a run. Task type and pinning level are unknown for some sources
+/// (e.g. MODEL_CALL_STARTED carries no pinning level); unknown parts are not checked.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModelUse {
+    pub source: String, // model_snapshot | MODEL_SELECTION_RESOLVED | MODEL_CALL_STARTED
+    pub model_id: String,
+    pub task_type: Option<TaskType>,
+    pub pinning_level: Option<PinningLevel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CatalogViolation {
+    pub model_use: ModelUse,
+    pub reason: CatalogViolationReason,
+}
+
+impl ModelCatalog {
+    pub fn from_json(bytes: &[u8]) -> CoreResult<Self> {
+        let catalog: ModelCatalog = serde_json::from_slice(bytes)
+            .map_err(|e| CoreError::InvalidInput(format!("invalid model catalog: {}", e)))?;
+        catalog.canonicalize()
+    }
+
+    pub fn load(path: &Path) -> CoreResult<Self> {
+        Self::from_json(&std::fs::read(path)?)
+    }
+
+    /// Validates the catalog and sorts every list, so equal catalogs hash equally.
+    pub fn canonicalize(mut self) -> CoreResult<Self> {
+        if self.catalog_version != MODEL_CATALOG_VERSION {
+            return Err(CoreError::InvalidInput(format!(
+                "unsupported catalog_version {}",
+                self.catalog_version
+            )));
+        }
+        if self.policy_pack_id.is_empty() || self.policy_pack_version.is_empty() {
+            return Err(CoreError::InvalidInput(
+                "model catalog must name its policy pack".to_string(),
+            ));
+        }
+        self.denied_model_ids.sort();
+        self.denied_model_ids.dedup();
+        for pack in &mut self.packs {
+            if pack.pack_id.is_empty() {
+                return Err(CoreError::InvalidInput(
+                    "model catalog section without pack_id".to_string(),
+                ));
+            }
+            pack.policy_modes.sort_by_key(|m| format!("{:?}", m));
+            pack.policy_modes.dedup();
+            pack.models.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+            if let Some(w) = pack
+                .models
+                .windows(2)
+                .find(|w| w[0].model_id == w[1].model_id)
+            {
+                return Err(CoreError::InvalidInput(format!(
+                    "model {} listed twice for pack {}",
+                    w[0].model_id, pack.pack_id
+                )));
+            }
+            for model in &mut pack.models {
+                if model.model_id.is_empty() || model.task_types.is_empty() {
+                    return Err(CoreError::InvalidInput(format!(
+                        "catalog model for pack {} needs model_id and task_types",
+                        pack.pack_id
+                    )));
+                }
+                model.task_types.sort_by_key(|t| format!("{:?}", t));
+                model.task_types.dedup();
+            }
+        }
+        self.packs
+            .sort_by_key(|p| (p.pack_id.clone(), format!("{:?}", p.policy_modes)));
+        Ok(self)
+    }
+
+    /// SHA-256 over the canonical JSON of the canonicalized catalog.
+    pub fn hash_sha256(&self) -> CoreResult<String> {
+        let canonical = self.clone().canonicalize()?;
+        Ok(sha256_hex(&json_canonical::to_canonical_bytes(&canonical)?))
+    }
+
+    pub fn snapshot(&self) -> CoreResult<ModelCatalogSnapshot> {
+        let catalog = self.clone().canonicalize()?;
+        Ok(ModelCatalogSnapshot {
+            model_catalog_hash_sha256: catalog.hash_sha256()?,
+            catalog,
+        })
+    }
+
+    /// Why `model_use` is not permitted for `pack_id` under `policy_mode`; empty when it is.
+    pub fn check(
+        &self,
+        pack_id: &str,
+        policy_mode: PolicyMode,
+        model_use: &ModelUse,
+    ) -> Vec<CatalogViolationReason> {
+        if self.denied_model_ids.contains(&model_use.model_id) {
+            return vec![CatalogViolationReason::MODEL_DENIED];
+        }
+        let sections: Vec<&PackModelCatalog> = self
+            .packs
+            .iter()
+            .filter(|p| p.pack_id == pack_id)
+            .filter(|p| p.policy_modes.is_empty() || p.policy_modes.contains(&policy_mode))
+            .collect();
+        if sections.is_empty() {
+            return vec![];
+        }
+        let listed: Vec<&CatalogModel> = sections
+            .iter()
+            .flat_map(|p| p.models.iter())
+            .filter(|m| m.model_id == model_use.model_id)
+            .collect();
+        if listed.is_empty() {
+            return vec![CatalogViolationReason::MODEL_NOT_IN_CATALOG];
+        }
+        let for_task: Vec<&&CatalogModel> = listed
+            .iter()
+            .filter(|m| {
+                model_use
+                    .task_type
+                    .is_none_or(|t| m.task_types.contains(&t))
+            })
+            .collect();
+        if for_task.is_empty() {
+            return vec![CatalogViolationReason::TASK_TYPE_NOT_IN_CATALOG];
+        }
+        let pinned = for_task.iter().any(|m| {
+            model_use
+                .pinning_level
+                .is_none_or(|l| l.rank() >= m.min_pinning_level.rank())
+        });
+        if !pinned {
+            return vec![CatalogViolationReason::PINNING_BELOW_CATALOG];
+        }
+        vec![]
+    }
+
+    pub fn violations(
+        &self,
+        pack_id: &str,
+        policy_mode: PolicyMode,
+        uses: &[ModelUse],
+    ) -> Vec<CatalogViolation> {
+        uses.iter()
+            .flat_map(|u| {
+                self.check(pack_id, policy_mode, u)
+                    .into_iter()
+                    .map(|reason| CatalogViolation {
+                        model_use: u.clone(),
+                        reason,
+                    })
+            })
+            .collect()
+    }
+}
+
+impl ModelCatalogSnapshot {
+    /// Fails when the recorded hash does not match the catalog it travels with.
+    pub fn verify(&self) -> CoreResult<()> {
+        let actual = self.catalog.hash_sha256()?;
+        if actual != self.model_catalog_hash_sha256 {
+            return Err(CoreError::InvalidInput(format!(
+                "model catalog hash mismatch: recorded {}, computed {}",
+                self.model_catalog_hash_sha256, actual
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Models a run used, from its model snapshot and its MODEL_SELECTION_RESOLVED and
+/// MODEL_CALL_STARTED events. Duplicates are dropped; order follows the sources.
+pub fn collect_model_uses(
+    model_snapshot: Option<&ModelSnapshot>,
+    audit_log_ndjson: &str,
+) -> CoreResult<Vec<ModelUse>> {
+    let mut uses = Vec::new();
+    if let Some(s) = model_snapshot.filter(|s| !s.model_id.is_empty()) {
+        uses.push(ModelUse {
+            source: "model_snapshot".to_string(),
+            model_id: s.model_id.clone(),
+            task_type: None,
+            pinning_level: Some(s.pinning_level),
+        });
+    }
+    for line in audit_log_ndjson.lines().filter(|l| !l.trim().is_empty()) {
+        let v: Value = serde_json::from_str(line)?;
+        let details = &v["details"];
+        let task_type = serde_json::from_value(details["task_type"].clone()).ok();
+        let (model_id, pinning_level) = match v["event_type"].as_str() {
+            Some("MODEL_SELECTION_RESOLVED") => (
+                details["selected_model_id"].as_str(),
+                serde_json::from_value(details["pinning_level"].clone()).ok(),
+            ),
+            Some("MODEL_CALL_STARTED") => (details["meta"]["model_id"].as_str(), None),
+            _ => continue,
+        };
+        let Some(model_id) = model_id else { continue };
+        let model_use = ModelUse {
+            source: v["event_type"].as_str().unwrap_or_default().to_string(),
+            model_id: model_id.to_string(),
+            task_type,
+            pinning_level,
+        };
+        if !uses.contains(&model_use) {
+            uses.push(model_use);
+        }
+    }
+    Ok(uses)
+}
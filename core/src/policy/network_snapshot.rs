@@ -18,3 +18,12 @@ pub struct NetworkSnapshot {
     pub ui_remote_fetch_disabled: bool,
     pub adapter_endpoints: Vec<AdapterEndpointSnapshot>,
 }
+
+impl NetworkSnapshot {
+    /// The allowlist entry whose `rule_id` is `rule_id`.
+    pub fn allowlist_rule(&self, rule_id: &str) -> Option<&AllowlistEntry> {
+        self.allowlist
+            .iter()
+            .find(|e| e.rule_id().is_ok_and(|id| id == rule_id))
+    }
+}
@@ -77,10 +77,18 @@ pub struct ClauseAnchor {
 }
 
 // Risk assessment structs
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RiskLevel {
+    HIGH,
+    MEDIUM,
+    LOW,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskAssessment {
     pub anchor_id: String,
-    pub risk_level: String,
+    pub risk_level: RiskLevel,
     pub keywords_matched: Vec<String>,
     pub advisory: String,
     pub citations: Vec<CitationMarker>,
@@ -1,4 +1,4 @@
-use super::model::{RedlineOsOutputManifestV1, RiskAssessment, SegmentedClause};
+use super::model::{RedlineOsOutputManifestV1, RiskAssessment, RiskLevel, SegmentedClause};
 use crate::error::CoreResult;
 
 pub fn output_manifest() -> RedlineOsOutputManifestV1 {
@@ -24,9 +24,9 @@ pub fn render_risk_memo(
     let mut memo = String::from("# Risk Assessment Memo\n\n");
     memo.push_str("## Summary\n\n");
 
-    let high_count = assessments.iter().filter(|a| a.risk_level == "HIGH").count();
-    let medium_count = assessments.iter().filter(|a| a.risk_level == "MEDIUM").count();
-    let low_count = assessments.iter().filter(|a| a.risk_level == "LOW").count();
+    let high_count = assessments.iter().filter(|a| a.risk_level == RiskLevel::HIGH).count();
+    let medium_count = assessments.iter().filter(|a| a.risk_level == RiskLevel::MEDIUM).count();
+    let low_count = assessments.iter().filter(|a| a.risk_level == RiskLevel::LOW).count();
 
     memo.push_str(&format!("- **HIGH Risk Clauses:** {}\n", high_count));
     memo.push_str(&format!("- **MEDIUM Risk Clauses:** {}\n", medium_count));
@@ -36,7 +36,7 @@ pub fn render_risk_memo(
 
     for assessment in assessments {
         memo.push_str(&format!("### Clause: {} \n", assessment.anchor_id));
-        memo.push_str(&format!("**Risk Level:** {}\n\n", assessment.risk_level));
+        memo.push_str(&format!("**Risk Level:** {:?}\n\n", assessment.risk_level));
         memo.push_str(&format!("**Advisory:** {}\n\n", assessment.advisory));
 
         // CRITICAL: Enforce citations with markers
@@ -62,7 +62,7 @@ pub fn render_clause_map_csv(
     for assessment in assessments {
         let keywords = assessment.keywords_matched.join(";");
         csv.push_str(&format!(
-            "{},{},{},{}\n",
+            "{},{:?},{},{}\n",
             assessment.anchor_id, assessment.risk_level, keywords, assessment.anchor_id
         ));
     }
@@ -78,7 +78,7 @@ pub fn render_redline_suggestions(
 
     let high_risk: Vec<_> = assessments
         .iter()
-        .filter(|a| a.risk_level == "HIGH")
+        .filter(|a| a.risk_level == RiskLevel::HIGH)
         .collect();
 
     if high_risk.is_empty() {
@@ -104,7 +104,7 @@ mod tests {
     fn test_risk_memo_has_citations() {
         let assessment = RiskAssessment {
             anchor_id: "REDLINE_test_abc123_0".to_string(),
-            risk_level: "HIGH".to_string(),
+            risk_level: RiskLevel::HIGH,
             keywords_matched: vec!["indemnify".to_string()],
             advisory: "High risk clause".to_string(),
             citations: vec![],
@@ -119,7 +119,7 @@ mod tests {
     fn test_clause_map_csv_format() {
         let assessment = RiskAssessment {
             anchor_id: "REDLINE_test_abc123_0".to_string(),
-            risk_level: "HIGH".to_string(),
+            risk_level: RiskLevel::HIGH,
             keywords_matched: vec!["indemnify".to_string()],
             advisory: "Test".to_string(),
             citations: vec![],
@@ -1,4 +1,4 @@
-use crate::redlineos::model::{SegmentedClause, RiskAssessment, ClauseAnchor};
+use crate::redlineos::model::{SegmentedClause, RiskAssessment, RiskLevel, ClauseAnchor};
 
 const HIGH_RISK_KEYWORDS: &[&str] = &[
     "indemnify", "indemnification", "perpetual", "irrevocable",
@@ -37,11 +37,11 @@ pub fn assess_clause_risk(
 
     // Determine risk level
     let risk_level = if !matched_high.is_empty() {
-        "HIGH"
+        RiskLevel::HIGH
     } else if !matched_medium.is_empty() {
-        "MEDIUM"
+        RiskLevel::MEDIUM
     } else {
-        "LOW"
+        RiskLevel::LOW
     };
 
     // Build advisory message
@@ -50,7 +50,7 @@ pub fn assess_clause_risk(
         "No significant risk keywords detected. Standard contract language.".to_string()
     } else {
         format!(
-            "Risk level: {}. Keywords found: {}. Recommend legal review.",
+            "Risk level: {:?}. Keywords found: {}. Recommend legal review.",
             risk_level,
             all_matched.join(", "),
         )
@@ -58,7 +58,7 @@ pub fn assess_clause_risk(
 
     RiskAssessment {
         anchor_id: anchor.anchor_id.clone(),
-        risk_level: risk_level.to_string(),
+        risk_level,
         keywords_matched: all_matched,
         advisory,
         citations: vec![],  // Will be filled during narrative rendering
@@ -92,7 +92,7 @@ mod tests {
         };
 
         let assessment = assess_clause_risk(&clause, &anchor);
-        assert_eq!(assessment.risk_level, "HIGH");
+        assert_eq!(assessment.risk_level, RiskLevel::HIGH);
         assert!(assessment.keywords_matched.contains(&"indemnify".to_string()));
     }
 
@@ -118,7 +118,7 @@ mod tests {
         };
 
         let assessment = assess_clause_risk(&clause, &anchor);
-        assert_eq!(assessment.risk_level, "MEDIUM");
+        assert_eq!(assessment.risk_level, RiskLevel::MEDIUM);
     }
 
     #[test]
@@ -143,6 +143,6 @@ mod tests {
         };
 
         let assessment = assess_clause_risk(&clause, &anchor);
-        assert_eq!(assessment.risk_level, "LOW");
+        assert_eq!(assessment.risk_level, RiskLevel::LOW);
     }
 }
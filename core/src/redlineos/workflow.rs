@@ -1,4 +1,4 @@
-use super::model::{RedlineOsInputV1, RiskAssessment, ContractArtifactRef};
+use super::model::{RedlineOsInputV1, RiskAssessment, RiskLevel, ContractArtifactRef};
 use super::extraction;
 use super::anchors;
 use super::risk_analysis;
@@ -107,7 +107,7 @@ pub fn execute_redlineos_workflow(
         clause_map,
         suggestions,
         assessment_count: assessments.len(),
-        high_risk_count: assessments.iter().filter(|a| a.risk_level == "HIGH").count(),
+        high_risk_count: assessments.iter().filter(|a| a.risk_level == RiskLevel::HIGH).count(),
         extraction_confidence: extracted.extraction_confidence,
     })
 }
@@ -0,0 +1,29 @@
+use crate::error::{CoreError, CoreResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared flag for aborting an in-flight run from another thread (e.g. a UI Cancel button).
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Returns `CoreError::Cancelled(step)` once cancellation has been requested.
+    pub fn check(&self, step: &str) -> CoreResult<()> {
+        if self.is_cancelled() {
+            return Err(CoreError::Cancelled(step.to_string()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use crate::determinism::json_canonical;
+use crate::determinism::run_id::validate_run_id;
+use crate::error::{CoreError, CoreResult};
+use crate::eval::runner::GateRunResult;
+use crate::run::sink::SinkDelivery;
+use crate::storage::vault::VaultStorage;
+use serde::{Deserialize, Serialize};
+
+// Checkpoints share the vault blob store with the run registry, one blob per run.
+const CHECKPOINT_SCHEMA_VERSION: &str = "EXPORT_CHECKPOINT_V1";
+
+/// Export pipeline steps in execution order.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExportStep {
+    PREFLIGHT_BUILD,
+    EVAL,
+    GATE_DECISION,
+    FINAL_BUILD,
+    VALIDATION,
+    DELIVERY, // sinks delivered and EXPORT_COMPLETED recorded
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StepCheckpoint {
+    pub step: ExportStep,
+    pub audit_event_hash: String, // last audit event appended by the step
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportCheckpoint {
+    pub schema_version: String,
+    pub run_id: String,
+    pub bundle_zip: String,
+    pub requested_event_hash: String, // EXPORT_REQUESTED that opened this export
+    pub completed: Vec<StepCheckpoint>,
+    #[serde(default)]
+    pub gate_results: Vec<GateRunResult>, // saved as soon as the gates are evaluated
+    #[serde(default)]
+    pub gates_recorded: usize, // leading gate_results already appended as EVAL_GATE_RESULT
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_sha256: Option<String>,
+    #[serde(default)]
+    pub deliveries: Vec<SinkDelivery>, // one per sink already tried, in registration order
+}
+
+impl ExportCheckpoint {
+    pub fn new(run_id: &str, bundle_zip: &str, requested_event_hash: &str) -> Self {
+        Self {
+            schema_version: CHECKPOINT_SCHEMA_VERSION.to_string(),
+            run_id: run_id.to_string(),
+            bundle_zip: bundle_zip.to_string(),
+            requested_event_hash: requested_event_hash.to_string(),
+            completed: vec![],
+            gate_results: vec![],
+            gates_recorded: 0,
+            bundle_sha256: None,
+            deliveries: vec![],
+        }
+    }
+
+    pub fn is_done(&self, step: ExportStep) -> bool {
+        self.completed.iter().any(|c| c.step == step)
+    }
+
+    /// Marks `step` finished; later steps are discarded since they depend on its outputs.
+    pub fn mark_done(&mut self, step: ExportStep, audit_event_hash: &str) {
+        self.completed.retain(|c| c.step < step);
+        self.completed.push(StepCheckpoint {
+            step,
+            audit_event_hash: audit_event_hash.to_string(),
+        });
+    }
+
+    /// Forgets `step` and everything after it so they run again.
+    pub fn invalidate_from(&mut self, step: ExportStep) {
+        self.completed.retain(|c| c.step < step);
+    }
+
+    /// First step that has not finished yet, if any.
+    pub fn next_step(&self) -> Option<ExportStep> {
+        use ExportStep::*;
+        [
+            PREFLIGHT_BUILD,
+            EVAL,
+            GATE_DECISION,
+            FINAL_BUILD,
+            VALIDATION,
+            DELIVERY,
+        ]
+        .into_iter()
+        .find(|s| !self.is_done(*s))
+    }
+
+    /// Hash of the most recent checkpointed audit event.
+    pub fn last_event_hash(&self) -> &str {
+        self.completed
+            .last()
+            .map(|c| c.audit_event_hash.as_str())
+            .unwrap_or(&self.requested_event_hash)
+    }
+}
+
+fn blob_id(run_id: &str) -> CoreResult<String> {
+    validate_run_id(run_id)?;
+    Ok(format!("export_checkpoint_{}", run_id))
+}
+
+pub fn load_checkpoint(vault: &VaultStorage, run_id: &str) -> CoreResult<Option<ExportCheckpoint>> {
+    let id = blob_id(run_id)?;
+    if !vault.blob_exists(&id) {
+        return Ok(None);
+    }
+    let cp: ExportCheckpoint = serde_json::from_slice(&vault.read_blob(&id)?)?;
+    if cp.schema_version != CHECKPOINT_SCHEMA_VERSION {
+        return Err(CoreError::InputSchemaError(format!(
+            "unsupported export checkpoint schema_version {}",
+            cp.schema_version
+        )));
+    }
+    Ok(Some(cp))
+}
+
+pub fn save_checkpoint(vault: &VaultStorage, cp: &ExportCheckpoint) -> CoreResult<()> {
+    let bytes = json_canonical::to_canonical_bytes(cp)?;
+    vault.write_blob(&blob_id(&cp.run_id)?, &bytes)
+}
+
+pub fn clear_checkpoint(vault: &VaultStorage, run_id: &str) -> CoreResult<()> {
+    vault.delete_blob(&blob_id(run_id)?)
+}
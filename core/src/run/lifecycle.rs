@@ -8,10 +8,9 @@ pub fn emit_vault_encryption_status(
     run_id: &str,
     vault_id: &str,
     vault: &VaultStorage,
-    ts_utc: &str,
 ) -> CoreResult<()> {
     audit.append(AuditEvent {
-        ts_utc: ts_utc.to_string(),
+        ts_utc: String::new(),
         event_type: "VAULT_ENCRYPTION_STATUS".to_string(),
         run_id: run_id.to_string(),
         vault_id: vault_id.to_string(),
@@ -29,10 +28,9 @@ pub fn emit_vault_key_rotated(
     vault_id: &str,
     old_key_id: &str,
     new_key_id: &str,
-    ts_utc: &str,
 ) -> CoreResult<()> {
     audit.append(AuditEvent {
-        ts_utc: ts_utc.to_string(),
+        ts_utc: String::new(),
         event_type: "VAULT_KEY_ROTATED".to_string(),
         run_id: run_id.to_string(),
         vault_id: vault_id.to_string(),
@@ -1,15 +1,32 @@
+use crate::adapters::calls::{ModelCallLog, ModelCallScope};
+use crate::adapters::interface::{
+    AdapterClient, EmbedRequest, EmbedResponse, GenerateRequest, GenerateResponse, StreamOptions,
+    TranscribeRequest, TranscribeResponse,
+};
 use crate::adapters::pinning::PinningLevel;
+use crate::adapters::supervisor::AdapterSupervisor;
 use crate::audit::event::{Actor, AuditEvent};
 use crate::audit::log::AuditLog;
-use crate::error::CoreResult;
-use crate::eval::runner::EvalRunner;
+use crate::determinism::clock::resumed_clock_for_profile;
+use crate::determinism::run_id::{sha256_hex, validate_run_id};
+use crate::error::{CoreError, CoreResult};
+use crate::eval::runner::{EvalRunner, GateRunResult, GateStatus};
 use crate::evidence_bundle::builder::EvidenceBundleBuilder;
 use crate::evidence_bundle::schemas::EvidenceBundleInputs;
-use crate::policy::export_gate::{evaluate_export_gate, ExportBlockReason, ExportGateInputs};
+use crate::policy::export_gate::{
+    evaluate_export_gate_all, ExportBlock, ExportBlockReason, ExportGateInputs,
+};
+use crate::policy::model_catalog::{collect_model_uses, CatalogViolation};
 use crate::policy::types::{NetworkMode, PolicyMode, ProofLevel};
-use crate::validator::BundleValidator;
+use crate::run::cancel::CancellationToken;
+use crate::run::checkpoint::{self, ExportCheckpoint, ExportStep};
+use crate::run::registry::{RunRecord, RunRegistry};
+use crate::run::sink::{CompletedBundle, DeliveryStatus, ExportSink, SinkDelivery};
+use crate::validator::checklist::Severity;
+use crate::validator::{BundleValidator, CheckStatus};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportRequest {
@@ -36,17 +53,77 @@ pub enum RunState {
     CANCELLED,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRunRequest {
+    pub run_id: String,
+    pub vault_id: String,
+    pub pack_id: String,
+    pub pack_version: String,
+    pub policy_pack_id: String,
+    pub policy_pack_version: String,
+    pub determinism_enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestArtifactRequest {
+    pub run_id: String,
+    pub vault_id: String,
+    pub source_type: String, // FOLDER|ZIP|FILE_PICKER|API
+    pub source_ref: String,
+    pub artifact_id: String,
+    pub artifact_sha256: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+    pub origin_path: String,
+    pub ingest_transformations: Vec<String>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ExportStatus {
+    COMPLETED,
+    BLOCKED,
+    FAILED,
+    CANCELLED,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportOutcome {
-    pub status: String, // COMPLETED|BLOCKED|FAILED
+    pub status: ExportStatus,
     pub bundle_path: Option<String>,
     pub bundle_sha256: Option<String>,
-    pub block_reason: Option<ExportBlockReason>,
+    pub block_reason: Option<ExportBlockReason>, // first of block_reasons
+    #[serde(default)]
+    pub block_reasons: Vec<ExportBlock>,
+    #[serde(default)]
+    pub deliveries: Vec<SinkDelivery>, // one per registered sink on COMPLETED
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PreviewStatus {
+    EXPORTABLE,
+    BLOCKED,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPreview {
+    pub status: PreviewStatus,
+    pub gate_results: Vec<GateRunResult>,
+    pub blocker_gate_failures: Vec<String>,
+    pub block_reason: Option<ExportBlockReason>, // first of block_reasons
+    pub block_reasons: Vec<ExportBlock>,
 }
 
 pub struct RunManager {
     pub audit: AuditLog,
     pub state: RunState,
+    ingested_count: usize,
+    registry: Option<RunRegistry>,
+    cancel: CancellationToken,
+    sinks: Vec<Box<dyn ExportSink>>,
+    model_calls: ModelCallLog,
+    adapters: Vec<AdapterSupervisor>,
 }
 
 impl RunManager {
@@ -54,9 +131,291 @@ impl RunManager {
         Self {
             audit,
             state: RunState::READY,
+            ingested_count: 0,
+            registry: None,
+            cancel: CancellationToken::new(),
+            sinks: Vec::new(),
+            model_calls: ModelCallLog::new(),
+            adapters: Vec::new(),
+        }
+    }
+
+    /// Starts a fresh run in CREATED and records RUN_CREATED as its first lifecycle event.
+    pub fn create_run(audit: AuditLog, req: &CreateRunRequest) -> CoreResult<Self> {
+        validate_run_id(&req.run_id)?;
+        let mut mgr = Self {
+            audit,
+            state: RunState::CREATED,
+            ingested_count: 0,
+            registry: None,
+            cancel: CancellationToken::new(),
+            sinks: Vec::new(),
+            model_calls: ModelCallLog::new(),
+            adapters: Vec::new(),
+        };
+        mgr.emit(
+            &req.run_id,
+            &req.vault_id,
+            "RUN_CREATED",
+            Actor::User,
+            serde_json::json!({
+                "pack_id": req.pack_id,
+                "pack_version": req.pack_version,
+                "policy_pack_id": req.policy_pack_id,
+                "policy_pack_version": req.policy_pack_version,
+                "determinism_enabled": req.determinism_enabled
+            }),
+        )?;
+        Ok(mgr)
+    }
+
+    /// Reopens a run recorded in the vault registry, continuing its audit chain from the last event.
+    /// The run's clock profile is restored too; a deterministic clock resumes after the last
+    /// recorded timestamp.
+    pub fn resume(registry: RunRegistry, run_id: &str) -> CoreResult<Self> {
+        validate_run_id(run_id)?;
+        let record = registry
+            .get(run_id)
+            .cloned()
+            .ok_or_else(|| CoreError::InvalidInput(format!("run not registered: {}", run_id)))?;
+        let audit = AuditLog::open_or_create(&record.audit_log_path)?;
+        let clock = resumed_clock_for_profile(record.determinism_enabled, audit.last_ts_utc())?;
+        let audit = audit.with_clock(clock);
+        Ok(Self {
+            audit,
+            state: record.state,
+            ingested_count: record.artifact_count,
+            registry: Some(registry),
+            cancel: CancellationToken::new(),
+            sinks: Vec::new(),
+            model_calls: ModelCallLog::new(),
+            adapters: Vec::new(),
+        })
+    }
+
+    /// Registers the run in the vault registry; later state changes are persisted there.
+    pub fn attach_registry(
+        &mut self,
+        mut registry: RunRegistry,
+        mut record: RunRecord,
+    ) -> CoreResult<()> {
+        record.state = self.state;
+        record.artifact_count = self.ingested_count;
+        registry.upsert(record)?;
+        self.registry = Some(registry);
+        Ok(())
+    }
+
+    pub fn registry(&self) -> Option<&RunRegistry> {
+        self.registry.as_ref()
+    }
+
+    /// Uses `token` to cancel in-flight exports; it is checked between export steps.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    /// Adds a sink that receives the bundle after a successful export.
+    pub fn with_export_sink(mut self, sink: Box<dyn ExportSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Hands a launched adapter to this run. Crashed adapters are restarted before each model
+    /// call and after a failed one. All are shut down, with ADAPTER_PROCESS_STOPPED recorded,
+    /// when the run reaches COMPLETED, FAILED or CANCELLED or the manager is dropped.
+    pub fn attach_adapter_supervisor(&mut self, supervisor: AdapterSupervisor) {
+        self.adapters.push(supervisor);
+    }
+
+    pub fn adapter_supervisors(&self) -> &[AdapterSupervisor] {
+        &self.adapters
+    }
+
+    /// Adapter calls made through this manager, in call order.
+    pub fn model_calls(&self) -> &ModelCallLog {
+        &self.model_calls
+    }
+
+    /// Runs an LLM call through `client`, audited as MODEL_CALL_* on this run's log.
+    pub fn generate(
+        &mut self,
+        client: &dyn AdapterClient,
+        scope: &ModelCallScope,
+        req: &GenerateRequest,
+    ) -> CoreResult<GenerateResponse> {
+        self.supervise_adapters()?;
+        let result = self
+            .model_calls
+            .generate(&mut self.audit, scope, client, req);
+        self.after_call(result)
+    }
+
+    /// Streaming variant of `generate`; see `ModelCallLog::generate_stream`.
+    pub fn generate_stream(
+        &mut self,
+        client: &dyn AdapterClient,
+        scope: &ModelCallScope,
+        req: &GenerateRequest,
+        opts: &StreamOptions,
+        on_delta: &mut dyn FnMut(&str),
+    ) -> CoreResult<GenerateResponse> {
+        self.supervise_adapters()?;
+        let result =
+            self.model_calls
+                .generate_stream(&mut self.audit, scope, client, req, opts, on_delta);
+        self.after_call(result)
+    }
+
+    pub fn embed(
+        &mut self,
+        client: &dyn AdapterClient,
+        scope: &ModelCallScope,
+        req: &EmbedRequest,
+    ) -> CoreResult<EmbedResponse> {
+        self.supervise_adapters()?;
+        let result = self.model_calls.embed(&mut self.audit, scope, client, req);
+        self.after_call(result)
+    }
+
+    pub fn transcribe(
+        &mut self,
+        client: &dyn AdapterClient,
+        scope: &ModelCallScope,
+        req: &TranscribeRequest,
+    ) -> CoreResult<TranscribeResponse> {
+        self.supervise_adapters()?;
+        let result = self
+            .model_calls
+            .transcribe(&mut self.audit, scope, client, req);
+        self.after_call(result)
+    }
+
+    /// Records one artifact ingest. The first call moves the run from CREATED to INGESTING.
+    pub fn ingest_artifact(&mut self, req: &IngestArtifactRequest) -> CoreResult<()> {
+        if self.state == RunState::CREATED {
+            self.transition(
+                &req.run_id,
+                &req.vault_id,
+                RunState::INGESTING,
+                "artifact ingest started",
+            )?;
         }
+        if self.state != RunState::INGESTING {
+            return Err(CoreError::WorkflowTransitionError(format!(
+                "cannot ingest artifacts in state {:?}",
+                self.state
+            )));
+        }
+        self.emit(
+            &req.run_id,
+            &req.vault_id,
+            "ARTIFACT_INGEST_STARTED",
+            Actor::User,
+            serde_json::json!({
+                "source_type": req.source_type,
+                "source_ref": req.source_ref
+            }),
+        )?;
+        let mut transformations = req.ingest_transformations.clone();
+        transformations.sort();
+        self.emit(
+            &req.run_id,
+            &req.vault_id,
+            "ARTIFACT_INGESTED",
+            Actor::System,
+            serde_json::json!({
+                "artifact_id": req.artifact_id,
+                "artifact_sha256": req.artifact_sha256,
+                "content_type": req.content_type,
+                "size_bytes": req.size_bytes,
+                "origin_path": req.origin_path,
+                "ingest_transformations": transformations
+            }),
+        )?;
+        self.ingested_count += 1;
+        let count = self.ingested_count;
+        self.update_registry(&req.run_id, |r| r.artifact_count = count)
     }
 
+    /// Closes the ingest phase with ARTIFACT_INGEST_COMPLETED and moves the run to READY.
+    pub fn complete_ingest(&mut self, run_id: &str, vault_id: &str) -> CoreResult<()> {
+        if self.state != RunState::CREATED && self.state != RunState::INGESTING {
+            return Err(CoreError::WorkflowTransitionError(format!(
+                "cannot complete ingest in state {:?}",
+                self.state
+            )));
+        }
+        self.emit(
+            run_id,
+            vault_id,
+            "ARTIFACT_INGEST_COMPLETED",
+            Actor::System,
+            serde_json::json!({ "artifact_count": self.ingested_count }),
+        )?;
+        self.transition(
+            run_id,
+            vault_id,
+            RunState::READY,
+            "artifact ingest completed",
+        )
+    }
+
+    pub fn start_execution(&mut self, run_id: &str, vault_id: &str) -> CoreResult<()> {
+        self.transition(run_id, vault_id, RunState::EXECUTING, "execution started")
+    }
+
+    pub fn cancel(&mut self, run_id: &str, vault_id: &str, reason: &str) -> CoreResult<()> {
+        self.cancel_at(run_id, vault_id, reason, None)
+    }
+
+    fn cancel_at(
+        &mut self,
+        run_id: &str,
+        vault_id: &str,
+        reason: &str,
+        step: Option<&str>,
+    ) -> CoreResult<()> {
+        let from = self.state;
+        self.transition(run_id, vault_id, RunState::CANCELLED, reason)?;
+        let mut details = serde_json::json!({
+            "from_state": format!("{:?}", from),
+            "reason": reason
+        });
+        if let Some(step) = step {
+            details["step"] = serde_json::json!(step);
+        }
+        self.emit(run_id, vault_id, "RUN_CANCELLED", Actor::User, details)
+    }
+
+    pub fn fail(&mut self, run_id: &str, vault_id: &str, reason: &str) -> CoreResult<()> {
+        let from = self.state;
+        self.transition(run_id, vault_id, RunState::FAILED, reason)?;
+        self.emit(
+            run_id,
+            vault_id,
+            "RUN_FAILED",
+            Actor::System,
+            serde_json::json!({
+                "from_state": format!("{:?}", from),
+                "reason": reason
+            }),
+        )
+    }
+
+    /// Runs the export pipeline. With a registry attached, each step is checkpointed to the
+    /// vault; calling this again for a run interrupted in EVALUATING or EXPORTING resumes after
+    /// the last finished step, recording EXPORT_RESUMED instead of re-emitting its events.
+    ///
+    /// Model calls recorded through this manager are appended to `run_manifest.model_calls`.
+    ///
+    /// The cancellation token is checked between steps and gate evaluations. A cancelled export
+    /// removes its partial files, records RUN_CANCELLED with the step and returns CANCELLED.
     pub fn export_run(
         &mut self,
         req: &ExportRequest,
@@ -64,290 +423,683 @@ impl RunManager {
         bundle_dir: &Path,
         bundle_zip: &Path,
     ) -> CoreResult<ExportOutcome> {
-        // 1) EXPORT_REQUESTED
-        self.audit.append(AuditEvent {
-            ts_utc: now_rfc3339_utc(),
-            event_type: "EXPORT_REQUESTED".to_string(),
-            run_id: req.run_id.clone(),
-            vault_id: req.vault_id.clone(),
-            actor: Actor::User,
-            details: serde_json::json!({
-                "requested_by": req.requested_by,
-                "export_targets": [bundle_zip.to_string_lossy().to_string()],
-                "policy_mode": format!("{:?}", req.policy_mode)
-            }),
-            prev_event_hash: String::new(),
-            event_hash: String::new(),
-        })?;
-        // 2) Run state -> EVALUATING
-        self.transition(req, RunState::EVALUATING, "export requested")?;
+        let bundle_inputs = self.with_model_calls(bundle_inputs);
+        match self.run_export(req, &bundle_inputs, bundle_dir, bundle_zip) {
+            Err(CoreError::Cancelled(step)) => {
+                self.abort_cancelled_export(req, bundle_dir, bundle_zip, &step)
+            }
+            other => other,
+        }
+    }
 
-        // 2-3) EVAL_STARTED + EVAL results
-        self.audit.append(AuditEvent {
-            ts_utc: now_rfc3339_utc(),
-            event_type: "EVAL_STARTED".to_string(),
-            run_id: req.run_id.clone(),
-            vault_id: req.vault_id.clone(),
-            actor: Actor::System,
-            details: serde_json::json!({ "registry_version": "gates_registry_v3" }),
-            prev_event_hash: String::new(),
-            event_hash: String::new(),
-        })?;
+    fn run_export(
+        &mut self,
+        req: &ExportRequest,
+        bundle_inputs: &EvidenceBundleInputs,
+        bundle_dir: &Path,
+        bundle_zip: &Path,
+    ) -> CoreResult<ExportOutcome> {
+        use ExportStep::*;
+        let target = bundle_zip.to_string_lossy().to_string();
+        let (preflight_root, preflight_zip) = preflight_paths(bundle_dir, &req.run_id);
 
-        // Preflight bundle for eval checks only (kept outside final export target).
-        let preflight_root = std::env::temp_dir().join(format!("{}_preflight_bundle", req.run_id));
-        let preflight_zip =
-            std::env::temp_dir().join(format!("{}_preflight_bundle.zip", req.run_id));
-        if preflight_root.exists() {
-            std::fs::remove_dir_all(&preflight_root)?;
-        }
-        if preflight_zip.exists() {
-            std::fs::remove_file(&preflight_zip)?;
-        }
-        EvidenceBundleBuilder::build_dir(&preflight_root, bundle_inputs)?;
-        EvidenceBundleBuilder::build_zip(&preflight_root, &preflight_zip)?;
-
-        let eval_runner = EvalRunner::new_v3()?;
-        let gate_results = eval_runner.run_all_for_bundle(&preflight_zip, req.policy_mode)?;
-        let mut blocker_fails = Vec::new();
-        for g in &gate_results {
-            self.audit.append(AuditEvent {
-                ts_utc: now_rfc3339_utc(),
-                event_type: "EVAL_GATE_RESULT".to_string(),
-                run_id: req.run_id.clone(),
-                vault_id: req.vault_id.clone(),
-                actor: Actor::System,
-                details: serde_json::json!({
-                    "gate_id": g.gate_id,
-                    "result": g.result,
-                    "severity": g.severity,
-                    "evidence_pointers": g.evidence_pointers,
-                    "message": g.message
-                }),
-                prev_event_hash: String::new(),
-                event_hash: String::new(),
-            })?;
-            if g.severity == "BLOCKER" && g.result == "FAIL" {
-                blocker_fails.push(g.gate_id.clone());
+        let mut cp = match self.resumable_checkpoint(&req.run_id, &target)? {
+            Some(cp) => {
+                // Resume marker; the export's one EXPORT_REQUESTED is the event it points back to.
+                self.emit(
+                    &req.run_id,
+                    &req.vault_id,
+                    "EXPORT_RESUMED",
+                    Actor::User,
+                    serde_json::json!({
+                        "requested_by": req.requested_by,
+                        "export_targets": [target],
+                        "requested_event_hash": cp.requested_event_hash,
+                        "resumed_from_step": cp.next_step(),
+                        "completed_steps": cp.completed.iter().map(|c| c.step).collect::<Vec<_>>(),
+                        "checkpoint_event_hash": cp.last_event_hash()
+                    }),
+                )?;
+                cp
             }
-        }
-        self.audit.append(AuditEvent {
-            ts_utc: now_rfc3339_utc(),
-            event_type: "EVAL_COMPLETED".to_string(),
-            run_id: req.run_id.clone(),
-            vault_id: req.vault_id.clone(),
-            actor: Actor::System,
-            details: serde_json::json!({
-                "gates_executed": gate_results.len(),
-                "gates_failed_blocker": blocker_fails.len(),
-                "gates_failed_total": blocker_fails.len()
-            }),
-            prev_event_hash: String::new(),
-            event_hash: String::new(),
-        })?;
+            None => {
+                // 1) EXPORT_REQUESTED
+                self.emit(
+                    &req.run_id,
+                    &req.vault_id,
+                    "EXPORT_REQUESTED",
+                    Actor::User,
+                    serde_json::json!({
+                        "requested_by": req.requested_by,
+                        "export_targets": [target],
+                        "policy_mode": format!("{:?}", req.policy_mode)
+                    }),
+                )?;
+                let requested_event_hash = self.audit.last_hash().to_string();
+                self.update_registry(&req.run_id, |r| {
+                    r.policy_mode = req.policy_mode;
+                    r.network_mode = req.network_mode;
+                    r.bundle_dir = Some(bundle_dir.to_string_lossy().to_string());
+                    r.bundle_zip = Some(bundle_zip.to_string_lossy().to_string());
+                })?;
+                // 2) Run state -> EVALUATING (already there if a previous attempt stopped
+                // before its first checkpoint)
+                if self.state != RunState::EVALUATING {
+                    self.transition(
+                        &req.run_id,
+                        &req.vault_id,
+                        RunState::EVALUATING,
+                        "export requested",
+                    )?;
+                }
+                // 2-3) EVAL_STARTED, recorded before the checkpoint that lets a resume skip it
+                self.emit(
+                    &req.run_id,
+                    &req.vault_id,
+                    "EVAL_STARTED",
+                    Actor::System,
+                    serde_json::json!({ "registry_version": "gates_registry_v3" }),
+                )?;
+                let cp = ExportCheckpoint::new(&req.run_id, &target, &requested_event_hash);
+                self.save_checkpoint(&cp)?;
+                cp
+            }
+        };
+
+        if !cp.is_done(EVAL) {
+            // Gates are evaluated once; a resume only records the results not yet in the log.
+            if cp.gate_results.is_empty() {
+                // Preflight bundle for eval checks only (kept outside final export target).
+                if !cp.is_done(PREFLIGHT_BUILD) || !preflight_zip.exists() {
+                    self.check_cancelled(PREFLIGHT_BUILD)?;
+                    build_preflight(&preflight_root, &preflight_zip, bundle_inputs)?;
+                    self.record_step(&mut cp, PREFLIGHT_BUILD)?;
+                }
 
-        // Policy gate checks from evaluated gates.
-        let citations_ok = gate_results
-            .iter()
-            .find(|g| g.gate_id == "CITATIONS.STRICT_ENFORCED_V1")
-            .map(|g| g.result == "PASS" || g.result == "NOT_APPLICABLE")
-            .unwrap_or(true);
-        let redactions_ok = gate_results
-            .iter()
-            .find(|g| g.gate_id == "REDACTION.REQUIRED_APPLIED_V1")
-            .map(|g| g.result == "PASS" || g.result == "NOT_APPLICABLE")
-            .unwrap_or(true);
-        let determinism_ok = gate_results
-            .iter()
-            .find(|g| g.gate_id == "DETERMINISM.ZIP_PACKAGING_V1")
-            .map(|g| g.result == "PASS" || g.result == "NOT_APPLICABLE")
-            .unwrap_or(true);
-
-        if let Err(reason) = evaluate_export_gate(&ExportGateInputs {
-            policy_mode: req.policy_mode,
-            pinning_level: req.pinning_level,
-            citations_required_passed: citations_ok,
-            redactions_required_passed: redactions_ok,
-            blocker_gate_failures: blocker_fails.clone(),
-            determinism_passed: determinism_ok,
-            network_mode: req.network_mode,
-            proof_level: req.proof_level,
-        }) {
-            self.audit.append(AuditEvent {
-                ts_utc: now_rfc3339_utc(),
-                event_type: "EXPORT_BLOCKED".to_string(),
-                run_id: req.run_id.clone(),
-                vault_id: req.vault_id.clone(),
-                actor: Actor::System,
-                details: serde_json::json!({
-                    "block_reason": format!("{:?}", reason),
-                    "failed_gate_ids": blocker_fails
+                let eval_runner = EvalRunner::new_v3()?;
+                let token = self.cancel.clone();
+                cp.gate_results = eval_runner.run_all_for_bundle_checked(
+                    &preflight_zip,
+                    req.policy_mode,
+                    &|gate| token.check(&format!("{:?}:{}", EVAL, gate)),
+                )?;
+                cp.gates_recorded = 0;
+                self.save_checkpoint(&cp)?;
+            }
+            let gate_results = cp.gate_results.clone();
+            for g in &gate_results[cp.gates_recorded.min(gate_results.len())..] {
+                self.emit(
+                    &req.run_id,
+                    &req.vault_id,
+                    "EVAL_GATE_RESULT",
+                    Actor::System,
+                    serde_json::json!({
+                        "gate_id": g.gate_id,
+                        "result": g.result,
+                        "severity": g.severity,
+                        "evidence_pointers": g.evidence_pointers,
+                        "message": g.message
+                    }),
+                )?;
+                cp.gates_recorded += 1;
+                self.save_checkpoint(&cp)?;
+            }
+            let blocker_fails = blocker_failures(&gate_results);
+            self.emit(
+                &req.run_id,
+                &req.vault_id,
+                "EVAL_COMPLETED",
+                Actor::System,
+                serde_json::json!({
+                    "gates_executed": gate_results.len(),
+                    "gates_failed_blocker": blocker_fails.len(),
+                    "gates_failed_total": blocker_fails.len()
                 }),
-                prev_event_hash: String::new(),
-                event_hash: String::new(),
-            })?;
-            self.transition(req, RunState::FAILED, "export blocked")?;
-            let _ = std::fs::remove_dir_all(&preflight_root);
-            let _ = std::fs::remove_file(&preflight_zip);
-            return Ok(ExportOutcome {
-                status: "BLOCKED".to_string(),
-                bundle_path: None,
-                bundle_sha256: None,
-                block_reason: Some(reason),
-            });
+            )?;
+            self.record_step(&mut cp, EVAL)?;
         }
 
-        // Preflight artifacts are no longer needed after export decision.
-        let _ = std::fs::remove_dir_all(&preflight_root);
-        let _ = std::fs::remove_file(&preflight_zip);
+        if !cp.is_done(GATE_DECISION) {
+            self.check_cancelled(GATE_DECISION)?;
+            let catalog = model_catalog_check(req, bundle_inputs)?;
+            let gate_inputs = export_gate_inputs(req, &cp.gate_results, catalog);
+            let blocker_fails = gate_inputs.blocker_gate_failures.clone();
+            let blocks = evaluate_export_gate_all(&gate_inputs);
+            if let Some(first) = blocks.first() {
+                let reason = first.reason.clone();
+                self.emit(
+                    &req.run_id,
+                    &req.vault_id,
+                    "EXPORT_BLOCKED",
+                    Actor::System,
+                    serde_json::json!({
+                        "block_reason": format!("{:?}", reason),
+                        "failed_gate_ids": blocker_fails,
+                        "meta": { "block_reasons": blocks }
+                    }),
+                )?;
+                self.fail(&req.run_id, &req.vault_id, "export blocked")?;
+                let _ = remove_scratch(&preflight_root, &preflight_zip);
+                self.clear_checkpoint(&req.run_id)?;
+                return Ok(ExportOutcome {
+                    status: ExportStatus::BLOCKED,
+                    bundle_path: None,
+                    bundle_sha256: None,
+                    block_reason: Some(reason),
+                    block_reasons: blocks,
+                    deliveries: vec![],
+                });
+            }
 
-        self.transition(req, RunState::EXPORTING, "gates passed")?;
-        // 8-10) Final bundle generation
-        self.audit.append(AuditEvent {
-            ts_utc: now_rfc3339_utc(),
-            event_type: "BUNDLE_GENERATION_STARTED".to_string(),
-            run_id: req.run_id.clone(),
-            vault_id: req.vault_id.clone(),
-            actor: Actor::System,
-            details: serde_json::json!({}),
-            prev_event_hash: String::new(),
-            event_hash: String::new(),
-        })?;
-        EvidenceBundleBuilder::build_dir(bundle_dir, bundle_inputs)?;
-        let bundle_sha = EvidenceBundleBuilder::build_zip(bundle_dir, bundle_zip)?;
-        self.audit.append(AuditEvent {
-            ts_utc: now_rfc3339_utc(),
-            event_type: "BUNDLE_GENERATION_COMPLETED".to_string(),
-            run_id: req.run_id.clone(),
-            vault_id: req.vault_id.clone(),
-            actor: Actor::System,
-            details: serde_json::json!({}),
-            prev_event_hash: String::new(),
-            event_hash: String::new(),
-        })?;
+            // Preflight artifacts are no longer needed after export decision.
+            remove_scratch(&preflight_root, &preflight_zip)?;
+            if self.state != RunState::EXPORTING {
+                self.transition(
+                    &req.run_id,
+                    &req.vault_id,
+                    RunState::EXPORTING,
+                    "gates passed",
+                )?;
+            }
+            self.record_step(&mut cp, GATE_DECISION)?;
+        }
+
+        // 8-10) Final bundle generation; a checkpointed zip is reused only if it is unchanged.
+        let reusable_sha = match (&cp.bundle_sha256, cp.is_done(FINAL_BUILD)) {
+            (Some(sha), true) if bundle_zip.exists() => {
+                (sha256_hex(&std::fs::read(bundle_zip)?) == *sha).then(|| sha.clone())
+            }
+            _ => None,
+        };
+        let bundle_sha = match reusable_sha {
+            Some(sha) => sha,
+            None => {
+                cp.invalidate_from(FINAL_BUILD);
+                self.check_cancelled(FINAL_BUILD)?;
+                self.emit(
+                    &req.run_id,
+                    &req.vault_id,
+                    "BUNDLE_GENERATION_STARTED",
+                    Actor::System,
+                    serde_json::json!({}),
+                )?;
+                EvidenceBundleBuilder::build_dir(bundle_dir, bundle_inputs)?;
+                self.check_cancelled(FINAL_BUILD)?;
+                let sha = EvidenceBundleBuilder::build_zip(bundle_dir, bundle_zip)?;
+                self.emit(
+                    &req.run_id,
+                    &req.vault_id,
+                    "BUNDLE_GENERATION_COMPLETED",
+                    Actor::System,
+                    serde_json::json!({}),
+                )?;
+                cp.bundle_sha256 = Some(sha.clone());
+                self.record_step(&mut cp, FINAL_BUILD)?;
+                sha
+            }
+        };
 
         // 11-13) Bundle validation
-        self.audit.append(AuditEvent {
-            ts_utc: now_rfc3339_utc(),
-            event_type: "BUNDLE_VALIDATION_STARTED".to_string(),
-            run_id: req.run_id.clone(),
-            vault_id: req.vault_id.clone(),
-            actor: Actor::System,
-            details: serde_json::json!({}),
-            prev_event_hash: String::new(),
-            event_hash: String::new(),
-        })?;
-        let validator = BundleValidator::new_v3();
-        let summary = validator.validate_zip(bundle_zip, req.policy_mode)?;
-        self.audit.append(AuditEvent {
-            ts_utc: now_rfc3339_utc(),
-            event_type: "BUNDLE_VALIDATION_RESULT".to_string(),
-            run_id: req.run_id.clone(),
-            vault_id: req.vault_id.clone(),
-            actor: Actor::System,
-            details: serde_json::json!({
-                "result": summary.overall,
-                "failed_checks": summary.checks.iter().filter(|c| c.result != "PASS").map(|c| c.check_id.clone()).collect::<Vec<_>>(),
-                "validator_version": "bundle_validator_v3"
-            }),
-            prev_event_hash: String::new(),
-            event_hash: String::new(),
-        })?;
-        if summary.overall != "PASS" {
-            self.audit.append(AuditEvent {
-                ts_utc: now_rfc3339_utc(),
-                event_type: "EXPORT_FAILED".to_string(),
-                run_id: req.run_id.clone(),
-                vault_id: req.vault_id.clone(),
-                actor: Actor::System,
-                details: serde_json::json!({"reason":"BUNDLE_VALIDATION_FAILED"}),
-                prev_event_hash: String::new(),
-                event_hash: String::new(),
-            })?;
-            self.transition(req, RunState::FAILED, "bundle validation failed")?;
-            return Ok(ExportOutcome {
-                status: "FAILED".to_string(),
-                bundle_path: None,
-                bundle_sha256: None,
-                block_reason: Some(ExportBlockReason::BUNDLE_VALIDATION_FAILED),
-            });
+        if !cp.is_done(VALIDATION) {
+            self.check_cancelled(VALIDATION)?;
+            self.emit(
+                &req.run_id,
+                &req.vault_id,
+                "BUNDLE_VALIDATION_STARTED",
+                Actor::System,
+                serde_json::json!({}),
+            )?;
+            let validator = BundleValidator::new_v3();
+            let summary = validator.validate_zip(bundle_zip, req.policy_mode)?;
+            let failed_checks: Vec<String> = summary
+                .checks
+                .iter()
+                .filter(|c| c.result != CheckStatus::PASS)
+                .map(|c| c.check_id.clone())
+                .collect();
+            self.emit(
+                &req.run_id,
+                &req.vault_id,
+                "BUNDLE_VALIDATION_RESULT",
+                Actor::System,
+                serde_json::json!({
+                    "result": summary.overall,
+                    "failed_checks": failed_checks,
+                    "validator_version": "bundle_validator_v3"
+                }),
+            )?;
+            if summary.overall != CheckStatus::PASS {
+                let block = ExportBlock {
+                    reason: ExportBlockReason::BUNDLE_VALIDATION_FAILED,
+                    inputs: serde_json::json!({ "failed_checks": failed_checks }),
+                };
+                self.emit(
+                    &req.run_id,
+                    &req.vault_id,
+                    "EXPORT_FAILED",
+                    Actor::System,
+                    serde_json::json!({
+                        "reason": "BUNDLE_VALIDATION_FAILED",
+                        "meta": { "block_reasons": [&block] }
+                    }),
+                )?;
+                self.fail(&req.run_id, &req.vault_id, "bundle validation failed")?;
+                self.clear_checkpoint(&req.run_id)?;
+                return Ok(ExportOutcome {
+                    status: ExportStatus::FAILED,
+                    bundle_path: None,
+                    bundle_sha256: None,
+                    block_reason: Some(ExportBlockReason::BUNDLE_VALIDATION_FAILED),
+                    block_reasons: vec![block],
+                    deliveries: vec![],
+                });
+            }
+            self.record_step(&mut cp, VALIDATION)?;
         }
 
-        // 15) EXPORT_COMPLETED
-        let rel = bundle_zip.to_string_lossy().to_string();
-        self.audit.append(AuditEvent {
-            ts_utc: now_rfc3339_utc(),
-            event_type: "EXPORT_COMPLETED".to_string(),
-            run_id: req.run_id.clone(),
-            vault_id: req.vault_id.clone(),
-            actor: Actor::System,
-            details: serde_json::json!({
-                "bundle_path": rel,
+        // 14-15) Sink deliveries, then one EXPORT_COMPLETED that lists them
+        if !cp.is_done(DELIVERY) {
+            self.deliver_to_sinks(req, &mut cp, bundle_zip, &bundle_sha)?;
+            let mut details = serde_json::json!({
+                "bundle_path": target,
                 "bundle_sha256": bundle_sha,
                 "bundle_version": "EVIDENCE_BUNDLE_V1",
                 "validator_result": "PASS"
-            }),
-            prev_event_hash: String::new(),
-            event_hash: String::new(),
-        })?;
-        self.transition(req, RunState::COMPLETED, "export completed")?;
+            });
+            if !cp.deliveries.is_empty() {
+                details["meta"] = serde_json::json!({ "deliveries": cp.deliveries });
+            }
+            self.emit(
+                &req.run_id,
+                &req.vault_id,
+                "EXPORT_COMPLETED",
+                Actor::System,
+                details,
+            )?;
+            self.record_step(&mut cp, DELIVERY)?;
+        }
+        let deliveries = cp.deliveries.clone();
+        self.transition(
+            &req.run_id,
+            &req.vault_id,
+            RunState::COMPLETED,
+            "export completed",
+        )?;
+        self.emit(
+            &req.run_id,
+            &req.vault_id,
+            "RUN_COMPLETED",
+            Actor::System,
+            serde_json::json!({ "bundle_sha256": bundle_sha }),
+        )?;
+        self.clear_checkpoint(&req.run_id)?;
         Ok(ExportOutcome {
-            status: "COMPLETED".to_string(),
-            bundle_path: Some(rel),
+            status: ExportStatus::COMPLETED,
+            bundle_path: Some(target),
             bundle_sha256: Some(bundle_sha),
             block_reason: None,
+            block_reasons: vec![],
+            deliveries,
+        })
+    }
+
+    /// Hands the validated bundle to each sink not yet tried by this export. Each outcome is
+    /// checkpointed as soon as the sink returns, so a resumed export never delivers twice.
+    fn deliver_to_sinks(
+        &mut self,
+        req: &ExportRequest,
+        cp: &mut ExportCheckpoint,
+        bundle_zip: &Path,
+        bundle_sha: &str,
+    ) -> CoreResult<()> {
+        let bundle = CompletedBundle {
+            run_id: req.run_id.clone(),
+            vault_id: req.vault_id.clone(),
+            bundle_zip: bundle_zip.to_path_buf(),
+            bundle_sha256: bundle_sha.to_string(),
+        };
+        // Sinks borrow the audit log while delivering, so they are detached for the loop.
+        let sinks = std::mem::take(&mut self.sinks);
+        let delivered = sinks.iter().skip(cp.deliveries.len()).try_for_each(|sink| {
+            cp.deliveries.push(self.deliver_one(sink.as_ref(), &bundle));
+            self.save_checkpoint(cp)
+        });
+        self.sinks = sinks;
+        delivered
+    }
+
+    fn deliver_one(&mut self, sink: &dyn ExportSink, bundle: &CompletedBundle) -> SinkDelivery {
+        match sink.deliver(bundle, &mut self.audit) {
+            Ok(location) => SinkDelivery {
+                sink_id: sink.sink_id().to_string(),
+                status: DeliveryStatus::DELIVERED,
+                location: Some(location),
+                error: None,
+            },
+            Err(e) => SinkDelivery {
+                sink_id: sink.sink_id().to_string(),
+                status: DeliveryStatus::FAILED,
+                location: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    fn check_cancelled(&self, step: ExportStep) -> CoreResult<()> {
+        self.cancel.check(&format!("{:?}", step))
+    }
+
+    fn abort_cancelled_export(
+        &mut self,
+        req: &ExportRequest,
+        bundle_dir: &Path,
+        bundle_zip: &Path,
+        step: &str,
+    ) -> CoreResult<ExportOutcome> {
+        let (preflight_root, preflight_zip) = preflight_paths(bundle_dir, &req.run_id);
+        remove_scratch(&preflight_root, &preflight_zip)?;
+        let final_steps = [ExportStep::FINAL_BUILD, ExportStep::VALIDATION];
+        if final_steps.iter().any(|s| format!("{:?}", s) == step) {
+            remove_scratch(bundle_dir, bundle_zip)?;
+        }
+        self.clear_checkpoint(&req.run_id)?;
+        self.cancel_at(&req.run_id, &req.vault_id, "export cancelled", Some(step))?;
+        Ok(ExportOutcome {
+            status: ExportStatus::CANCELLED,
+            bundle_path: None,
+            bundle_sha256: None,
+            block_reason: None,
+            block_reasons: vec![],
+            deliveries: vec![],
         })
     }
 
-    fn transition(&mut self, req: &ExportRequest, to: RunState, reason: &str) -> CoreResult<()> {
+    /// Dry run of the export decision. The preflight bundle is built under `scratch_dir` and
+    /// removed afterwards; run state, the registry and the audit log are left untouched.
+    pub fn preview_export(
+        &self,
+        req: &ExportRequest,
+        bundle_inputs: &EvidenceBundleInputs,
+        scratch_dir: &Path,
+    ) -> CoreResult<ExportPreview> {
+        let root = scratch_dir.join(format!("{}_preview_bundle", req.run_id));
+        let zip = scratch_dir.join(format!("{}_preview_bundle.zip", req.run_id));
+        let evaluated = build_preflight(&root, &zip, &self.with_model_calls(bundle_inputs))
+            .and_then(|_| EvalRunner::new_v3()?.run_all_for_bundle(&zip, req.policy_mode));
+        remove_scratch(&root, &zip)?;
+        let gate_results = evaluated?;
+
+        let catalog = model_catalog_check(req, bundle_inputs)?;
+        let gate_inputs = export_gate_inputs(req, &gate_results, catalog);
+        let block_reasons = evaluate_export_gate_all(&gate_inputs);
+        Ok(ExportPreview {
+            status: if !block_reasons.is_empty() {
+                PreviewStatus::BLOCKED
+            } else {
+                PreviewStatus::EXPORTABLE
+            },
+            gate_results,
+            blocker_gate_failures: gate_inputs.blocker_gate_failures,
+            block_reason: block_reasons.first().map(|b| b.reason.clone()),
+            block_reasons,
+        })
+    }
+
+    /// Bundle inputs with the model calls made through this manager added to the run manifest.
+    fn with_model_calls<'a>(
+        &self,
+        bundle_inputs: &'a EvidenceBundleInputs,
+    ) -> Cow<'a, EvidenceBundleInputs> {
+        if self.model_calls.calls().is_empty() {
+            return Cow::Borrowed(bundle_inputs);
+        }
+        let mut inputs = bundle_inputs.clone();
+        self.model_calls.fill_manifest(&mut inputs.run_manifest);
+        Cow::Owned(inputs)
+    }
+
+    /// Returns the stored checkpoint when the run is mid-export; stale checkpoints are dropped.
+    fn resumable_checkpoint(
+        &mut self,
+        run_id: &str,
+        target: &str,
+    ) -> CoreResult<Option<ExportCheckpoint>> {
+        let Some(registry) = self.registry.as_ref() else {
+            return Ok(None);
+        };
+        let Some(cp) = checkpoint::load_checkpoint(registry.vault(), run_id)? else {
+            return Ok(None);
+        };
+        if !matches!(self.state, RunState::EVALUATING | RunState::EXPORTING) {
+            self.clear_checkpoint(run_id)?;
+            return Ok(None);
+        }
+        if cp.bundle_zip != target {
+            return Err(CoreError::InvalidInput(format!(
+                "interrupted export for run {} targets {}",
+                run_id, cp.bundle_zip
+            )));
+        }
+        Ok(Some(cp))
+    }
+
+    fn record_step(&mut self, cp: &mut ExportCheckpoint, step: ExportStep) -> CoreResult<()> {
+        cp.mark_done(step, self.audit.last_hash());
+        self.save_checkpoint(cp)
+    }
+
+    fn save_checkpoint(&self, cp: &ExportCheckpoint) -> CoreResult<()> {
+        match self.registry.as_ref() {
+            Some(registry) => checkpoint::save_checkpoint(registry.vault(), cp),
+            None => Ok(()),
+        }
+    }
+
+    fn clear_checkpoint(&self, run_id: &str) -> CoreResult<()> {
+        match self.registry.as_ref() {
+            Some(registry) => checkpoint::clear_checkpoint(registry.vault(), run_id),
+            None => Ok(()),
+        }
+    }
+
+    fn transition(
+        &mut self,
+        run_id: &str,
+        vault_id: &str,
+        to: RunState,
+        reason: &str,
+    ) -> CoreResult<()> {
         if !valid_transition(self.state, to) {
-            return Err(crate::error::CoreError::PolicyBlocked(format!(
+            return Err(CoreError::PolicyBlocked(format!(
                 "invalid run state transition {:?} -> {:?}",
                 self.state, to
             )));
         }
-        self.audit.append(AuditEvent {
-            ts_utc: now_rfc3339_utc(),
-            event_type: "RUN_STATE_CHANGED".to_string(),
-            run_id: req.run_id.clone(),
-            vault_id: req.vault_id.clone(),
-            actor: Actor::System,
-            details: serde_json::json!({
+        self.emit(
+            run_id,
+            vault_id,
+            "RUN_STATE_CHANGED",
+            Actor::System,
+            serde_json::json!({
                 "from_state": format!("{:?}", self.state),
                 "to_state": format!("{:?}", to),
                 "reason": reason
             }),
+        )?;
+        self.state = to;
+        if matches!(
+            to,
+            RunState::COMPLETED | RunState::FAILED | RunState::CANCELLED
+        ) {
+            for adapter in &mut self.adapters {
+                adapter.shutdown(&mut self.audit)?;
+            }
+        }
+        self.update_registry(run_id, |r| r.state = to)
+    }
+
+    fn supervise_adapters(&mut self) -> CoreResult<()> {
+        for adapter in &mut self.adapters {
+            adapter.ensure_running(&mut self.audit)?;
+        }
+        Ok(())
+    }
+
+    // A call can fail because its adapter died mid-call; restart it now so the crash is
+    // audited where it happened. The call's own error is what the caller sees.
+    fn after_call<T>(&mut self, result: CoreResult<T>) -> CoreResult<T> {
+        if result.is_err() {
+            let _ = self.supervise_adapters();
+        }
+        result
+    }
+
+    fn update_registry(&mut self, run_id: &str, f: impl FnOnce(&mut RunRecord)) -> CoreResult<()> {
+        match self.registry.as_mut() {
+            Some(registry) => registry.update(run_id, f),
+            None => Ok(()),
+        }
+    }
+
+    fn emit(
+        &mut self,
+        run_id: &str,
+        vault_id: &str,
+        event_type: &str,
+        actor: Actor,
+        details: serde_json::Value,
+    ) -> CoreResult<()> {
+        self.audit.append(AuditEvent {
+            ts_utc: String::new(),
+            event_type: event_type.to_string(),
+            run_id: run_id.to_string(),
+            vault_id: vault_id.to_string(),
+            actor,
+            details,
             prev_event_hash: String::new(),
             event_hash: String::new(),
         })?;
-        self.state = to;
         Ok(())
     }
 }
 
+// Adapters still running when the manager goes away are stopped with an audit record, so a run
+// dropped before reaching a terminal state does not leave an unrecorded stop behind.
+impl Drop for RunManager {
+    fn drop(&mut self) {
+        for adapter in &mut self.adapters {
+            let _ = adapter.shutdown(&mut self.audit);
+        }
+    }
+}
+
+// Preflight scratch sits beside the export target so concurrent runs never share a temp path.
+fn preflight_paths(bundle_dir: &Path, run_id: &str) -> (PathBuf, PathBuf) {
+    let parent = bundle_dir.parent().unwrap_or_else(|| Path::new("."));
+    (
+        parent.join(format!(".{}_preflight_bundle", run_id)),
+        parent.join(format!(".{}_preflight_bundle.zip", run_id)),
+    )
+}
+
+fn remove_scratch(root: &Path, zip: &Path) -> CoreResult<()> {
+    for path in [root, zip] {
+        if path.is_dir() {
+            std::fs::remove_dir_all(path)?;
+        } else if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn build_preflight(
+    root: &Path,
+    zip: &Path,
+    bundle_inputs: &EvidenceBundleInputs,
+) -> CoreResult<()> {
+    remove_scratch(root, zip)?;
+    EvidenceBundleBuilder::build_dir(root, bundle_inputs)?;
+    EvidenceBundleBuilder::build_zip(root, zip)?;
+    Ok(())
+}
+
+// Policy gate checks from evaluated gates.
+fn export_gate_inputs(
+    req: &ExportRequest,
+    gate_results: &[GateRunResult],
+    (model_catalog_violations, model_catalog_error): (Vec<CatalogViolation>, Option<String>),
+) -> ExportGateInputs {
+    ExportGateInputs {
+        policy_mode: req.policy_mode,
+        pinning_level: req.pinning_level,
+        citations_required_passed: gate_passed(gate_results, "CITATIONS.STRICT_ENFORCED_V1"),
+        redactions_required_passed: gate_passed(gate_results, "REDACTION.REQUIRED_APPLIED_V1"),
+        blocker_gate_failures: blocker_failures(gate_results),
+        determinism_passed: gate_passed(gate_results, "DETERMINISM.ZIP_PACKAGING_V1"),
+        network_mode: req.network_mode,
+        proof_level: req.proof_level,
+        model_catalog_violations,
+        model_catalog_error,
+    }
+}
+
+// Models the bundle records that the policy pack's catalog does not permit for this pack. A
+// catalog whose recorded hash does not match is reported instead of being applied.
+fn model_catalog_check(
+    req: &ExportRequest,
+    bundle_inputs: &EvidenceBundleInputs,
+) -> CoreResult<(Vec<CatalogViolation>, Option<String>)> {
+    let Some(snapshot) = &bundle_inputs.policy_snapshot.model_catalog else {
+        return Ok((vec![], None));
+    };
+    if let Err(e) = snapshot.verify() {
+        return Ok((vec![], Some(e.to_string())));
+    }
+    let uses = collect_model_uses(
+        Some(&bundle_inputs.model_snapshot),
+        &bundle_inputs.audit_log_ndjson,
+    )?;
+    let violations = snapshot
+        .catalog
+        .violations(&bundle_inputs.pack_id, req.policy_mode, &uses);
+    Ok((violations, None))
+}
+
+fn blocker_failures(gate_results: &[GateRunResult]) -> Vec<String> {
+    gate_results
+        .iter()
+        .filter(|g| g.severity == Severity::BLOCKER && g.result == GateStatus::FAIL)
+        .map(|g| g.gate_id.clone())
+        .collect()
+}
+
+fn gate_passed(gate_results: &[GateRunResult], gate_id: &str) -> bool {
+    gate_results
+        .iter()
+        .find(|g| g.gate_id == gate_id)
+        .map(|g| matches!(g.result, GateStatus::PASS | GateStatus::NOT_APPLICABLE))
+        .unwrap_or(true)
+}
+
 fn valid_transition(from: RunState, to: RunState) -> bool {
     use RunState::*;
     match (from, to) {
+        (COMPLETED | FAILED | CANCELLED, _) => false,
         (CREATED, INGESTING) => true,
+        (INGESTING, READY) => true,
         (CREATED, READY) => true,
         (READY, EXECUTING) => true,
         (EXECUTING, EVALUATING) => true,
         (READY, EVALUATING) => true,
         (EVALUATING, EXPORTING) => true,
-        (EVALUATING, FAILED) => true,
         (EXPORTING, COMPLETED) => true,
-        (EXPORTING, FAILED) => true,
+        (_, FAILED) => true,
         (_, CANCELLED) => true,
         _ => false,
     }
 }
 
-fn now_rfc3339_utc() -> String {
-    time::OffsetDateTime::now_utc()
-        .format(&time::format_description::well_known::Rfc3339)
-        .unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use super::{valid_transition, RunState};
@@ -357,5 +1109,7 @@ mod tests {
         assert!(valid_transition(RunState::CREATED, RunState::READY));
         assert!(!valid_transition(RunState::CREATED, RunState::EXPORTING));
         assert!(!valid_transition(RunState::COMPLETED, RunState::EVALUATING));
+        assert!(valid_transition(RunState::INGESTING, RunState::READY));
+        assert!(!valid_transition(RunState::CANCELLED, RunState::FAILED));
     }
 }
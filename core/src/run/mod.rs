@@ -1,2 +1,7 @@
+pub mod cancel;
+pub mod checkpoint;
 pub mod lifecycle;
 pub mod manager;
+pub mod registry;
+pub mod scheduler;
+pub mod sink;
@@ -0,0 +1,109 @@
+use crate::determinism::json_canonical;
+use crate::error::{CoreError, CoreResult};
+use crate::policy::types::{NetworkMode, PolicyMode};
+use crate::run::manager::RunState;
+use crate::storage::vault::VaultStorage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, PoisonError};
+
+// The registry lives in the vault blob store so it is encrypted at rest and re-wrapped on DEK rotation.
+const REGISTRY_BLOB_ID: &str = "run_registry";
+const REGISTRY_SCHEMA_VERSION: &str = "RUN_REGISTRY_V1";
+
+// Registries opened on the same vault by concurrent runs (e.g. scheduler workers) re-read the
+// blob before each change; this keeps their read-modify-write cycles from interleaving.
+static PERSIST_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RunRecord {
+    pub run_id: String,
+    pub vault_id: String,
+    pub pack_id: String,
+    pub pack_version: String,
+    pub state: RunState,
+    pub policy_mode: PolicyMode,
+    pub network_mode: NetworkMode,
+    #[serde(default)]
+    pub determinism_enabled: bool, // picks the audit clock when the run is resumed
+    pub audit_log_path: String,
+    pub artifact_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_zip: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RegistryDocument {
+    schema_version: String,
+    runs: Vec<RunRecord>, // sorted by run_id
+}
+
+pub struct RunRegistry {
+    vault: VaultStorage,
+    runs: BTreeMap<String, RunRecord>,
+}
+
+impl RunRegistry {
+    pub fn open(vault: VaultStorage) -> CoreResult<Self> {
+        let runs = load_runs(&vault)?;
+        Ok(Self { vault, runs })
+    }
+
+    pub fn vault(&self) -> &VaultStorage {
+        &self.vault
+    }
+
+    pub fn get(&self, run_id: &str) -> Option<&RunRecord> {
+        self.runs.get(run_id)
+    }
+
+    pub fn list(&self) -> Vec<&RunRecord> {
+        self.runs.values().collect()
+    }
+
+    pub fn upsert(&mut self, record: RunRecord) -> CoreResult<()> {
+        let _guard = PERSIST_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        self.runs = load_runs(&self.vault)?;
+        self.runs.insert(record.run_id.clone(), record);
+        self.persist()
+    }
+
+    pub fn update(&mut self, run_id: &str, f: impl FnOnce(&mut RunRecord)) -> CoreResult<()> {
+        let _guard = PERSIST_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        self.runs = load_runs(&self.vault)?;
+        let record = self
+            .runs
+            .get_mut(run_id)
+            .ok_or_else(|| CoreError::InvalidInput(format!("run not registered: {}", run_id)))?;
+        f(record);
+        self.persist()
+    }
+
+    fn persist(&self) -> CoreResult<()> {
+        let doc = RegistryDocument {
+            schema_version: REGISTRY_SCHEMA_VERSION.to_string(),
+            runs: self.runs.values().cloned().collect(),
+        };
+        let bytes = json_canonical::to_canonical_bytes(&doc)?;
+        self.vault.write_blob(REGISTRY_BLOB_ID, &bytes)
+    }
+}
+
+fn load_runs(vault: &VaultStorage) -> CoreResult<BTreeMap<String, RunRecord>> {
+    let mut runs = BTreeMap::new();
+    if vault.blob_exists(REGISTRY_BLOB_ID) {
+        let doc: RegistryDocument = serde_json::from_slice(&vault.read_blob(REGISTRY_BLOB_ID)?)?;
+        if doc.schema_version != REGISTRY_SCHEMA_VERSION {
+            return Err(CoreError::InputSchemaError(format!(
+                "unsupported run registry schema_version {}",
+                doc.schema_version
+            )));
+        }
+        for r in doc.runs {
+            runs.insert(r.run_id.clone(), r);
+        }
+    }
+    Ok(runs)
+}
@@ -0,0 +1,435 @@
+use crate::audit::log::AuditLog;
+use crate::determinism::clock::clock_for_profile;
+use crate::determinism::run_id::validate_run_id;
+use crate::error::{CoreError, CoreResult};
+use crate::evidence_bundle::schemas::EvidenceBundleInputs;
+use crate::run::cancel::CancellationToken;
+use crate::run::manager::{
+    CreateRunRequest, ExportOutcome, ExportRequest, ExportStatus, RunManager, RunState,
+};
+use crate::run::registry::{RunRecord, RunRegistry};
+use crate::storage::vault::VaultStorage;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::JoinHandle;
+
+/// Per-run directory layout under the scheduler's workspace root. Nothing is shared between runs.
+#[derive(Debug, Clone)]
+pub struct RunWorkspace {
+    pub root: PathBuf,
+    pub audit_log_path: PathBuf,
+    pub bundle_dir: PathBuf,
+    pub bundle_zip: PathBuf,
+    pub scratch_dir: PathBuf,
+}
+
+impl RunWorkspace {
+    fn new(workspace_root: &Path, run_id: &str) -> Self {
+        let root = workspace_root.join(run_id);
+        Self {
+            audit_log_path: root.join("audit_log.ndjson"),
+            bundle_dir: root.join("bundle"),
+            bundle_zip: root.join("bundle.zip"),
+            scratch_dir: root.join("scratch"),
+            root,
+        }
+    }
+}
+
+pub type ExecuteFn = Box<dyn FnOnce(&RunWorkspace) -> CoreResult<EvidenceBundleInputs> + Send>;
+
+/// One queued run: it is created like a manual run, pack execution produces the bundle
+/// inputs, then the run is exported. `create` and `export` must name the same run and vault.
+pub struct RunJob {
+    pub create: CreateRunRequest,
+    pub export: ExportRequest,
+    pub execute: ExecuteFn,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RunStatus {
+    QUEUED,
+    RUNNING,
+    COMPLETED,
+    BLOCKED,
+    FAILED,
+    CANCELLED,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunProgress {
+    pub run_id: String,
+    pub status: RunStatus,
+    pub state: Option<RunState>, // last observed RunState; None while queued
+    pub audit_log_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<ExportOutcome>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SchedulerSummary {
+    pub queued: usize,
+    pub running: usize,
+    pub completed: usize,
+    pub blocked: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+}
+
+struct SchedulerState {
+    queue: VecDeque<RunJob>,
+    progress: BTreeMap<String, RunProgress>,
+    tokens: BTreeMap<String, CancellationToken>,
+    running: usize,
+    shutting_down: bool,
+}
+
+struct Shared {
+    state: Mutex<SchedulerState>,
+    vault_root: Option<PathBuf>, // runs are registered in this vault's run registry
+    work_ready: Condvar,
+    idle: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, SchedulerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn set_progress(&self, run_id: &str, f: impl FnOnce(&mut RunProgress)) {
+        if let Some(p) = self.lock().progress.get_mut(run_id) {
+            f(p);
+        }
+    }
+}
+
+/// Runs many packs at once on a fixed pool of worker threads.
+pub struct RunScheduler {
+    workspace_root: PathBuf,
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl RunScheduler {
+    pub fn new(workspace_root: impl AsRef<Path>, max_workers: usize) -> CoreResult<Self> {
+        Self::start(workspace_root.as_ref(), max_workers, None)
+    }
+
+    /// Like `new`, but every run is also recorded in the run registry of the vault at
+    /// `vault_root`, so it can be listed and resumed like a manually created run.
+    pub fn with_registry(
+        workspace_root: impl AsRef<Path>,
+        max_workers: usize,
+        vault_root: impl AsRef<Path>,
+    ) -> CoreResult<Self> {
+        Self::start(
+            workspace_root.as_ref(),
+            max_workers,
+            Some(vault_root.as_ref().to_path_buf()),
+        )
+    }
+
+    fn start(
+        workspace_root: &Path,
+        max_workers: usize,
+        vault_root: Option<PathBuf>,
+    ) -> CoreResult<Self> {
+        if max_workers == 0 {
+            return Err(CoreError::InvalidInput(
+                "max_workers must be at least 1".to_string(),
+            ));
+        }
+        let workspace_root = workspace_root.to_path_buf();
+        std::fs::create_dir_all(&workspace_root)?;
+        let shared = Arc::new(Shared {
+            state: Mutex::new(SchedulerState {
+                queue: VecDeque::new(),
+                progress: BTreeMap::new(),
+                tokens: BTreeMap::new(),
+                running: 0,
+                shutting_down: false,
+            }),
+            vault_root,
+            work_ready: Condvar::new(),
+            idle: Condvar::new(),
+        });
+        let workers = (0..max_workers)
+            .map(|_| {
+                let shared = shared.clone();
+                let root = workspace_root.clone();
+                std::thread::spawn(move || worker_loop(&shared, &root))
+            })
+            .collect();
+        Ok(Self {
+            workspace_root,
+            shared,
+            workers,
+        })
+    }
+
+    /// Queues a run. Run ids name workspace directories, so they must be unique path-safe tokens.
+    pub fn submit(&self, job: RunJob) -> CoreResult<()> {
+        let run_id = job.export.run_id.clone();
+        validate_run_id(&run_id)?;
+        if job.create.run_id != run_id || job.create.vault_id != job.export.vault_id {
+            return Err(CoreError::InvalidInput(format!(
+                "create and export requests name different runs: {}",
+                run_id
+            )));
+        }
+        let mut st = self.shared.lock();
+        if st.shutting_down {
+            return Err(CoreError::WorkflowTransitionError(
+                "scheduler is shutting down".to_string(),
+            ));
+        }
+        if st.progress.contains_key(&run_id) {
+            return Err(CoreError::InvalidInput(format!(
+                "run already scheduled: {}",
+                run_id
+            )));
+        }
+        let workspace = RunWorkspace::new(&self.workspace_root, &run_id);
+        st.progress.insert(
+            run_id.clone(),
+            RunProgress {
+                run_id,
+                status: RunStatus::QUEUED,
+                state: None,
+                audit_log_path: workspace.audit_log_path.to_string_lossy().to_string(),
+                outcome: None,
+                error: None,
+            },
+        );
+        st.tokens
+            .insert(job.export.run_id.clone(), CancellationToken::new());
+        st.queue.push_back(job);
+        self.shared.work_ready.notify_one();
+        Ok(())
+    }
+
+    /// Cancels a run: queued runs are dropped, running ones abort at their next export step.
+    /// Returns false for unknown or already finished runs.
+    pub fn cancel(&self, run_id: &str) -> bool {
+        let mut st = self.shared.lock();
+        let Some(status) = st.progress.get(run_id).map(|p| p.status) else {
+            return false;
+        };
+        match status {
+            RunStatus::QUEUED => {
+                st.queue.retain(|j| j.export.run_id != run_id);
+                st.tokens.remove(run_id);
+                if let Some(p) = st.progress.get_mut(run_id) {
+                    p.status = RunStatus::CANCELLED;
+                }
+                if st.queue.is_empty() && st.running == 0 {
+                    self.shared.idle.notify_all();
+                }
+                true
+            }
+            RunStatus::RUNNING => {
+                if let Some(token) = st.tokens.get(run_id) {
+                    token.cancel();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Run ids still waiting for a worker, in submission order.
+    pub fn queued(&self) -> Vec<String> {
+        self.shared
+            .lock()
+            .queue
+            .iter()
+            .map(|j| j.export.run_id.clone())
+            .collect()
+    }
+
+    pub fn progress(&self) -> Vec<RunProgress> {
+        self.shared.lock().progress.values().cloned().collect()
+    }
+
+    pub fn run_progress(&self, run_id: &str) -> Option<RunProgress> {
+        self.shared.lock().progress.get(run_id).cloned()
+    }
+
+    pub fn summary(&self) -> SchedulerSummary {
+        let st = self.shared.lock();
+        let mut s = SchedulerSummary::default();
+        for p in st.progress.values() {
+            match p.status {
+                RunStatus::QUEUED => s.queued += 1,
+                RunStatus::RUNNING => s.running += 1,
+                RunStatus::COMPLETED => s.completed += 1,
+                RunStatus::BLOCKED => s.blocked += 1,
+                RunStatus::FAILED => s.failed += 1,
+                RunStatus::CANCELLED => s.cancelled += 1,
+            }
+        }
+        s
+    }
+
+    /// Blocks until the queue is drained and no run is in flight.
+    pub fn wait_idle(&self) {
+        let mut st = self.shared.lock();
+        while !st.queue.is_empty() || st.running > 0 {
+            st = self
+                .shared
+                .idle
+                .wait(st)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Finishes every queued run, stops the workers and returns the final progress.
+    pub fn shutdown(mut self) -> Vec<RunProgress> {
+        self.wait_idle();
+        self.stop_workers();
+        self.progress()
+    }
+
+    fn stop_workers(&mut self) {
+        self.shared.lock().shutting_down = true;
+        self.shared.work_ready.notify_all();
+        for w in self.workers.drain(..) {
+            let _ = w.join();
+        }
+    }
+}
+
+impl Drop for RunScheduler {
+    fn drop(&mut self) {
+        self.stop_workers();
+    }
+}
+
+fn worker_loop(shared: &Shared, workspace_root: &Path) {
+    loop {
+        // Claiming a job and marking it RUNNING happen under one lock so `cancel` sees either.
+        let (job, token) = {
+            let mut st = shared.lock();
+            loop {
+                if let Some(job) = st.queue.pop_front() {
+                    st.running += 1;
+                    let run_id = &job.export.run_id;
+                    if let Some(p) = st.progress.get_mut(run_id) {
+                        p.status = RunStatus::RUNNING;
+                    }
+                    let token = st.tokens.get(run_id).cloned().unwrap_or_default();
+                    break (job, token);
+                }
+                if st.shutting_down {
+                    return;
+                }
+                st = shared
+                    .work_ready
+                    .wait(st)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+        };
+        let run_id = job.export.run_id.clone();
+
+        let workspace = RunWorkspace::new(workspace_root, &run_id);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            execute_job(shared, &workspace, job, token)
+        }))
+        .unwrap_or_else(|_| Err(CoreError::InvalidInput("run job panicked".to_string())));
+        shared.set_progress(&run_id, |p| match result {
+            Ok(outcome) => {
+                p.status = match outcome.status {
+                    ExportStatus::COMPLETED => RunStatus::COMPLETED,
+                    ExportStatus::BLOCKED => RunStatus::BLOCKED,
+                    ExportStatus::FAILED => RunStatus::FAILED,
+                    ExportStatus::CANCELLED => RunStatus::CANCELLED,
+                };
+                p.outcome = Some(outcome);
+            }
+            Err(e) => {
+                p.status = RunStatus::FAILED;
+                p.error = Some(e.to_string());
+            }
+        });
+
+        let mut st = shared.lock();
+        st.tokens.remove(&run_id);
+        st.running -= 1;
+        if st.queue.is_empty() && st.running == 0 {
+            shared.idle.notify_all();
+        }
+    }
+}
+
+fn execute_job(
+    shared: &Shared,
+    ws: &RunWorkspace,
+    job: RunJob,
+    token: CancellationToken,
+) -> CoreResult<ExportOutcome> {
+    std::fs::create_dir_all(&ws.scratch_dir)?;
+    let req = job.export;
+    let audit = AuditLog::open_or_create(&ws.audit_log_path)?
+        .with_clock(clock_for_profile(job.create.determinism_enabled));
+    let mut mgr = RunManager::create_run(audit, &job.create)?.with_cancellation_token(token);
+    if let Some(vault_root) = &shared.vault_root {
+        mgr.attach_registry(
+            RunRegistry::open(VaultStorage::open(vault_root)?)?,
+            RunRecord {
+                run_id: req.run_id.clone(),
+                vault_id: req.vault_id.clone(),
+                pack_id: job.create.pack_id.clone(),
+                pack_version: job.create.pack_version.clone(),
+                state: mgr.state,
+                policy_mode: req.policy_mode,
+                network_mode: req.network_mode,
+                determinism_enabled: job.create.determinism_enabled,
+                audit_log_path: ws.audit_log_path.to_string_lossy().to_string(),
+                artifact_count: 0,
+                bundle_dir: None,
+                bundle_zip: None,
+            },
+        )?;
+    }
+    // Packs ingest inside `execute`, not through the manager, so nothing is ingested here.
+    mgr.complete_ingest(&req.run_id, &req.vault_id)?;
+    mgr.start_execution(&req.run_id, &req.vault_id)?;
+    shared.set_progress(&req.run_id, |p| p.state = Some(mgr.state));
+
+    let inputs = match (job.execute)(ws) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            mgr.fail(&req.run_id, &req.vault_id, "pack execution failed")?;
+            shared.set_progress(&req.run_id, |p| p.state = Some(mgr.state));
+            return Err(e);
+        }
+    };
+
+    if mgr.cancellation_token().is_cancelled() {
+        mgr.cancel(
+            &req.run_id,
+            &req.vault_id,
+            "cancelled during pack execution",
+        )?;
+        shared.set_progress(&req.run_id, |p| p.state = Some(mgr.state));
+        return Ok(ExportOutcome {
+            status: ExportStatus::CANCELLED,
+            bundle_path: None,
+            bundle_sha256: None,
+            block_reason: None,
+            block_reasons: vec![],
+            deliveries: vec![],
+        });
+    }
+
+    shared.set_progress(&req.run_id, |p| p.state = Some(RunState::EVALUATING));
+    let outcome = mgr.export_run(&req, &inputs, &ws.bundle_dir, &ws.bundle_zip);
+    shared.set_progress(&req.run_id, |p| p.state = Some(mgr.state));
+    outcome
+}
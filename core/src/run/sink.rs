@@ -0,0 +1,191 @@
+use crate::adapters::interface::enforce_loopback_endpoint;
+use crate::audit::log::AuditLog;
+use crate::determinism::run_id::sha256_hex;
+use crate::error::{CoreError, CoreResult};
+use crate::policy::egress::{EgressClient, EgressPolicy};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+
+/// A validated bundle handed to sinks before `EXPORT_COMPLETED` records their deliveries.
+#[derive(Debug, Clone)]
+pub struct CompletedBundle {
+    pub run_id: String,
+    pub vault_id: String,
+    pub bundle_zip: PathBuf,
+    pub bundle_sha256: String,
+}
+
+/// Post-export delivery target. Sinks run in registration order after validation passed;
+/// a failing sink is recorded and does not affect the others or the run outcome.
+pub trait ExportSink: Send {
+    fn sink_id(&self) -> &str;
+
+    /// Delivers the bundle and returns where it landed. `audit` is the run's log, for sinks
+    /// that must record their own events (e.g. egress decisions).
+    fn deliver(&self, bundle: &CompletedBundle, audit: &mut AuditLog) -> CoreResult<String>;
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    DELIVERED,
+    FAILED,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SinkDelivery {
+    pub sink_id: String,
+    pub status: DeliveryStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Copies the bundle zip into a directory under its original file name.
+pub struct LocalDirSink {
+    dir: PathBuf,
+}
+
+impl LocalDirSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl ExportSink for LocalDirSink {
+    fn sink_id(&self) -> &str {
+        "local_dir"
+    }
+
+    fn deliver(&self, bundle: &CompletedBundle, _audit: &mut AuditLog) -> CoreResult<String> {
+        let name = bundle
+            .bundle_zip
+            .file_name()
+            .ok_or_else(|| CoreError::InvalidInput("bundle path has no file name".to_string()))?;
+        let dest = self.dir.join(name);
+        let bytes = read_verified(bundle)?;
+        write_atomic(&dest, &bytes)?;
+        Ok(dest.to_string_lossy().to_string())
+    }
+}
+
+/// Stores bundles at `<root>/<sha[..2]>/<sha>.zip`. Re-delivering the same bundle is a no-op.
+pub struct ContentAddressedSink {
+    root: PathBuf,
+}
+
+impl ContentAddressedSink {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn path_for(&self, bundle_sha256: &str) -> PathBuf {
+        self.root
+            .join(&bundle_sha256[..2])
+            .join(format!("{}.zip", bundle_sha256))
+    }
+}
+
+impl ExportSink for ContentAddressedSink {
+    fn sink_id(&self) -> &str {
+        "content_addressed"
+    }
+
+    fn deliver(&self, bundle: &CompletedBundle, _audit: &mut AuditLog) -> CoreResult<String> {
+        let sha = &bundle.bundle_sha256;
+        if sha.len() != 64 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(CoreError::InvalidInput(
+                "bundle_sha256 must be 64 hex chars".to_string(),
+            ));
+        }
+        let dest = self.path_for(sha);
+        let stored = dest.is_file() && sha256_hex(&std::fs::read(&dest)?) == *sha;
+        if !stored {
+            write_atomic(&dest, &read_verified(bundle)?)?;
+        }
+        Ok(dest.to_string_lossy().to_string())
+    }
+}
+
+/// POSTs the bundle zip to a loopback HTTP endpoint through `EgressClient::send`, so it is
+/// decided against `policy` and recorded as EGRESS_REQUEST_ALLOWED/BLOCKED first.
+pub struct LoopbackHttpSink {
+    url: Url,
+    policy: EgressPolicy,
+    timeout: Duration,
+}
+
+impl LoopbackHttpSink {
+    pub fn new(url: &str, policy: EgressPolicy) -> CoreResult<Self> {
+        enforce_loopback_endpoint(url)?;
+        let url =
+            Url::parse(url).map_err(|_| CoreError::InvalidInput("invalid sink URL".to_string()))?;
+        Ok(Self {
+            url,
+            policy,
+            timeout: Duration::from_secs(30),
+        })
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl ExportSink for LoopbackHttpSink {
+    fn sink_id(&self) -> &str {
+        "loopback_http"
+    }
+
+    fn deliver(&self, bundle: &CompletedBundle, audit: &mut AuditLog) -> CoreResult<String> {
+        let bytes = read_verified(bundle)?;
+        let mut egress =
+            EgressClient::new(self.policy.clone(), audit, &bundle.run_id, &bundle.vault_id);
+        let resp = egress.send(
+            "POST",
+            self.url.as_str(),
+            &[
+                ("Content-Type", "application/zip"),
+                ("X-Bundle-Sha256", &bundle.bundle_sha256),
+            ],
+            &bytes,
+            self.timeout,
+        )?;
+        if !resp.is_success() {
+            return Err(CoreError::InvalidInput(format!(
+                "sink endpoint returned HTTP {}",
+                resp.status
+            )));
+        }
+        Ok(self.url.to_string())
+    }
+}
+
+// Sinks never ship bytes that differ from the validated bundle's sha256.
+fn read_verified(bundle: &CompletedBundle) -> CoreResult<Vec<u8>> {
+    let bytes = std::fs::read(&bundle.bundle_zip)?;
+    if sha256_hex(&bytes) != bundle.bundle_sha256 {
+        return Err(CoreError::InvalidInput(format!(
+            "bundle {} no longer matches its recorded sha256",
+            bundle.bundle_zip.display()
+        )));
+    }
+    Ok(bytes)
+}
+
+fn write_atomic(dest: &Path, bytes: &[u8]) -> CoreResult<()> {
+    let parent = dest.parent().unwrap_or_else(|| Path::new("."));
+    std::fs::create_dir_all(parent)?;
+    let name = dest
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    let partial = parent.join(format!(".{}.partial", name));
+    std::fs::write(&partial, bytes)?;
+    std::fs::rename(&partial, dest)?;
+    Ok(())
+}
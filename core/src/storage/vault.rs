@@ -85,13 +85,28 @@ impl VaultStorage {
         })
     }
 
+    pub fn vault_id(&self) -> &str {
+        &self.cfg.vault_id
+    }
+
+    /// Writes to a temporary file and renames it over the blob, so readers never see a partial one.
     pub fn write_blob(&self, blob_id: &str, plaintext: &[u8]) -> CoreResult<()> {
-        let path = self.root.join("blobs").join(format!("{}.bin", blob_id));
-        if self.cfg.encryption_at_rest {
+        let blobs = self.root.join("blobs");
+        let bytes = if self.cfg.encryption_at_rest {
             let enc = encrypt_bytes(self.cfg.encryption_algorithm, &self.dek, plaintext)?;
-            fs::write(path, serde_json::to_vec(&enc)?)?;
+            serde_json::to_vec(&enc)?
         } else {
-            fs::write(path, plaintext)?;
+            plaintext.to_vec()
+        };
+        let partial = blobs.join(format!(
+            ".{}.{}.partial",
+            blob_id,
+            hex::encode(rand::random::<[u8; 8]>())
+        ));
+        fs::write(&partial, bytes)?;
+        if let Err(e) = fs::rename(&partial, blobs.join(format!("{}.bin", blob_id))) {
+            let _ = fs::remove_file(&partial);
+            return Err(e.into());
         }
         Ok(())
     }
@@ -107,6 +122,22 @@ impl VaultStorage {
         }
     }
 
+    pub fn blob_exists(&self, blob_id: &str) -> bool {
+        self.root
+            .join("blobs")
+            .join(format!("{}.bin", blob_id))
+            .exists()
+    }
+
+    /// Removes a blob; a missing blob is not an error.
+    pub fn delete_blob(&self, blob_id: &str) -> CoreResult<()> {
+        let path = self.root.join("blobs").join(format!("{}.bin", blob_id));
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
     pub fn write_sqlite_bytes(&self, db_bytes: &[u8]) -> CoreResult<()> {
         let path = self.root.join("sqlite").join("vault.db.enc");
         if self.cfg.encryption_at_rest {
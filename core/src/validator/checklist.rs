@@ -9,11 +9,18 @@ pub struct Checklist {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChecklistCheck {
     pub check_id: String,
-    pub severity: String,
+    pub severity: Severity,
     pub description: String,
     pub validate: serde_json::Value,
 }
 
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Severity {
+    BLOCKER,
+    MAJOR,
+}
+
 pub fn checklist_v3() -> Checklist {
     // Embedded for reference/documentation; validator logic is implemented in code in mod.rs.
     // This ensures we still "ship" the checklist contract and can surface versions.
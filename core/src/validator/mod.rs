@@ -1,6 +1,7 @@
 pub mod checklist;
 
 use crate::error::{CoreError, CoreResult};
+use crate::validator::checklist::Severity;
 use crate::policy::types::PolicyMode;
 use serde::{Deserialize, Serialize};
 use sha2::Digest;
@@ -13,50 +14,57 @@ use zip::ZipArchive;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckResult {
     pub check_id: String,
-    pub severity: String,
-    pub result: String, // PASS|FAIL
+    pub severity: Severity,
+    pub result: CheckStatus,
     pub message: String,
 }
 
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CheckStatus {
+    PASS,
+    FAIL,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationSummary {
     pub checklist_version: String,
     pub policy: String,
-    pub overall: String, // PASS|FAIL
+    pub overall: CheckStatus,
     pub checks: Vec<CheckResult>,
 }
 
 impl ValidationSummary {
-    pub fn result_for_check(&self, check_id: &str) -> (String, String) {
+    pub fn result_for_check(&self, check_id: &str) -> (CheckStatus, String) {
         for c in &self.checks {
             if c.check_id == check_id {
-                return (c.result.clone(), c.message.clone());
+                return (c.result, c.message.clone());
             }
         }
         (
-            "FAIL".to_string(),
+            CheckStatus::FAIL,
             format!("missing check result for {}", check_id),
         )
     }
 
-    pub fn result_for_checks_prefix(&self, prefix: &str) -> (String, String) {
+    pub fn result_for_checks_prefix(&self, prefix: &str) -> (CheckStatus, String) {
         let mut any_fail = false;
         for c in &self.checks {
-            if c.check_id.starts_with(prefix) && c.result != "PASS" {
+            if c.check_id.starts_with(prefix) && c.result != CheckStatus::PASS {
                 any_fail = true;
             }
         }
         if any_fail {
             (
-                "FAIL".to_string(),
+                CheckStatus::FAIL,
                 format!("one or more {} checks failed", prefix),
             )
         } else {
-            ("PASS".to_string(), "ok".to_string())
+            (CheckStatus::PASS, "ok".to_string())
         }
     }
 
-    pub fn vault_crypto_gate_result(&self) -> String {
+    pub fn vault_crypto_gate_result(&self) -> CheckStatus {
         // Phase 2: we validate policy_snapshot says encryption_at_rest=true and algorithm allowed.
         self.result_for_check("CHK.VAULT_CRYPTO.POLICY_SNAPSHOT").0
     }
@@ -144,17 +152,17 @@ impl BundleValidator {
 
         let overall = if checks_out
             .iter()
-            .any(|c| c.severity == "BLOCKER" && c.result != "PASS")
+            .any(|c| c.severity == Severity::BLOCKER && c.result != CheckStatus::PASS)
         {
-            "FAIL"
+            CheckStatus::FAIL
         } else {
-            "PASS"
+            CheckStatus::PASS
         };
 
         Ok(ValidationSummary {
             checklist_version: self.checklist.checklist_version.clone(),
             policy: policy_s.to_string(),
-            overall: overall.to_string(),
+            overall,
             checks: checks_out,
         })
     }
@@ -189,15 +197,15 @@ fn check_required_files(paths: &BTreeSet<String>) -> CheckResult {
     if missing.is_empty() {
         CheckResult {
             check_id: "CHK.BUNDLE.REQUIRED_FILES".to_string(),
-            severity: "BLOCKER".to_string(),
-            result: "PASS".to_string(),
+            severity: Severity::BLOCKER,
+            result: CheckStatus::PASS,
             message: "ok".to_string(),
         }
     } else {
         CheckResult {
             check_id: "CHK.BUNDLE.REQUIRED_FILES".to_string(),
-            severity: "BLOCKER".to_string(),
-            result: "FAIL".to_string(),
+            severity: Severity::BLOCKER,
+            result: CheckStatus::FAIL,
             message: format!("missing: {}", missing.join(", ")),
         }
     }
@@ -215,15 +223,15 @@ fn check_exports_layout(paths: &BTreeSet<String>) -> CheckResult {
     if ok {
         CheckResult {
             check_id: "CHK.EXPORTS.ATTACHMENTS_LAYOUT".to_string(),
-            severity: "BLOCKER".to_string(),
-            result: "PASS".to_string(),
+            severity: Severity::BLOCKER,
+            result: CheckStatus::PASS,
             message: "ok".to_string(),
         }
     } else {
         CheckResult {
             check_id: "CHK.EXPORTS.ATTACHMENTS_LAYOUT".to_string(),
-            severity: "BLOCKER".to_string(),
-            result: "FAIL".to_string(),
+            severity: Severity::BLOCKER,
+            result: CheckStatus::FAIL,
             message: "missing templates_used.json under exports/**/attachments/".to_string(),
         }
     }
@@ -259,8 +267,8 @@ fn check_json_required_fields<R: Read + Seek>(
         Err(e) => {
             return CheckResult {
                 check_id: "CHK.NETWORK.SNAPSHOT_PRESENT".to_string(),
-                severity: "BLOCKER".to_string(),
-                result: "FAIL".to_string(),
+                severity: Severity::BLOCKER,
+                result: CheckStatus::FAIL,
                 message: format!("failed to read {}: {}", path, e),
             };
         }
@@ -285,15 +293,15 @@ fn check_json_required_fields<R: Read + Seek>(
     if missing.is_empty() {
         CheckResult {
             check_id: "CHK.NETWORK.SNAPSHOT_PRESENT".to_string(),
-            severity: "BLOCKER".to_string(),
-            result: "PASS".to_string(),
+            severity: Severity::BLOCKER,
+            result: CheckStatus::PASS,
             message: "ok".to_string(),
         }
     } else {
         CheckResult {
             check_id: "CHK.NETWORK.SNAPSHOT_PRESENT".to_string(),
-            severity: "BLOCKER".to_string(),
-            result: "FAIL".to_string(),
+            severity: Severity::BLOCKER,
+            result: CheckStatus::FAIL,
             message: format!("missing fields: {}", missing.join(", ")),
         }
     }
@@ -306,8 +314,8 @@ fn check_audit_chain<R: Read + Seek>(zip: &mut ZipArchive<R>) -> CheckResult {
         Err(e) => {
             return CheckResult {
                 check_id: "CHK.AUDIT.REQUIRED_KEYS_AND_CHAIN".to_string(),
-                severity: "BLOCKER".to_string(),
-                result: "FAIL".to_string(),
+                severity: Severity::BLOCKER,
+                result: CheckStatus::FAIL,
                 message: format!("failed to read audit_log.ndjson: {}", e),
             };
         }
@@ -553,8 +561,8 @@ fn check_citations_strict<R: Read + Seek>(
     if policy != PolicyMode::STRICT {
         return CheckResult {
             check_id: "CHK.CITATIONS.STRICT".to_string(),
-            severity: "BLOCKER".to_string(),
-            result: "PASS".to_string(),
+            severity: Severity::BLOCKER,
+            result: CheckStatus::PASS,
             message: "not applicable".to_string(),
         };
     }
@@ -661,8 +669,8 @@ fn check_redaction_policy_gate<R: Read + Seek>(
     if policy == PolicyMode::DRAFT_ONLY {
         return CheckResult {
             check_id: "CHK.REDACTION.POLICY_GATE".to_string(),
-            severity: "BLOCKER".to_string(),
-            result: "PASS".to_string(),
+            severity: Severity::BLOCKER,
+            result: CheckStatus::PASS,
             message: "not applicable".to_string(),
         };
     }
@@ -955,8 +963,8 @@ fn check_zip_determinism<R: Read + Seek>(zip: &mut ZipArchive<R>) -> CheckResult
     if !determinism_enabled {
         return CheckResult {
             check_id: "CHK.DETERMINISM.ZIP_RULES".to_string(),
-            severity: "MAJOR".to_string(),
-            result: "PASS".to_string(),
+            severity: Severity::MAJOR,
+            result: CheckStatus::PASS,
             message: "not applicable".to_string(),
         };
     }
@@ -1112,8 +1120,8 @@ fn check_vault_crypto_policy_snapshot<R: Read + Seek>(zip: &mut ZipArchive<R>) -
 fn pass(check_id: &str) -> CheckResult {
     CheckResult {
         check_id: check_id.to_string(),
-        severity: "BLOCKER".to_string(),
-        result: "PASS".to_string(),
+        severity: Severity::BLOCKER,
+        result: CheckStatus::PASS,
         message: "ok".to_string(),
     }
 }
@@ -1121,8 +1129,8 @@ fn pass(check_id: &str) -> CheckResult {
 fn fail(check_id: &str, msg: String) -> CheckResult {
     CheckResult {
         check_id: check_id.to_string(),
-        severity: "BLOCKER".to_string(),
-        result: "FAIL".to_string(),
+        severity: Severity::BLOCKER,
+        result: CheckStatus::FAIL,
         message: msg,
     }
 }
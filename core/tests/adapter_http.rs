@@ -0,0 +1,302 @@
+mod common;
+
+use aigc_core::adapters::http::HttpAdapterClient;
+use aigc_core::adapters::interface::{
+    parse_adapter_error, AdapterClient, ChatMessage, EmbedRequest, GenerateChunk, GenerateRequest,
+    ResolveModelRequest, SafetyProfile, StreamOptions, ADAPTER_ERROR_CATEGORIES,
+};
+use aigc_core::error::CoreError;
+use aigc_core::run::cancel::CancellationToken;
+use common::http_stub::{StubResponse, StubServer};
+use serde_json::json;
+use std::time::Duration;
+
+fn stub_adapter() -> StubServer {
+    StubServer::start(|req| match (req.method.as_str(), req.path.as_str()) {
+        ("GET", "/v1/health") => StubResponse::json(
+            200,
+            json!({"status": "ok", "adapter_id": "llm_local", "adapter_version": "0.3.1", "uptime_ms": 42}),
+        ),
+        ("GET", "/v1/capabilities") => StubResponse::json(
+            200,
+            json!({
+                "adapter_type": "LLM",
+                "features": ["json_schema"],
+                "limits": {"max_context_tokens": 8192},
+                "models": [{"model_id": "llama3-8b", "model_sha256": "ab".repeat(32), "context_window": 8192}]
+            }),
+        ),
+        ("POST", "/v1/models/resolve") => {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+            StubResponse::json(
+                200,
+                json!({
+                    "resolved_model": {"model_id": body["preferred_model"]},
+                    "rationale": "preferred model available"
+                }),
+            )
+        }
+        ("POST", "/v1/llm/generate") => StubResponse::json(
+            200,
+            json!({"status": "ok", "output_text": "whole answer", "usage": {"tokens_out": 2}}),
+        ),
+        _ => StubResponse::json(404, json!({"detail": "route not found"})),
+    })
+}
+
+fn adapter_category(err: CoreError) -> (String, bool) {
+    match err {
+        CoreError::Adapter(env) => (env.error.category, env.error.retryable),
+        other => panic!("expected adapter error, got {:?}", other),
+    }
+}
+
+#[test]
+fn http_client_speaks_annex_b_endpoints() {
+    let server = stub_adapter();
+    let client = HttpAdapterClient::new(&server.endpoint).unwrap();
+
+    let health = client.health().unwrap();
+    assert_eq!(health.adapter_id, "llm_local");
+    assert_eq!(health.adapter_version, "0.3.1");
+
+    let caps = client.capabilities().unwrap();
+    assert_eq!(caps.adapter_type, "LLM");
+    assert_eq!(caps.models[0].context_window, Some(8192));
+
+    let resolved = client
+        .resolve_model(ResolveModelRequest {
+            preferred_model: "llama3-8b".to_string(),
+            constraints: json!({"min_context_window": 4096}),
+        })
+        .unwrap();
+    assert_eq!(resolved.resolved_model.model_id, "llama3-8b");
+
+    let requests = server.requests();
+    let resolve = requests
+        .iter()
+        .find(|r| r.path == "/v1/models/resolve")
+        .unwrap();
+    let sent: serde_json::Value = serde_json::from_slice(&resolve.body).unwrap();
+    assert_eq!(sent["constraints"]["min_context_window"], 4096);
+}
+
+#[test]
+fn http_client_is_loopback_only() {
+    assert!(HttpAdapterClient::new("http://192.168.1.8:11434").is_err());
+    assert!(HttpAdapterClient::new("http://localhost.example.com:11434").is_err());
+    assert!(HttpAdapterClient::new("http://127.0.0.1:11434/").is_ok());
+}
+
+#[test]
+fn http_client_classifies_failures() {
+    let server = StubServer::start(|req| match req.path.as_str() {
+        "/v1/health" => {
+            StubResponse::json(200, json!({"status": "ok"})).delayed(Duration::from_millis(500))
+        }
+        "/v1/capabilities" => StubResponse::json(500, json!({"detail": "boom"})),
+        "/v1/llm/generate" => StubResponse::json(507, json!({"detail": "weights do not fit"})),
+        "/v1/emb/embed" => StubResponse::json(413, json!({"detail": "batch too large"})),
+        _ => StubResponse::json(404, json!({"detail": "model not found"})),
+    });
+    let client = HttpAdapterClient::new(&server.endpoint)
+        .unwrap()
+        .with_timeout(Duration::from_millis(100));
+
+    assert_eq!(
+        adapter_category(client.health().unwrap_err()),
+        ("TIMEOUT".to_string(), true)
+    );
+    assert_eq!(
+        adapter_category(client.capabilities().unwrap_err()),
+        ("RUNTIME_ERROR".to_string(), false)
+    );
+    let err = client
+        .resolve_model(ResolveModelRequest {
+            preferred_model: "missing".to_string(),
+            constraints: json!({}),
+        })
+        .unwrap_err();
+    assert_eq!(adapter_category(err).0, "MODEL_NOT_FOUND");
+    // Bodies without an envelope are classified from the HTTP status where it is unambiguous.
+    assert_eq!(
+        adapter_category(client.generate(&stream_request()).unwrap_err()),
+        ("OUT_OF_MEMORY".to_string(), false)
+    );
+    let err = client
+        .embed(&EmbedRequest {
+            call_id: "c_embed".to_string(),
+            model_id: "bge-small".to_string(),
+            texts: vec!["chunk".to_string()],
+            normalize: false,
+        })
+        .unwrap_err();
+    assert_eq!(adapter_category(err).0, "INVALID_INPUT");
+
+    // Nothing listening: the connection error is still reported as an adapter error.
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let closed = HttpAdapterClient::new(&format!("http://127.0.0.1:{}", port)).unwrap();
+    assert_eq!(
+        adapter_category(closed.health().unwrap_err()).0,
+        "RUNTIME_ERROR"
+    );
+
+    // A malformed success body is not silently accepted.
+    let bad = StubServer::start(|_| StubResponse::json(200, json!({"unexpected": true})));
+    let client = HttpAdapterClient::new(&bad.endpoint).unwrap();
+    assert_eq!(
+        adapter_category(client.health().unwrap_err()).0,
+        "RUNTIME_ERROR"
+    );
+}
+
+#[test]
+fn http_client_reads_adapter_error_envelopes() {
+    let server = StubServer::start(|req| {
+        let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+        let category = body["preferred_model"].as_str().unwrap().to_string();
+        let status = if category == "OUT_OF_MEMORY" {
+            503
+        } else {
+            422
+        };
+        StubResponse::json(
+            status,
+            json!({"error": {
+                "code": format!("E_{}", category),
+                "message": "adapter reported a failure",
+                "retryable": category == "OUT_OF_MEMORY",
+                "category": category,
+                "details": {"model_id": "llama3-70b", "required_mb": 40960}
+            }}),
+        )
+    });
+    let client = HttpAdapterClient::new(&server.endpoint).unwrap();
+    let resolve = |category: &str| {
+        client
+            .resolve_model(ResolveModelRequest {
+                preferred_model: category.to_string(),
+                constraints: json!({}),
+            })
+            .unwrap_err()
+    };
+
+    for category in ADAPTER_ERROR_CATEGORIES {
+        let CoreError::Adapter(env) = resolve(category) else {
+            panic!("expected adapter error for {}", category);
+        };
+        assert_eq!(env.error.category, category);
+        assert_eq!(env.error.code, format!("E_{}", category));
+        assert_eq!(env.error.retryable, category == "OUT_OF_MEMORY");
+        assert_eq!(env.error.details["required_mb"], 40960);
+    }
+    // An unknown category is not a conforming envelope; the HTTP status decides.
+    let CoreError::Adapter(env) = resolve("DISK_FULL") else {
+        panic!("expected adapter error");
+    };
+    assert_eq!(env.error.category, "INVALID_INPUT");
+    assert!(env.error.message.contains("HTTP 422"));
+}
+
+#[test]
+fn error_envelope_parsing_requires_a_conforming_body() {
+    let env = parse_adapter_error(
+        br#"{"error":{"code":"OOM","message":"m","retryable":false,"category":"OUT_OF_MEMORY","details":{"x":1}}}"#,
+    )
+    .unwrap();
+    assert_eq!(env.error.details["x"], 1);
+    assert!(parse_adapter_error(br#"{"detail":"boom"}"#).is_none());
+    assert!(parse_adapter_error(
+        br#"{"error":{"code":"","message":"m","retryable":false,"category":"TIMEOUT","details":{}}}"#
+    )
+    .is_none());
+    assert!(parse_adapter_error(b"Internal Server Error").is_none());
+}
+
+fn stream_request() -> GenerateRequest {
+    GenerateRequest {
+        call_id: "c_stream".to_string(),
+        model_id: "llama3-8b".to_string(),
+        messages: vec![ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }],
+        response_mode: "TEXT".to_string(),
+        json_schema: None,
+        temperature: None,
+        top_p: None,
+        max_tokens: None,
+        seed: None,
+        context_chunks: vec![],
+        safety_profile: SafetyProfile::default(),
+    }
+}
+
+fn stream_options() -> StreamOptions {
+    StreamOptions {
+        timeout: Duration::from_secs(5),
+        cancel: CancellationToken::new(),
+    }
+}
+
+#[test]
+fn error_envelopes_end_a_generate_stream() {
+    let server = StubServer::start(|req| match req.path.as_str() {
+        "/v1/capabilities" => StubResponse::json(
+            200,
+            json!({"adapter_type": "LLM", "features": ["streaming"], "limits": {}, "models": []}),
+        ),
+        _ => StubResponse::ndjson_stream(
+            vec![
+                json!({"delta": "partial "}),
+                json!({"error": {
+                    "code": "SAFETY_BLOCK",
+                    "message": "output blocked",
+                    "retryable": false,
+                    "category": "SAFETY_REFUSAL",
+                    "details": {"rule": "pii"}
+                }}),
+            ],
+            Duration::ZERO,
+        ),
+    });
+    let client = HttpAdapterClient::new(&server.endpoint).unwrap();
+    let req = stream_request();
+    let mut stream = client.generate_stream(&req, &stream_options()).unwrap();
+    assert!(matches!(
+        stream.next_chunk().unwrap(),
+        Some(GenerateChunk::Delta(_))
+    ));
+    let CoreError::Adapter(env) = stream.next_chunk().unwrap_err() else {
+        panic!("expected adapter error");
+    };
+    assert_eq!(env.error.category, "SAFETY_REFUSAL");
+    assert_eq!(env.error.details["rule"], "pii");
+}
+
+#[test]
+fn adapters_without_streaming_get_a_buffered_generate() {
+    let server = stub_adapter();
+    let client = HttpAdapterClient::new(&server.endpoint).unwrap();
+    let opts = stream_options();
+    let mut chunks = Vec::new();
+    for _ in 0..2 {
+        let mut stream = client.generate_stream(&stream_request(), &opts).unwrap();
+        while let Some(chunk) = stream.next_chunk().unwrap() {
+            chunks.push(chunk);
+        }
+    }
+    assert!(matches!(&chunks[0], GenerateChunk::Delta(t) if t == "whole answer"));
+    assert!(matches!(&chunks[1], GenerateChunk::Done { status, .. } if status == "ok"));
+    assert_eq!(chunks.len(), 4);
+    // Capabilities are read once per client; no request ever reaches the stream endpoint.
+    let paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+    assert_eq!(
+        paths,
+        ["/v1/capabilities", "/v1/llm/generate", "/v1/llm/generate"]
+    );
+}
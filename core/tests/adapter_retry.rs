@@ -0,0 +1,265 @@
+use aigc_core::adapters::calls::ModelCallScope;
+use aigc_core::adapters::interface::{
+    classify_adapter_error, AdapterCapabilitiesResponse, AdapterClient, AdapterHealthResponse,
+    ChatMessage, GenerateRequest, GenerateResponse, ResolveModelRequest, ResolveModelResponse,
+    SafetyProfile,
+};
+use aigc_core::adapters::retry::{
+    CircuitBreaker, CircuitState, RetryBudget, RetryPolicy, RetryingClient,
+};
+use aigc_core::audit::log::AuditLog;
+use aigc_core::error::{CoreError, CoreResult};
+use aigc_core::run::manager::RunManager;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Fails generate calls with the scripted errors in order, then succeeds.
+#[derive(Clone, Default)]
+struct ScriptedAdapter {
+    failures: Arc<Mutex<VecDeque<&'static str>>>,
+    calls: Arc<AtomicUsize>,
+}
+
+impl ScriptedAdapter {
+    fn failing(errors: &[&'static str]) -> Self {
+        Self {
+            failures: Arc::new(Mutex::new(errors.iter().copied().collect())),
+            ..Self::default()
+        }
+    }
+
+    fn next_failure(&self) -> Option<CoreError> {
+        let err = self.failures.lock().unwrap().pop_front()?;
+        Some(CoreError::Adapter(Box::new(classify_adapter_error(err))))
+    }
+}
+
+impl AdapterClient for ScriptedAdapter {
+    fn endpoint(&self) -> &str {
+        "http://127.0.0.1:11434"
+    }
+
+    fn health(&self) -> CoreResult<AdapterHealthResponse> {
+        Ok(AdapterHealthResponse {
+            status: "ok".to_string(),
+            adapter_id: "llm_local".to_string(),
+            adapter_version: "0.3.1".to_string(),
+            uptime_ms: 1,
+        })
+    }
+
+    fn capabilities(&self) -> CoreResult<AdapterCapabilitiesResponse> {
+        Err(CoreError::InvalidInput("not used".to_string()))
+    }
+
+    fn resolve_model(&self, _req: ResolveModelRequest) -> CoreResult<ResolveModelResponse> {
+        Err(CoreError::InvalidInput("not used".to_string()))
+    }
+
+    fn generate(&self, _req: &GenerateRequest) -> CoreResult<GenerateResponse> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if let Some(err) = self.next_failure() {
+            return Err(err);
+        }
+        Ok(GenerateResponse {
+            output_text: Some("done".to_string()),
+            output_json: None,
+            usage: serde_json::json!({}),
+            timing_ms: 1,
+            output_hash: None,
+            status: "ok".to_string(),
+        })
+    }
+}
+
+fn policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_backoff: Duration::from_millis(5),
+        max_backoff: Duration::from_millis(20),
+        multiplier: 2,
+    }
+}
+
+fn scope() -> ModelCallScope {
+    ModelCallScope {
+        run_id: "r_retry".to_string(),
+        vault_id: "v_0001".to_string(),
+        adapter_version: "0.3.1".to_string(),
+        input_artifact_refs: vec![],
+    }
+}
+
+fn request(call_id: &str) -> GenerateRequest {
+    GenerateRequest {
+        call_id: call_id.to_string(),
+        model_id: "llama3-8b".to_string(),
+        messages: vec![ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }],
+        response_mode: "TEXT".to_string(),
+        json_schema: None,
+        temperature: None,
+        top_p: None,
+        max_tokens: None,
+        seed: None,
+        context_chunks: vec![],
+        safety_profile: SafetyProfile::default(),
+    }
+}
+
+fn events(path: &Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+#[test]
+fn backoff_grows_exponentially_up_to_the_cap() {
+    let p = policy(5);
+    let waits: Vec<u128> = (1..=4).map(|a| p.backoff(a).as_millis()).collect();
+    assert_eq!(waits, [5, 10, 20, 20]);
+}
+
+#[test]
+fn retryable_failures_are_retried_and_each_attempt_is_audited() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+    let budget = RetryBudget::new(3);
+    let adapter = ScriptedAdapter::failing(&["adapter timeout", "adapter timeout"]);
+    let client = RetryingClient::new(
+        adapter.clone(),
+        policy(3),
+        budget.clone(),
+        CircuitBreaker::new(5, Duration::from_secs(60)),
+    );
+
+    let resp = mgr.generate(&client, &scope(), &request("c_1")).unwrap();
+    assert_eq!(resp.output_text.as_deref(), Some("done"));
+    assert_eq!(adapter.calls.load(Ordering::SeqCst), 3);
+    assert_eq!(budget.remaining(), 1);
+
+    let evs = events(&audit_path);
+    let types: Vec<&str> = evs
+        .iter()
+        .map(|e| e["event_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        types,
+        [
+            "MODEL_CALL_STARTED",
+            "MODEL_CALL_FAILED",
+            "MODEL_CALL_FAILED",
+            "MODEL_CALL_COMPLETED",
+        ]
+    );
+    for (i, ev) in evs[1..3].iter().enumerate() {
+        let d = &ev["details"];
+        assert_eq!(d["call_id"], "c_1");
+        assert_eq!(d["error_category"], "TIMEOUT");
+        assert_eq!(d["meta"]["attempt"], i as u64 + 1);
+        assert_eq!(d["meta"]["will_retry"], true);
+    }
+    assert_eq!(evs[1]["details"]["meta"]["backoff_ms"], 5);
+    assert_eq!(evs[2]["details"]["meta"]["backoff_ms"], 10);
+    assert_eq!(evs[3]["details"]["meta"]["attempts"], 3);
+    assert_eq!(mgr.model_calls().calls().len(), 1);
+
+    // Non-retryable errors fail on the first attempt.
+    adapter
+        .failures
+        .lock()
+        .unwrap()
+        .push_back("model not found");
+    assert!(mgr.generate(&client, &scope(), &request("c_2")).is_err());
+    let last = events(&audit_path).pop().unwrap();
+    assert_eq!(last["details"]["meta"]["attempt"], 1);
+    assert_eq!(last["details"]["meta"]["will_retry"], false);
+    assert_eq!(last["details"]["meta"]["retry_stop"], "NOT_RETRYABLE");
+}
+
+#[test]
+fn retries_stop_at_max_attempts_and_when_the_run_budget_is_spent() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+    let adapter = ScriptedAdapter::failing(&["adapter timeout"; 5]);
+    let client = RetryingClient::new(
+        adapter.clone(),
+        policy(2),
+        RetryBudget::new(2),
+        CircuitBreaker::new(10, Duration::from_secs(60)),
+    );
+
+    assert!(mgr.generate(&client, &scope(), &request("c_1")).is_err());
+    assert!(mgr.generate(&client, &scope(), &request("c_2")).is_err());
+    assert!(mgr.generate(&client, &scope(), &request("c_3")).is_err());
+    assert_eq!(adapter.calls.load(Ordering::SeqCst), 5);
+
+    let stops: Vec<(String, serde_json::Value)> = events(&audit_path)
+        .into_iter()
+        .filter(|e| e["event_type"] == "MODEL_CALL_FAILED")
+        .map(|e| {
+            let d = &e["details"];
+            (
+                d["call_id"].as_str().unwrap().to_string(),
+                d["meta"]["retry_stop"].clone(),
+            )
+        })
+        .collect();
+    let expected = [
+        ("c_1", serde_json::Value::Null),
+        ("c_1", "MAX_ATTEMPTS".into()),
+        ("c_2", serde_json::Value::Null),
+        ("c_2", "MAX_ATTEMPTS".into()),
+        ("c_3", "BUDGET_EXHAUSTED".into()),
+    ];
+    let expected: Vec<(String, serde_json::Value)> = expected
+        .into_iter()
+        .map(|(c, s)| (c.to_string(), s))
+        .collect();
+    assert_eq!(stops, expected);
+}
+
+#[test]
+fn circuit_breaker_opens_after_repeated_failures_and_recovers() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+    let adapter = ScriptedAdapter::failing(&["connection reset"; 3]);
+    let breaker = CircuitBreaker::new(3, Duration::from_millis(50));
+    let client = RetryingClient::new(
+        adapter.clone(),
+        policy(1),
+        RetryBudget::new(0),
+        breaker.clone(),
+    );
+
+    for i in 0..3 {
+        assert!(mgr
+            .generate(&client, &scope(), &request(&format!("c_{}", i)))
+            .is_err());
+    }
+    assert_eq!(breaker.state(), CircuitState::OPEN);
+    // Open: calls and health probes fail fast without reaching the adapter.
+    assert!(client.health().is_err());
+    assert!(mgr.generate(&client, &scope(), &request("c_open")).is_err());
+    assert_eq!(adapter.calls.load(Ordering::SeqCst), 3);
+    let last = events(&audit_path).pop().unwrap();
+    assert_eq!(last["details"]["error_category"], "ADAPTER_UNAVAILABLE");
+    assert_eq!(last["details"]["error_code"], "CIRCUIT_OPEN");
+
+    // After the cool-down one trial call goes through and closes the circuit.
+    std::thread::sleep(Duration::from_millis(60));
+    mgr.generate(&client, &scope(), &request("c_trial"))
+        .unwrap();
+    assert_eq!(breaker.state(), CircuitState::CLOSED);
+    assert!(client.health().is_ok());
+}
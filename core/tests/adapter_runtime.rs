@@ -1,14 +1,43 @@
+use aigc_core::adapters::calls::{ModelCallLog, ModelCallScope};
 use aigc_core::adapters::interface::{
-    AdapterCapabilitiesResponse, AdapterClient, AdapterHealthResponse, AdapterModel,
-    ResolveModelRequest, ResolveModelResponse,
+    classify_adapter_error, classify_adapter_status, AdapterCapabilitiesResponse, AdapterClient,
+    AdapterHealthResponse, AdapterModel, ChatMessage, GenerateRequest, GenerateResponse,
+    ResolveModelRequest, ResolveModelResponse, SafetyProfile,
 };
 use aigc_core::adapters::runtime::AdapterRuntime;
-use aigc_core::error::CoreResult;
+use aigc_core::audit::log::AuditLog;
+use aigc_core::error::{CoreError, CoreResult};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone)]
 struct FakeAdapter {
     endpoint: String,
     adapter_id: String,
+    adapter_type: String,
+    features: Vec<String>,
+    health_probes: Arc<AtomicUsize>,
+    up: Arc<AtomicBool>,
+}
+
+fn fake(adapter_id: &str, endpoint: &str) -> FakeAdapter {
+    FakeAdapter {
+        endpoint: endpoint.to_string(),
+        adapter_id: adapter_id.to_string(),
+        adapter_type: "LLM".to_string(),
+        features: vec!["json_schema".to_string()],
+        health_probes: Arc::new(AtomicUsize::new(0)),
+        up: Arc::new(AtomicBool::new(true)),
+    }
+}
+
+fn fake_typed(adapter_id: &str, adapter_type: &str, features: &[&str]) -> FakeAdapter {
+    FakeAdapter {
+        adapter_type: adapter_type.to_string(),
+        features: features.iter().map(|f| f.to_string()).collect(),
+        ..fake(adapter_id, "http://127.0.0.1:1234")
+    }
 }
 
 impl AdapterClient for FakeAdapter {
@@ -17,6 +46,10 @@ impl AdapterClient for FakeAdapter {
     }
 
     fn health(&self) -> CoreResult<AdapterHealthResponse> {
+        self.health_probes.fetch_add(1, Ordering::SeqCst);
+        if !self.up.load(Ordering::SeqCst) {
+            return Err(CoreError::InvalidInput("adapter down".to_string()));
+        }
         Ok(AdapterHealthResponse {
             status: "ok".to_string(),
             adapter_id: self.adapter_id.clone(),
@@ -27,8 +60,8 @@ impl AdapterClient for FakeAdapter {
 
     fn capabilities(&self) -> CoreResult<AdapterCapabilitiesResponse> {
         Ok(AdapterCapabilitiesResponse {
-            adapter_type: "LLM".to_string(),
-            features: vec!["json_schema".to_string()],
+            adapter_type: self.adapter_type.clone(),
+            features: self.features.clone(),
             limits: serde_json::json!({"max_input_bytes": 1}),
             models: vec![AdapterModel {
                 model_id: "m".to_string(),
@@ -41,6 +74,9 @@ impl AdapterClient for FakeAdapter {
     }
 
     fn resolve_model(&self, _req: ResolveModelRequest) -> CoreResult<ResolveModelResponse> {
+        if !self.up.load(Ordering::SeqCst) {
+            return Err(CoreError::InvalidInput("adapter down".to_string()));
+        }
         Ok(ResolveModelResponse {
             resolved_model: AdapterModel {
                 model_id: "m".to_string(),
@@ -52,24 +88,178 @@ impl AdapterClient for FakeAdapter {
             rationale: "ok".to_string(),
         })
     }
+
+    // A request the adapter rejects is INVALID_INPUT; a down adapter times out.
+    fn generate(&self, req: &GenerateRequest) -> CoreResult<GenerateResponse> {
+        let env = if !self.up.load(Ordering::SeqCst) {
+            classify_adapter_error("timeout calling generate")
+        } else {
+            classify_adapter_status(400, &format!("{} rejected", req.call_id))
+        };
+        Err(CoreError::Adapter(Box::new(env)))
+    }
 }
 
 #[test]
 fn runtime_rejects_non_loopback_adapter_endpoint() {
-    let rt = AdapterRuntime::new(vec![FakeAdapter {
-        endpoint: "http://8.8.8.8:1234".to_string(),
-        adapter_id: "a1".to_string(),
-    }]);
+    let rt = AdapterRuntime::new(vec![fake("a1", "http://8.8.8.8:1234")]);
     assert!(rt.validate_loopback_only().is_err());
 }
 
 #[test]
 fn runtime_returns_health_and_capabilities() {
-    let rt = AdapterRuntime::new(vec![FakeAdapter {
-        endpoint: "http://127.0.0.1:1234".to_string(),
-        adapter_id: "a1".to_string(),
-    }]);
+    let rt = AdapterRuntime::new(vec![fake("a1", "http://127.0.0.1:1234")]);
     assert!(rt.validate_loopback_only().is_ok());
     assert_eq!(rt.health_all().unwrap().len(), 1);
     assert_eq!(rt.capabilities_all().unwrap().len(), 1);
 }
+
+fn resolve_req() -> ResolveModelRequest {
+    ResolveModelRequest {
+        preferred_model: "m".to_string(),
+        constraints: serde_json::json!({}),
+    }
+}
+
+fn probes(adapters: &[FakeAdapter]) -> usize {
+    adapters
+        .iter()
+        .map(|a| a.health_probes.load(Ordering::SeqCst))
+        .sum()
+}
+
+#[test]
+fn registry_is_probed_once_and_indexed() {
+    let adapters = vec![
+        fake_typed("llm_local", "LLM", &["json_schema", "streaming"]),
+        fake_typed("stt_local", "STT", &["timestamps"]),
+        fake_typed("emb_local", "EMB", &[]),
+    ];
+    let rt = AdapterRuntime::new(adapters.clone());
+
+    for _ in 0..5 {
+        rt.resolve_model_for("stt_local", resolve_req()).unwrap();
+    }
+    assert_eq!(probes(&adapters), 3);
+
+    let ids: Vec<String> = rt
+        .registered()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.adapter_id)
+        .collect();
+    assert_eq!(ids, ["emb_local", "llm_local", "stt_local"]);
+    assert_eq!(
+        rt.adapters_of_type("STT").unwrap()[0].adapter_id,
+        "stt_local"
+    );
+    assert_eq!(
+        rt.adapters_with_feature("streaming").unwrap()[0].adapter_id,
+        "llm_local"
+    );
+    assert!(rt.adapters_with_feature("diarization").unwrap().is_empty());
+    assert_eq!(
+        rt.adapter("llm_local").unwrap().unwrap().adapter_version,
+        "1.0.0"
+    );
+    assert!(rt.resolve_model_for("vlm_local", resolve_req()).is_err());
+    assert_eq!(probes(&adapters), 3 + 3);
+}
+
+#[test]
+fn registry_refreshes_on_ttl_and_failure() {
+    let adapters = vec![
+        fake_typed("llm_local", "LLM", &[]),
+        fake_typed("stt_local", "STT", &[]),
+    ];
+    let rt = AdapterRuntime::new(adapters.clone()).with_registry_ttl(Duration::ZERO);
+    rt.adapters_of_type("LLM").unwrap();
+    rt.adapters_of_type("LLM").unwrap();
+    assert_eq!(probes(&adapters), 4);
+
+    let rt = AdapterRuntime::new(adapters.clone());
+    rt.registered().unwrap();
+    adapters[1].up.store(false, Ordering::SeqCst);
+    assert!(rt.resolve_model_for("stt_local", resolve_req()).is_err());
+    // The failed call forced a re-probe: the down adapter drops out of the registry.
+    assert!(rt.adapter("stt_local").unwrap().is_none());
+    assert_eq!(rt.registered().unwrap().len(), 1);
+
+    adapters[1].up.store(true, Ordering::SeqCst);
+    assert!(rt.resolve_model_for("stt_local", resolve_req()).is_ok());
+}
+
+#[test]
+fn unknown_adapter_ids_reprobe_at_most_once_per_ttl() {
+    let adapters = vec![fake_typed("llm_local", "LLM", &[])];
+    let rt = AdapterRuntime::new(adapters.clone());
+    rt.registered().unwrap();
+    assert_eq!(probes(&adapters), 1);
+
+    for id in ["vlm_local", "vlm_local", "stt_local"] {
+        assert!(rt.client(id).is_err());
+    }
+    // Only the first miss re-probed; the rest are answered from the registry.
+    assert_eq!(probes(&adapters), 2);
+    assert!(rt.client("llm_local").is_ok());
+
+    // A registry rebuilt after invalidation allows one miss re-probe again.
+    rt.invalidate_registry();
+    assert!(rt.client("vlm_local").is_err());
+    assert_eq!(probes(&adapters), 3);
+    for _ in 0..3 {
+        assert!(rt.client("vlm_local").is_err());
+    }
+    assert_eq!(probes(&adapters), 4);
+}
+
+fn generate_req(call_id: &str) -> GenerateRequest {
+    GenerateRequest {
+        call_id: call_id.to_string(),
+        model_id: "m".to_string(),
+        messages: vec![ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }],
+        response_mode: "TEXT".to_string(),
+        json_schema: None,
+        temperature: None,
+        top_p: None,
+        max_tokens: None,
+        seed: None,
+        context_chunks: vec![],
+        safety_profile: SafetyProfile::default(),
+    }
+}
+
+#[test]
+fn adapter_faults_on_model_calls_invalidate_the_registry() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut audit = AuditLog::open_or_create(&dir.path().join("audit.ndjson")).unwrap();
+    let mut calls = ModelCallLog::new();
+    let scope = ModelCallScope {
+        run_id: "r_faults".to_string(),
+        vault_id: "v_0001".to_string(),
+        adapter_version: "1.0.0".to_string(),
+        input_artifact_refs: vec![],
+    };
+    let adapters = vec![fake_typed("llm_local", "LLM", &[])];
+    let rt = AdapterRuntime::new(adapters.clone());
+    let client = rt.call_client("llm_local").unwrap();
+    assert_eq!(probes(&adapters), 1);
+
+    // A rejected request says nothing about the adapter; the registry is kept.
+    assert!(calls
+        .generate(&mut audit, &scope, &client, &generate_req("c_bad"))
+        .is_err());
+    assert!(rt.adapter("llm_local").unwrap().is_some());
+    assert_eq!(probes(&adapters), 1);
+
+    // The adapter goes away mid-run: the timed-out call forces a re-probe on the next lookup.
+    adapters[0].up.store(false, Ordering::SeqCst);
+    assert!(calls
+        .generate(&mut audit, &scope, &client, &generate_req("c_down"))
+        .is_err());
+    assert!(rt.adapter("llm_local").unwrap().is_none());
+    assert_eq!(probes(&adapters), 2);
+}
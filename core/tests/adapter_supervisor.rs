@@ -0,0 +1,317 @@
+mod common;
+
+use aigc_core::adapters::calls::ModelCallScope;
+use aigc_core::adapters::interface::{AdapterClient, ChatMessage, GenerateRequest, SafetyProfile};
+use aigc_core::adapters::pinning::{ModelSnapshot, PinningLevel};
+use aigc_core::adapters::supervisor::{
+    AdapterLaunchConfig, AdapterSupervisor, SupervisorScope, ADAPTER_LISTEN_ENV,
+};
+use aigc_core::audit::log::AuditLog;
+use aigc_core::error::CoreError;
+use aigc_core::run::manager::RunManager;
+use common::http_stub::{StubResponse, StubServer};
+use serde_json::json;
+use std::path::Path;
+use std::time::Duration;
+
+// Set when this test binary is re-executed as a stub adapter; names the version file.
+const STUB_VERSION_FILE: &str = "AIGC_STUB_ADAPTER_VERSION_FILE";
+// When set and the file does not exist yet, the stub creates it and crashes shortly after.
+const STUB_CRASH_MARKER: &str = "AIGC_STUB_ADAPTER_CRASH_MARKER";
+
+/// Not a test of its own: the stub adapter binary the other tests launch.
+#[test]
+fn stub_adapter_process() {
+    let Ok(version_file) = std::env::var(STUB_VERSION_FILE) else {
+        return;
+    };
+    let version = std::fs::read_to_string(version_file).unwrap();
+    if let Ok(marker) = std::env::var(STUB_CRASH_MARKER) {
+        if !Path::new(&marker).exists() {
+            std::fs::write(&marker, b"crashed").unwrap();
+            std::thread::spawn(|| {
+                std::thread::sleep(Duration::from_millis(300));
+                std::process::exit(3);
+            });
+        }
+    }
+    let addr = std::env::var(ADAPTER_LISTEN_ENV).unwrap();
+    let _server = StubServer::start_on(&addr, move |req| match req.path.as_str() {
+        "/v1/health" => StubResponse::json(
+            200,
+            json!({
+                "status": "ok",
+                "adapter_id": "llm_local",
+                "adapter_version": version.trim(),
+                "uptime_ms": 1
+            }),
+        ),
+        _ => StubResponse::json(
+            200,
+            json!({
+                "output_text": "ok",
+                "output_json": null,
+                "usage": {},
+                "timing_ms": 1,
+                "output_hash": null,
+                "status": "ok"
+            }),
+        ),
+    });
+    loop {
+        std::thread::sleep(Duration::from_secs(60));
+    }
+}
+
+fn free_endpoint() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+fn config(dir: &Path, adapter_id: &str) -> AdapterLaunchConfig {
+    let version_file = dir.join("version.txt");
+    if !version_file.exists() {
+        std::fs::write(&version_file, "2.1.0").unwrap();
+    }
+    let mut config = AdapterLaunchConfig::new(
+        adapter_id,
+        std::env::current_exe().unwrap(),
+        &free_endpoint(),
+    );
+    config.args = ["stub_adapter_process", "--exact", "--nocapture"]
+        .map(str::to_string)
+        .to_vec();
+    config.env = vec![(
+        STUB_VERSION_FILE.to_string(),
+        version_file.to_string_lossy().to_string(),
+    )];
+    config.startup_timeout = Duration::from_secs(10);
+    config.health_poll_interval = Duration::from_millis(20);
+    config
+}
+
+fn crashing_once(dir: &Path, max_restarts: u32) -> AdapterLaunchConfig {
+    let mut config = config(dir, "llm_local");
+    config.env.push((
+        STUB_CRASH_MARKER.to_string(),
+        dir.join("crashed").to_string_lossy().to_string(),
+    ));
+    config.max_restarts = max_restarts;
+    config
+}
+
+fn scope() -> SupervisorScope {
+    SupervisorScope {
+        run_id: "r_sup".to_string(),
+        vault_id: "v_0001".to_string(),
+    }
+}
+
+// (event_type, exit_code, restarts) of each recorded adapter process event.
+fn process_events(path: &Path) -> Vec<(String, serde_json::Value, u64)> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+        .filter(|e| {
+            e["event_type"]
+                .as_str()
+                .unwrap()
+                .starts_with("ADAPTER_PROCESS_")
+        })
+        .map(|e| {
+            (
+                e["event_type"].as_str().unwrap().to_string(),
+                e["details"]["exit_code"].clone(),
+                e["details"]["restarts"].as_u64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+fn wait_for_exit(supervisor: &AdapterSupervisor) {
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while supervisor.client().health().is_ok() {
+        assert!(std::time::Instant::now() < deadline, "stub did not crash");
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    std::thread::sleep(Duration::from_millis(50));
+}
+
+fn adapter_code(err: &CoreError) -> &str {
+    match err {
+        CoreError::Adapter(env) => &env.error.code,
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn launch_waits_for_health_captures_version_and_audits_stop() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+
+    let mut supervisor =
+        AdapterSupervisor::launch(config(dir.path(), "llm_local"), scope(), &mut audit).unwrap();
+    assert_eq!(supervisor.adapter_version(), "2.1.0");
+    assert!(supervisor.pid().is_some());
+    assert_eq!(
+        supervisor.client().health().unwrap().adapter_id,
+        "llm_local"
+    );
+    let mut snapshot = ModelSnapshot {
+        adapter_id: String::new(),
+        adapter_version: String::new(),
+        adapter_endpoint: String::new(),
+        model_id: "llama3-8b".to_string(),
+        model_sha256: None,
+        pinning_level: PinningLevel::VERSION_PINNED,
+    };
+    supervisor.fill_snapshot(&mut snapshot);
+    assert_eq!(snapshot.adapter_id, "llm_local");
+    assert_eq!(snapshot.adapter_version, "2.1.0");
+    assert_eq!(snapshot.adapter_endpoint, supervisor.client().endpoint());
+    supervisor.ensure_running(&mut audit).unwrap();
+
+    supervisor.shutdown(&mut audit).unwrap();
+    supervisor.shutdown(&mut audit).unwrap();
+    assert!(supervisor.client().health().is_err());
+    let err = supervisor.ensure_running(&mut audit).unwrap_err();
+    assert_eq!(adapter_code(&err), "ADAPTER_STOPPED");
+
+    let events = process_events(&audit_path);
+    let actions: Vec<&str> = events.iter().map(|e| e.0.as_str()).collect();
+    assert_eq!(
+        actions,
+        ["ADAPTER_PROCESS_LAUNCHED", "ADAPTER_PROCESS_STOPPED"]
+    );
+    let first: serde_json::Value = serde_json::from_str(
+        std::fs::read_to_string(&audit_path)
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(first["event_type"], "ADAPTER_PROCESS_LAUNCHED");
+    assert_eq!(first["details"]["adapter_id"], "llm_local");
+    assert_eq!(first["details"]["adapter_version"], "2.1.0");
+}
+
+#[test]
+fn startup_fails_for_wrong_adapter_or_early_exit() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut audit = AuditLog::open_or_create(&dir.path().join("audit.ndjson")).unwrap();
+    let before = audit.last_hash().to_string();
+
+    let err = AdapterSupervisor::launch(config(dir.path(), "stt_local"), scope(), &mut audit)
+        .err()
+        .unwrap();
+    assert_eq!(adapter_code(&err), "ADAPTER_ID_MISMATCH");
+
+    // With no matching test the binary exits before ever listening.
+    let mut quits = config(dir.path(), "llm_local");
+    quits.args = vec!["no_such_test".to_string(), "--exact".to_string()];
+    let err = AdapterSupervisor::launch(quits, scope(), &mut audit)
+        .err()
+        .unwrap();
+    assert_eq!(adapter_code(&err), "ADAPTER_EXITED");
+    // Nothing was launched, so nothing is audited.
+    assert_eq!(audit.last_hash(), before);
+}
+
+#[test]
+fn crashed_adapter_is_restarted_before_calls_and_stopped_with_the_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+    let supervisor =
+        AdapterSupervisor::launch(crashing_once(dir.path(), 1), scope(), &mut mgr.audit).unwrap();
+    let client = supervisor.client().clone();
+    wait_for_exit(&supervisor);
+    mgr.attach_adapter_supervisor(supervisor);
+
+    let call_scope = ModelCallScope {
+        run_id: "r_sup".to_string(),
+        vault_id: "v_0001".to_string(),
+        adapter_version: "2.1.0".to_string(),
+        input_artifact_refs: vec![],
+    };
+    let req = GenerateRequest {
+        call_id: "c_1".to_string(),
+        model_id: "llama3-8b".to_string(),
+        messages: vec![ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }],
+        response_mode: "TEXT".to_string(),
+        json_schema: None,
+        temperature: None,
+        top_p: None,
+        max_tokens: None,
+        seed: None,
+        context_chunks: vec![],
+        safety_profile: SafetyProfile::default(),
+    };
+    let resp = mgr.generate(&client, &call_scope, &req).unwrap();
+    assert_eq!(resp.output_text.as_deref(), Some("ok"));
+    assert_eq!(mgr.adapter_supervisors()[0].restarts(), 1);
+
+    mgr.fail("r_sup", "v_0001", "operator stopped run").unwrap();
+    assert!(client.health().is_err());
+    assert_eq!(
+        process_events(&audit_path),
+        [
+            ("ADAPTER_PROCESS_LAUNCHED".to_string(), json!(null), 0),
+            ("ADAPTER_PROCESS_EXITED".to_string(), json!(3), 0),
+            ("ADAPTER_PROCESS_LAUNCHED".to_string(), json!(null), 1),
+            ("ADAPTER_PROCESS_STOPPED".to_string(), json!(null), 1),
+        ]
+    );
+}
+
+#[test]
+fn dropping_the_run_manager_stops_its_adapters_audited() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+    let supervisor =
+        AdapterSupervisor::launch(config(dir.path(), "llm_local"), scope(), &mut mgr.audit)
+            .unwrap();
+    let client = supervisor.client().clone();
+    mgr.attach_adapter_supervisor(supervisor);
+
+    drop(mgr);
+    assert!(client.health().is_err());
+    let actions: Vec<String> = process_events(&audit_path)
+        .into_iter()
+        .map(|e| e.0)
+        .collect();
+    assert_eq!(
+        actions,
+        ["ADAPTER_PROCESS_LAUNCHED", "ADAPTER_PROCESS_STOPPED"]
+    );
+}
+
+#[test]
+fn restarts_stop_at_the_limit_and_on_version_change() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut audit = AuditLog::open_or_create(&dir.path().join("audit.ndjson")).unwrap();
+    let mut supervisor =
+        AdapterSupervisor::launch(crashing_once(dir.path(), 0), scope(), &mut audit).unwrap();
+    wait_for_exit(&supervisor);
+    let err = supervisor.ensure_running(&mut audit).unwrap_err();
+    assert_eq!(adapter_code(&err), "ADAPTER_RESTART_LIMIT");
+
+    // The binary on disk was upgraded while the run was going.
+    let dir = tempfile::tempdir().unwrap();
+    let mut supervisor =
+        AdapterSupervisor::launch(crashing_once(dir.path(), 3), scope(), &mut audit).unwrap();
+    std::fs::write(dir.path().join("version.txt"), "2.2.0").unwrap();
+    wait_for_exit(&supervisor);
+    let err = supervisor.ensure_running(&mut audit).unwrap_err();
+    assert_eq!(adapter_code(&err), "ADAPTER_VERSION_CHANGED");
+    assert_eq!(supervisor.adapter_version(), "2.1.0");
+    assert!(supervisor.pid().is_none());
+    assert!(supervisor.client().health().is_err());
+}
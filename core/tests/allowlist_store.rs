@@ -0,0 +1,204 @@
+use aigc_core::audit::log::AuditLog;
+use aigc_core::determinism::json_canonical;
+use aigc_core::determinism::run_id::sha256_hex;
+use aigc_core::policy::allowlist::{allowlist_hash_sha256, AllowlistEntry};
+use aigc_core::policy::allowlist_store::AllowlistStore;
+use aigc_core::storage::crypto::EncryptionAlgorithm;
+use aigc_core::storage::vault::{VaultConfig, VaultStorage};
+use serde_json::json;
+use std::path::Path;
+
+fn entry(host: &str, pack_version: &str) -> AllowlistEntry {
+    AllowlistEntry {
+        scheme: "https".to_string(),
+        host: host.to_string(),
+        port: 0,
+        path_prefix: Some("v1".to_string()),
+        purpose: "license check".to_string(),
+        policy_pack_id: "pp_vendor".to_string(),
+        policy_pack_version: pack_version.to_string(),
+        max_requests_per_run: None,
+        max_bytes_per_run: None,
+    }
+}
+
+fn vault(root: &Path) -> VaultStorage {
+    VaultStorage::create(
+        root,
+        VaultConfig {
+            vault_id: "v_allow".to_string(),
+            encryption_algorithm: EncryptionAlgorithm::XCHACHA20_POLY1305,
+            encryption_at_rest: true,
+        },
+    )
+    .unwrap()
+}
+
+fn updates(path: &Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+        .filter(|e| e["event_type"] == "ALLOWLIST_UPDATED")
+        .map(|e| e["details"].clone())
+        .collect()
+}
+
+#[test]
+fn hash_covers_canonical_sorted_entries() {
+    let a = entry("API.Vendor.example", "1.0.0");
+    let b = entry("updates.vendor.example", "1.0.0");
+    let hash = allowlist_hash_sha256(&[a.clone(), b.clone()]).unwrap();
+    assert_eq!(
+        hash,
+        allowlist_hash_sha256(&[b.clone(), a.clone()]).unwrap()
+    );
+
+    let canonical = vec![a.canonicalize().unwrap(), b.canonicalize().unwrap()];
+    assert_eq!(canonical[0].host, "api.vendor.example");
+    assert_eq!(canonical[0].port, 443);
+    assert_eq!(
+        hash,
+        sha256_hex(&json_canonical::to_canonical_bytes(&canonical).unwrap())
+    );
+    assert_eq!(
+        allowlist_hash_sha256(&[]).unwrap(),
+        sha256_hex(b"[]"),
+        "an empty allowlist hashes as an empty JSON array"
+    );
+}
+
+#[test]
+fn changes_are_persisted_in_the_vault_and_audited() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault_root = dir.path().join("vault");
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let mut store = AllowlistStore::open(vault(&vault_root)).unwrap();
+    assert!(store.entries().is_empty());
+
+    let updates_id = store
+        .add(entry("updates.vendor.example", "1.0.0"), &mut audit, "r_1")
+        .unwrap();
+    let api_id = store
+        .add(entry("api.vendor.example", "1.0.0"), &mut audit, "r_1")
+        .unwrap();
+    assert!(store
+        .add(entry("API.vendor.example", "1.0.0"), &mut audit, "r_1")
+        .is_err());
+    let hosts: Vec<&str> = store.entries().iter().map(|e| e.host.as_str()).collect();
+    assert_eq!(hosts, ["api.vendor.example", "updates.vendor.example"]);
+    assert_eq!(store.entries()[0].path_prefix.as_deref(), Some("/v1"));
+
+    let removed = store.remove(&updates_id, &mut audit, "r_1").unwrap();
+    assert_eq!(removed.host, "updates.vendor.example");
+    assert!(store.remove(&updates_id, &mut audit, "r_1").is_err());
+
+    let reopened = AllowlistStore::open(VaultStorage::open(&vault_root).unwrap()).unwrap();
+    assert_eq!(reopened.entries(), store.entries());
+    assert_eq!(
+        reopened.hash_sha256().unwrap(),
+        store.hash_sha256().unwrap()
+    );
+
+    let events = updates(&audit_path);
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1]["allowlist_count"], 2);
+    assert_eq!(events[2]["allowlist_count"], 1);
+    assert_eq!(
+        events[2]["allowlist_hash_sha256"],
+        store.hash_sha256().unwrap()
+    );
+    assert_eq!(
+        events[1]["meta"]["allowlist_change"],
+        json!({
+            "change": "ADDED",
+            "added_rule_ids": [api_id],
+            "removed_rule_ids": [],
+            "policy_packs": [{"policy_pack_id": "pp_vendor", "policy_pack_version": "1.0.0"}]
+        })
+    );
+    assert_eq!(
+        events[2]["meta"]["allowlist_change"]["removed_rule_ids"],
+        json!([updates_id])
+    );
+}
+
+#[test]
+fn replace_records_the_difference_and_rejects_unattributed_or_duplicate_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let mut store = AllowlistStore::open(vault(&dir.path().join("vault"))).unwrap();
+    store
+        .replace(
+            vec![
+                entry("api.vendor.example", "1.0.0"),
+                entry("updates.vendor.example", "1.0.0"),
+            ],
+            &mut audit,
+            "r_1",
+        )
+        .unwrap();
+
+    // The pack's 1.1.0 release keeps one host and moves the other.
+    let kept = entry("api.vendor.example", "1.0.0");
+    let moved = entry("cdn.vendor.example", "1.1.0");
+    store
+        .replace(vec![moved.clone(), kept.clone()], &mut audit, "r_1")
+        .unwrap();
+    let change = &updates(&audit_path)[1]["meta"]["allowlist_change"];
+    assert_eq!(change["change"], "REPLACED");
+    assert_eq!(change["added_rule_ids"], json!([moved.rule_id().unwrap()]));
+    assert_eq!(
+        change["removed_rule_ids"],
+        json!([entry("updates.vendor.example", "1.0.0").rule_id().unwrap()])
+    );
+    assert_eq!(
+        change["policy_packs"],
+        json!([
+            {"policy_pack_id": "pp_vendor", "policy_pack_version": "1.0.0"},
+            {"policy_pack_id": "pp_vendor", "policy_pack_version": "1.1.0"}
+        ])
+    );
+
+    let before = store.hash_sha256().unwrap();
+    let mut unattributed = entry("mirror.vendor.example", "1.1.0");
+    unattributed.policy_pack_id.clear();
+    assert!(store.add(unattributed, &mut audit, "r_1").is_err());
+    assert!(store
+        .replace(vec![kept.clone(), kept.clone()], &mut audit, "r_1")
+        .is_err());
+    // A quota is not part of the rule's identity: the same rule with a cap is a duplicate.
+    let mut capped = kept;
+    capped.max_requests_per_run = Some(5);
+    assert_eq!(
+        capped.rule_id().unwrap(),
+        store.entries()[0].rule_id().unwrap()
+    );
+    assert!(store.add(capped, &mut audit, "r_1").is_err());
+    assert_eq!(store.hash_sha256().unwrap(), before);
+    assert_eq!(updates(&audit_path).len(), 2);
+}
+
+#[test]
+fn changes_that_cannot_be_audited_are_not_persisted() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault_root = dir.path().join("vault");
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let mut store = AllowlistStore::open(vault(&vault_root)).unwrap();
+    store
+        .add(entry("api.vendor.example", "1.0.0"), &mut audit, "r_1")
+        .unwrap();
+
+    // The audit log can no longer be appended to.
+    std::fs::remove_file(&audit_path).unwrap();
+    std::fs::create_dir(&audit_path).unwrap();
+    assert!(store
+        .add(entry("updates.vendor.example", "1.0.0"), &mut audit, "r_1")
+        .is_err());
+    assert_eq!(store.entries().len(), 1);
+    let reopened = AllowlistStore::open(VaultStorage::open(&vault_root).unwrap()).unwrap();
+    assert_eq!(reopened.entries(), store.entries());
+}
@@ -0,0 +1,170 @@
+// Minimal HTTP/1.1 stand-in for loopback services (adapters, sinks) in integration tests.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct StubRequest {
+    pub method: String,
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StubResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+    pub delay: Duration,
+    pub chunks: Vec<Vec<u8>>, // sent with chunked transfer encoding instead of `body`
+    pub chunk_gap: Duration,
+}
+
+impl StubResponse {
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        Self {
+            status,
+            body: serde_json::to_vec(&body).unwrap(),
+            delay: Duration::ZERO,
+            chunks: Vec::new(),
+            chunk_gap: Duration::ZERO,
+        }
+    }
+
+    /// NDJSON body sent as one HTTP chunk per line, `gap` apart.
+    pub fn ndjson_stream(lines: Vec<serde_json::Value>, gap: Duration) -> Self {
+        Self {
+            status: 200,
+            body: Vec::new(),
+            delay: Duration::ZERO,
+            chunks: lines
+                .iter()
+                .map(|l| {
+                    let mut line = serde_json::to_vec(l).unwrap();
+                    line.push(b'\n');
+                    line
+                })
+                .collect(),
+            chunk_gap: gap,
+        }
+    }
+
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+pub struct StubServer {
+    pub endpoint: String,
+    requests: Arc<Mutex<Vec<StubRequest>>>,
+}
+
+impl StubServer {
+    /// Serves every connection on its own thread until the test process exits.
+    pub fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&StubRequest) -> StubResponse + Send + Sync + 'static,
+    {
+        Self::start_on("127.0.0.1:0", handler)
+    }
+
+    /// Like `start`, on a fixed address.
+    pub fn start_on<F>(addr: &str, handler: F) -> Self
+    where
+        F: Fn(&StubRequest) -> StubResponse + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind(addr).unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler = Arc::new(handler);
+        let seen = requests.clone();
+        std::thread::spawn(move || {
+            for conn in listener.incoming() {
+                let Ok(conn) = conn else { continue };
+                let handler = handler.clone();
+                let seen = seen.clone();
+                std::thread::spawn(move || serve(conn, &*handler, &seen));
+            }
+        });
+        Self { endpoint, requests }
+    }
+
+    pub fn requests(&self) -> Vec<StubRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn serve(
+    mut conn: TcpStream,
+    handler: &dyn Fn(&StubRequest) -> StubResponse,
+    seen: &Mutex<Vec<StubRequest>>,
+) {
+    let Some(req) = read_request(&mut conn) else {
+        return;
+    };
+    seen.lock().unwrap().push(req.clone());
+    let resp = handler(&req);
+    std::thread::sleep(resp.delay);
+    if !resp.chunks.is_empty() {
+        let head = format!(
+            "HTTP/1.1 {} STUB\r\nContent-Type: application/x-ndjson\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+            resp.status
+        );
+        let _ = conn.write_all(head.as_bytes());
+        for chunk in &resp.chunks {
+            let framed = [
+                format!("{:x}\r\n", chunk.len()).into_bytes(),
+                chunk.clone(),
+                b"\r\n".to_vec(),
+            ]
+            .concat();
+            // The client may hang up mid-stream (cancel, timeout); stop quietly.
+            if conn.write_all(&framed).and_then(|_| conn.flush()).is_err() {
+                return;
+            }
+            std::thread::sleep(resp.chunk_gap);
+        }
+        let _ = conn.write_all(b"0\r\n\r\n");
+        return;
+    }
+    let head = format!(
+        "HTTP/1.1 {} STUB\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        resp.status,
+        resp.body.len()
+    );
+    let _ = conn.write_all(head.as_bytes());
+    let _ = conn.write_all(&resp.body);
+}
+
+fn read_request(conn: &mut TcpStream) -> Option<StubRequest> {
+    let mut raw = Vec::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = conn.read(&mut buf).ok()?;
+        if n == 0 {
+            return None;
+        }
+        raw.extend_from_slice(&buf[..n]);
+        let Some(end) = raw.windows(4).position(|w| w == b"\r\n\r\n") else {
+            continue;
+        };
+        let head = String::from_utf8_lossy(&raw[..end]).to_string();
+        let len = head
+            .lines()
+            .filter_map(|l| l.split_once(':'))
+            .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, v)| v.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        if raw.len() < end + 4 + len {
+            continue;
+        }
+        let mut parts = head.split_whitespace();
+        return Some(StubRequest {
+            method: parts.next()?.to_string(),
+            path: parts.next()?.to_string(),
+            body: raw[end + 4..end + 4 + len].to_vec(),
+        });
+    }
+}
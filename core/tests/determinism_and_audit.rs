@@ -1,4 +1,6 @@
 use aigc_core::audit::event::{finalize_event, Actor, AuditEvent};
+use aigc_core::audit::log::AuditLog;
+use aigc_core::determinism::clock::{clock_for_profile, Clock, FixedClock, SteppedClock};
 use aigc_core::determinism::json_canonical::to_canonical_bytes;
 use aigc_core::determinism::run_id::run_id_from_manifest_inputs_fingerprint_hex32;
 
@@ -35,3 +37,45 @@ fn deterministic_run_id_rule() {
     let run = run_id_from_manifest_inputs_fingerprint_hex32(fp).unwrap();
     assert_eq!(run, "r_1234567890abcdef1234567890abcdef");
 }
+
+#[test]
+fn stepped_clock_advances_from_fixed_origin() {
+    let clock = SteppedClock::new("2026-02-10T00:00:00Z", time::Duration::SECOND).unwrap();
+    assert_eq!(clock.now_rfc3339_utc(), "2026-02-10T00:00:00Z");
+    assert_eq!(clock.now_rfc3339_utc(), "2026-02-10T00:00:01Z");
+    let fixed = FixedClock::new("2026-02-10T00:00:00+00:00").unwrap();
+    assert_eq!(fixed.now_rfc3339_utc(), "2026-02-10T00:00:00Z");
+    assert!(FixedClock::new("not a timestamp").is_err());
+}
+
+#[test]
+fn deterministic_clock_yields_byte_identical_audit_logs() {
+    let dir = tempfile::tempdir().unwrap();
+    let write_log = |name: &str| {
+        let path = dir.path().join(name);
+        let mut audit = AuditLog::open_or_create(&path)
+            .unwrap()
+            .with_clock(clock_for_profile(true));
+        for state in ["INGESTING", "READY"] {
+            audit
+                .append(AuditEvent {
+                    ts_utc: String::new(),
+                    event_type: "RUN_STATE_CHANGED".to_string(),
+                    run_id: "r_1".to_string(),
+                    vault_id: "v_1".to_string(),
+                    actor: Actor::System,
+                    details: serde_json::json!({"from_state":"CREATED","to_state":state,"reason":"test"}),
+                    prev_event_hash: String::new(),
+                    event_hash: String::new(),
+                })
+                .unwrap();
+        }
+        std::fs::read(&path).unwrap()
+    };
+    let a = write_log("a.ndjson");
+    let b = write_log("b.ndjson");
+    assert_eq!(a, b);
+    assert!(String::from_utf8(a)
+        .unwrap()
+        .contains("\"ts_utc\":\"2026-02-10T00:00:01Z\""));
+}
@@ -0,0 +1,453 @@
+mod common;
+
+use aigc_core::audit::log::AuditLog;
+use aigc_core::determinism::run_id::sha256_hex;
+use aigc_core::error::CoreError;
+use aigc_core::policy::allowlist::AllowlistEntry;
+use aigc_core::policy::egress::{EgressClient, EgressDecision, EgressPolicy};
+use aigc_core::policy::network_snapshot::NetworkSnapshot;
+use aigc_core::policy::types::{NetworkMode, ProofLevel};
+use common::http_stub::{StubResponse, StubServer};
+use serde_json::json;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn events(path: &Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+fn policy(network_mode: NetworkMode, port: u16) -> EgressPolicy {
+    EgressPolicy {
+        network_mode,
+        proof_level: ProofLevel::OFFLINE_STRICT,
+        allowlist: vec![AllowlistEntry {
+            scheme: "http".to_string(),
+            host: "127.0.0.1".to_string(),
+            port,
+            path_prefix: Some("/api".to_string()),
+            purpose: "license check".to_string(),
+            policy_pack_id: "default".to_string(),
+            policy_pack_version: "1.0.0".to_string(),
+            max_requests_per_run: None,
+            max_bytes_per_run: None,
+        }
+        .canonicalize()
+        .unwrap()],
+    }
+}
+
+fn send(
+    audit: &mut AuditLog,
+    policy: EgressPolicy,
+    url: &str,
+    body: &[u8],
+) -> Result<u16, CoreError> {
+    EgressClient::new(policy, audit, "r_egress", "v_0001")
+        .send(
+            "POST",
+            url,
+            &[("Content-Type", "application/json")],
+            body,
+            Duration::from_secs(5),
+        )
+        .map(|resp| resp.status)
+}
+
+// https server on 127.0.0.1 with a fresh self-signed certificate. Serves `connections`
+// connections and returns the port, the certificate (DER) and the requests it answered.
+fn tls_server(connections: usize) -> (u16, Vec<u8>, std::thread::JoinHandle<Vec<Vec<u8>>>) {
+    let certified = rcgen::generate_simple_self_signed(vec!["127.0.0.1".to_string()]).unwrap();
+    let key = rustls::pki_types::PrivateKeyDer::Pkcs8(certified.key_pair.serialize_der().into());
+    let config = Arc::new(
+        rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(vec![certified.cert.der().clone()], key)
+        .unwrap(),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for _ in 0..connections {
+            let (tcp, _) = listener.accept().unwrap();
+            let conn = rustls::ServerConnection::new(config.clone()).unwrap();
+            let mut tls = rustls::StreamOwned::new(conn, tcp);
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 4096];
+            let complete = |raw: &[u8]| {
+                let Some(end) = raw.windows(4).position(|w| w == b"\r\n\r\n") else {
+                    return false;
+                };
+                let head = String::from_utf8_lossy(&raw[..end]).to_ascii_lowercase();
+                let len: usize = head
+                    .lines()
+                    .find_map(|l| l.strip_prefix("content-length:"))
+                    .map_or(0, |v| v.trim().parse().unwrap());
+                raw.len() >= end + 4 + len
+            };
+            while !complete(&raw) {
+                match tls.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => raw.extend_from_slice(&buf[..n]),
+                }
+            }
+            if !complete(&raw) {
+                continue; // handshake refused by the client
+            }
+            tls.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                .unwrap();
+            tls.conn.send_close_notify();
+            let _ = tls.flush();
+            requests.push(raw);
+        }
+        requests
+    });
+    (port, certified.cert.der().to_vec(), handle)
+}
+
+#[test]
+fn allowlisted_request_is_recorded_then_sent() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let server = StubServer::start(|_| StubResponse::json(202, json!({"ok": true})));
+    let port: u16 = server.endpoint.rsplit(':').next().unwrap().parse().unwrap();
+
+    let url = format!("{}/api/check", server.endpoint);
+    let policy = policy(NetworkMode::ONLINE_ALLOWLISTED, port);
+    assert_eq!(send(&mut audit, policy.clone(), &url, b"{}").unwrap(), 202);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/api/check");
+    assert_eq!(requests[0].body, b"{}");
+    let evs = events(&audit_path);
+    assert_eq!(evs.len(), 1);
+    assert_eq!(evs[0]["event_type"], "EGRESS_REQUEST_ALLOWED");
+    assert_eq!(evs[0]["details"]["destination"]["path"], "/api/check");
+    assert_eq!(
+        evs[0]["details"]["allowlist_rule_id"],
+        policy.allowlist[0].rule_id().unwrap()
+    );
+    assert_eq!(evs[0]["details"]["request_hash_sha256"], sha256_hex(b"{}"));
+}
+
+#[test]
+fn blocked_requests_are_recorded_and_never_sent() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let server = StubServer::start(|_| StubResponse::json(200, json!({})));
+    let port: u16 = server.endpoint.rsplit(':').next().unwrap().parse().unwrap();
+
+    let listed = format!("{}/api/check", server.endpoint);
+    let unlisted = format!("{}/other", server.endpoint);
+    let offline = send(
+        &mut audit,
+        policy(NetworkMode::OFFLINE, port),
+        &listed,
+        b"a",
+    );
+    assert!(matches!(offline, Err(CoreError::PolicyBlocked(_))));
+    let not_listed = send(
+        &mut audit,
+        policy(NetworkMode::ONLINE_ALLOWLISTED, port),
+        &unlisted,
+        b"b",
+    );
+    assert!(matches!(not_listed, Err(CoreError::PolicyBlocked(_))));
+
+    assert!(server.requests().is_empty());
+    let reasons: Vec<(String, String)> = events(&audit_path)
+        .iter()
+        .map(|e| {
+            (
+                e["event_type"].as_str().unwrap().to_string(),
+                e["details"]["block_reason"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        reasons,
+        [
+            (
+                "EGRESS_REQUEST_BLOCKED".to_string(),
+                "OFFLINE_MODE".to_string()
+            ),
+            (
+                "EGRESS_REQUEST_BLOCKED".to_string(),
+                "NOT_ALLOWLISTED".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn unparseable_url_is_rejected_before_any_record() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let before = audit.last_hash().to_string();
+
+    let err = send(
+        &mut audit,
+        policy(NetworkMode::ONLINE_ALLOWLISTED, 80),
+        "not a url",
+        b"",
+    )
+    .unwrap_err();
+    assert!(matches!(err, CoreError::InvalidInput(_)), "{:?}", err);
+    assert_eq!(audit.last_hash(), before);
+}
+
+#[test]
+fn rule_ids_survive_allowlist_edits_and_resolve_through_the_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut audit = AuditLog::open_or_create(&dir.path().join("audit.ndjson")).unwrap();
+    let mut policy = policy(NetworkMode::ONLINE_ALLOWLISTED, 8443);
+    let rule = policy.allowlist[0].clone();
+    let url = url::Url::parse("http://127.0.0.1:8443/api/check").unwrap();
+    let decide = |policy: &EgressPolicy, audit: &mut AuditLog| {
+        let mut client = EgressClient::new(policy.clone(), audit, "r_egress", "v_0001");
+        match client.decide(&url, b"").unwrap() {
+            EgressDecision::Allowed { allowlist_rule_id } => allowlist_rule_id,
+            other => panic!("unexpected {:?}", other),
+        }
+    };
+    let before = decide(&policy, &mut audit);
+    assert!(before.starts_with("ALW-"), "{}", before);
+
+    // A rule inserted ahead of it does not renumber it.
+    let mut earlier = rule.clone();
+    earlier.host = "mirror.example.com".to_string();
+    policy.allowlist.insert(0, earlier.canonicalize().unwrap());
+    assert_eq!(decide(&policy, &mut audit), before);
+
+    // Spelling differences that canonicalize away keep the id; content changes do not.
+    let mut respelled = rule.clone();
+    respelled.scheme = "HTTP".to_string();
+    assert_eq!(respelled.rule_id().unwrap(), before);
+    let mut repurposed = rule.clone();
+    repurposed.purpose = "telemetry".to_string();
+    assert_ne!(repurposed.rule_id().unwrap(), before);
+
+    let snapshot = NetworkSnapshot {
+        network_mode: policy.network_mode,
+        proof_level: policy.proof_level,
+        allowlist: policy.allowlist.clone(),
+        ui_remote_fetch_disabled: true,
+        adapter_endpoints: vec![],
+    };
+    assert_eq!(snapshot.allowlist_rule(&before), Some(&rule));
+    assert!(snapshot.allowlist_rule("ALW0001").is_none());
+}
+
+#[test]
+fn quotas_block_once_a_rule_has_been_used_up_for_the_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let server = StubServer::start(|_| StubResponse::json(200, json!({})));
+    let port: u16 = server.endpoint.rsplit(':').next().unwrap().parse().unwrap();
+    let url = format!("{}/api/check", server.endpoint);
+
+    let mut counted = policy(NetworkMode::ONLINE_ALLOWLISTED, port);
+    counted.allowlist[0].max_requests_per_run = Some(2);
+    // Each call builds a new client; usage comes from the run's audit trail.
+    assert_eq!(send(&mut audit, counted.clone(), &url, b"1").unwrap(), 200);
+    assert_eq!(send(&mut audit, counted.clone(), &url, b"2").unwrap(), 200);
+    let third = send(&mut audit, counted.clone(), &url, b"3");
+    assert!(
+        matches!(third, Err(CoreError::PolicyBlocked(_))),
+        "{:?}",
+        third
+    );
+    assert_eq!(server.requests().len(), 2);
+    let evs = events(&audit_path);
+    assert_eq!(evs[2]["event_type"], "EGRESS_REQUEST_BLOCKED");
+    assert_eq!(evs[2]["details"]["block_reason"], "QUOTA_EXCEEDED");
+    assert_eq!(evs[1]["details"]["meta"]["request_bytes"], 1);
+
+    // Another run has its own quota.
+    let other_run = EgressClient::new(counted, &mut audit, "r_other", "v_0001").send(
+        "POST",
+        &url,
+        &[],
+        b"4",
+        Duration::from_secs(5),
+    );
+    assert_eq!(other_run.unwrap().status, 200);
+
+    // A byte quota counts body sizes, including the request being decided. It is the same rule,
+    // so the two bytes sent above count too.
+    let mut sized = policy(NetworkMode::ONLINE_ALLOWLISTED, port);
+    sized.allowlist[0].max_bytes_per_run = Some(10);
+    let sized_url = format!("{}/api/upload", server.endpoint);
+    assert_eq!(
+        send(&mut audit, sized.clone(), &sized_url, b"123456").unwrap(),
+        200
+    );
+    let over = send(&mut audit, sized.clone(), &sized_url, b"12345");
+    assert!(
+        matches!(over, Err(CoreError::PolicyBlocked(_))),
+        "{:?}",
+        over
+    );
+    assert_eq!(send(&mut audit, sized, &sized_url, b"12").unwrap(), 200);
+    assert_eq!(server.requests().len(), 5);
+
+    let mut zero = policy(NetworkMode::ONLINE_ALLOWLISTED, port).allowlist[0].clone();
+    zero.max_bytes_per_run = Some(0);
+    assert!(zero.canonicalize().is_err());
+
+    // A long-lived client counts its own requests without re-reading the log.
+    let mut solo = policy(NetworkMode::ONLINE_ALLOWLISTED, port);
+    solo.allowlist[0] = AllowlistEntry {
+        path_prefix: Some("/solo".to_string()),
+        max_requests_per_run: Some(1),
+        ..solo.allowlist[0].clone()
+    }
+    .canonicalize()
+    .unwrap();
+    let solo_url = format!("{}/solo", server.endpoint);
+    let mut client = EgressClient::new(solo, &mut audit, "r_egress", "v_0001");
+    let timeout = Duration::from_secs(5);
+    assert!(client.send("POST", &solo_url, &[], b"", timeout).is_ok());
+    let again = client.send("POST", &solo_url, &[], b"", timeout);
+    assert!(
+        matches!(again, Err(CoreError::PolicyBlocked(_))),
+        "{:?}",
+        again
+    );
+    assert_eq!(server.requests().len(), 6);
+}
+
+#[test]
+fn allowlisted_https_requests_are_sent_over_verified_tls() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let (port, cert_der, server) = tls_server(2);
+    let mut policy = policy(NetworkMode::ONLINE_ALLOWLISTED, port);
+    policy.allowlist[0] = AllowlistEntry {
+        scheme: "https".to_string(),
+        ..policy.allowlist[0].clone()
+    }
+    .canonicalize()
+    .unwrap();
+    let url = format!("https://127.0.0.1:{}/api/check", port);
+
+    // No trusted root vouches for the server, so the handshake fails after the attempt is recorded.
+    let err = send(&mut audit, policy.clone(), &url, b"{}").unwrap_err();
+    assert!(!matches!(err, CoreError::PolicyBlocked(_)), "{:?}", err);
+
+    let resp = EgressClient::new(policy, &mut audit, "r_egress", "v_0001")
+        .with_root_certificate(cert_der)
+        .send(
+            "POST",
+            &url,
+            &[("Content-Type", "application/json")],
+            b"{}",
+            Duration::from_secs(5),
+        )
+        .unwrap();
+    assert_eq!(resp.status, 200);
+    assert_eq!(resp.body, b"ok");
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with(b"POST /api/check HTTP/1.1\r\n"));
+    assert!(requests[0].ends_with(b"\r\n\r\n{}"));
+    let evs = events(&audit_path);
+    assert_eq!(evs.len(), 2);
+    for ev in &evs {
+        assert_eq!(ev["event_type"], "EGRESS_REQUEST_ALLOWED");
+        assert_eq!(ev["details"]["destination"]["scheme"], "https");
+    }
+}
+
+#[test]
+fn unsendable_requests_are_rejected_before_they_are_decided() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let server = StubServer::start(|_| StubResponse::json(200, json!({})));
+    let port: u16 = server.endpoint.rsplit(':').next().unwrap().parse().unwrap();
+    let url = format!("{}/api/check", server.endpoint);
+    let mut policy = policy(NetworkMode::ONLINE_ALLOWLISTED, port);
+    policy.allowlist[0].max_requests_per_run = Some(1);
+
+    // Only http and https can be sent.
+    let ftp_url = format!("ftp://127.0.0.1:{}/api/check", port);
+    let err = send(&mut audit, policy.clone(), &ftp_url, b"").unwrap_err();
+    assert!(matches!(err, CoreError::InvalidInput(_)), "{:?}", err);
+
+    let mut client = EgressClient::new(policy.clone(), &mut audit, "r_egress", "v_0001");
+    let timeout = Duration::from_secs(5);
+    let injected = "1\r\n\r\nGET /admin HTTP/1.1\r\nHost: internal";
+    for (method, headers) in [
+        ("POST", vec![("X-Trace", injected)]),
+        ("POST", vec![("X-Trace\r\nX-Admin", "1")]),
+        ("POST /admin HTTP/1.1\r\nX:", vec![]),
+        ("", vec![]),
+    ] {
+        let err = client
+            .send(method, &url, &headers, b"", timeout)
+            .unwrap_err();
+        assert!(matches!(err, CoreError::InvalidInput(_)), "{:?}", err);
+    }
+
+    // Nothing was recorded or sent, so the rule's single request is still available.
+    assert!(events(&audit_path).is_empty());
+    assert!(server.requests().is_empty());
+    assert_eq!(send(&mut audit, policy, &url, b"").unwrap(), 200);
+}
+
+#[test]
+fn changing_a_quota_keeps_the_rule_id_and_its_usage() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let server = StubServer::start(|_| StubResponse::json(200, json!({})));
+    let port: u16 = server.endpoint.rsplit(':').next().unwrap().parse().unwrap();
+    let url = format!("{}/api/check", server.endpoint);
+
+    let mut loose = policy(NetworkMode::ONLINE_ALLOWLISTED, port);
+    loose.allowlist[0].max_requests_per_run = Some(3);
+    assert_eq!(send(&mut audit, loose.clone(), &url, b"1").unwrap(), 200);
+    assert_eq!(send(&mut audit, loose.clone(), &url, b"2").unwrap(), 200);
+
+    // Tightened mid-run: the two requests already sent still count.
+    let mut tight = loose.clone();
+    tight.allowlist[0].max_requests_per_run = Some(2);
+    let rule_id = loose.allowlist[0].rule_id().unwrap();
+    assert_eq!(tight.allowlist[0].rule_id().unwrap(), rule_id);
+    let blocked = send(&mut audit, tight, &url, b"3");
+    assert!(
+        matches!(blocked, Err(CoreError::PolicyBlocked(_))),
+        "{:?}",
+        blocked
+    );
+    assert_eq!(send(&mut audit, loose.clone(), &url, b"4").unwrap(), 200);
+    let exhausted = send(&mut audit, loose, &url, b"5");
+    assert!(matches!(exhausted, Err(CoreError::PolicyBlocked(_))));
+
+    let allowed: Vec<serde_json::Value> = events(&audit_path)
+        .into_iter()
+        .filter(|e| e["event_type"] == "EGRESS_REQUEST_ALLOWED")
+        .map(|e| e["details"]["allowlist_rule_id"].clone())
+        .collect();
+    assert_eq!(allowed, vec![json!(rule_id); 3]);
+    assert_eq!(server.requests().len(), 3);
+}
@@ -3,7 +3,7 @@ use aigc_core::audit::event::{Actor, AuditEvent};
 use aigc_core::audit::log::AuditLog;
 use aigc_core::determinism::json_canonical;
 use aigc_core::determinism::run_id::sha256_hex;
-use aigc_core::eval::runner::EvalRunner;
+use aigc_core::eval::runner::{EvalRunner, GateStatus};
 use aigc_core::evidence_bundle::artifact_hashes::{render_artifact_hashes_csv, ArtifactHashRow};
 use aigc_core::evidence_bundle::builder::EvidenceBundleBuilder;
 use aigc_core::evidence_bundle::schemas::*;
@@ -11,7 +11,7 @@ use aigc_core::evidenceos::model::{CitationInput, EvidenceItem, NarrativeClaimIn
 use aigc_core::evidenceos::workflow::{generate_evidenceos_artifacts, EvidenceOsRequest};
 use aigc_core::policy::network_snapshot::{AdapterEndpointSnapshot, NetworkSnapshot};
 use aigc_core::policy::types::{InputExportProfile, NetworkMode, PolicyMode, ProofLevel};
-use aigc_core::validator::BundleValidator;
+use aigc_core::validator::{BundleValidator, CheckStatus};
 use serde_json::json;
 use std::path::Path;
 
@@ -35,12 +35,12 @@ fn evidenceos_bundle_validates_and_is_deterministic() {
 
     let validator = BundleValidator::new_v3();
     let summary = validator.validate_zip(&zip_1, PolicyMode::STRICT).unwrap();
-    assert_eq!(summary.overall, "PASS");
+    assert_eq!(summary.overall, CheckStatus::PASS);
 
     let eval = EvalRunner::new_v3().unwrap();
     let gates = eval.run_all_for_bundle(&zip_1, PolicyMode::STRICT).unwrap();
-    assert!(gates.iter().any(|g| g.gate_id == "EVIDENCEOS.OUTPUTS_PRESENT_V1" && g.result == "PASS"));
-    assert!(gates.iter().any(|g| g.gate_id == "EVIDENCEOS.MAPPING_REVIEW_PRESENT_V1" && g.result == "PASS"));
+    assert!(gates.iter().any(|g| g.gate_id == "EVIDENCEOS.OUTPUTS_PRESENT_V1" && g.result == GateStatus::PASS));
+    assert!(gates.iter().any(|g| g.gate_id == "EVIDENCEOS.MAPPING_REVIEW_PRESENT_V1" && g.result == GateStatus::PASS));
 }
 
 fn make_inputs(bundle_root: &Path) -> Result<EvidenceBundleInputs, Box<dyn std::error::Error>> {
@@ -245,6 +245,7 @@ fn make_inputs(bundle_root: &Path) -> Result<EvidenceBundleInputs, Box<dyn std::
             },
             encryption_at_rest: true,
             encryption_algorithm: "XCHACHA20_POLY1305".to_string(),
+            model_catalog: None,
         },
         network_snapshot: NetworkSnapshot {
             network_mode: NetworkMode::OFFLINE,
@@ -0,0 +1,143 @@
+mod common;
+
+use aigc_core::audit::log::AuditLog;
+use aigc_core::error::CoreError;
+use aigc_core::eval::runner::EvalRunner;
+use aigc_core::evidence_bundle::builder::EvidenceBundleBuilder;
+use aigc_core::policy::types::{NetworkMode, PolicyMode};
+use aigc_core::run::cancel::CancellationToken;
+use aigc_core::run::checkpoint::load_checkpoint;
+use aigc_core::run::manager::{ExportStatus, RunManager, RunState};
+use aigc_core::run::registry::{RunRecord, RunRegistry};
+use aigc_core::storage::crypto::EncryptionAlgorithm;
+use aigc_core::storage::vault::{VaultConfig, VaultStorage};
+use std::path::Path;
+
+fn last_event(path: &Path) -> serde_json::Value {
+    let text = std::fs::read_to_string(path).unwrap();
+    serde_json::from_str(text.lines().last().unwrap()).unwrap()
+}
+
+#[test]
+fn cancelled_export_stops_before_preflight_and_cleans_scratch() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let out = dir.path().join("out");
+    std::fs::create_dir_all(&out).unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let token = CancellationToken::new();
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap())
+        .with_cancellation_token(token.clone());
+
+    token.cancel();
+    let outcome = mgr
+        .export_run(
+            &common::strict_export_request(&run_id, "v_0001"),
+            &inputs,
+            &out.join("bundle"),
+            &out.join("bundle.zip"),
+        )
+        .unwrap();
+    assert_eq!(outcome.status, ExportStatus::CANCELLED);
+    assert_eq!(mgr.state, RunState::CANCELLED);
+    let ev = last_event(&audit_path);
+    assert_eq!(ev["event_type"], "RUN_CANCELLED");
+    assert_eq!(ev["details"]["step"], "PREFLIGHT_BUILD");
+    assert_eq!(ev["details"]["from_state"], "EVALUATING");
+    assert_eq!(std::fs::read_dir(&out).unwrap().count(), 0);
+}
+
+#[test]
+fn cancelled_final_build_removes_partial_bundle_and_checkpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let vault_root = dir.path().join("vault");
+    let audit_path = dir.path().join("audit.ndjson");
+    let bundle_dir = dir.path().join("bundle");
+    let bundle_zip = dir.path().join("bundle.zip");
+    let req = common::strict_export_request(&run_id, "v_0001");
+    let vault = VaultStorage::create(
+        &vault_root,
+        VaultConfig {
+            vault_id: "v_0001".to_string(),
+            encryption_algorithm: EncryptionAlgorithm::XCHACHA20_POLY1305,
+            encryption_at_rest: true,
+        },
+    )
+    .unwrap();
+
+    // Leave the run in EXPORTING with a half-written target, as after a crash in the final build.
+    std::fs::write(&bundle_dir, b"partial").unwrap();
+    {
+        let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+        mgr.attach_registry(
+            RunRegistry::open(vault).unwrap(),
+            RunRecord {
+                run_id: run_id.clone(),
+                vault_id: "v_0001".to_string(),
+                pack_id: "evidenceos".to_string(),
+                pack_version: "1.0.0".to_string(),
+                state: RunState::READY,
+                policy_mode: PolicyMode::STRICT,
+                network_mode: NetworkMode::OFFLINE,
+                determinism_enabled: false,
+                audit_log_path: audit_path.to_string_lossy().to_string(),
+                artifact_count: 0,
+                bundle_dir: None,
+                bundle_zip: None,
+            },
+        )
+        .unwrap();
+        assert!(mgr
+            .export_run(&req, &inputs, &bundle_dir, &bundle_zip)
+            .is_err());
+    }
+
+    let registry = RunRegistry::open(VaultStorage::open(&vault_root).unwrap()).unwrap();
+    let token = CancellationToken::new();
+    token.cancel();
+    let mut mgr = RunManager::resume(registry, &run_id)
+        .unwrap()
+        .with_cancellation_token(token);
+    let outcome = mgr
+        .export_run(&req, &inputs, &bundle_dir, &bundle_zip)
+        .unwrap();
+    assert_eq!(outcome.status, ExportStatus::CANCELLED);
+    assert_eq!(last_event(&audit_path)["details"]["step"], "FINAL_BUILD");
+    assert!(!bundle_dir.exists());
+    assert!(!bundle_zip.exists());
+    let registry = mgr.registry().unwrap();
+    assert_eq!(registry.get(&run_id).unwrap().state, RunState::CANCELLED);
+    assert!(load_checkpoint(registry.vault(), &run_id)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn eval_runner_stops_at_the_gate_being_cancelled() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let root = dir.path().join("bundle");
+    let zip = dir.path().join("bundle.zip");
+    EvidenceBundleBuilder::build_dir(&root, &inputs).unwrap();
+    EvidenceBundleBuilder::build_zip(&root, &zip).unwrap();
+
+    let token = CancellationToken::new();
+    let seen = std::cell::RefCell::new(Vec::new());
+    let err = EvalRunner::new_v3()
+        .unwrap()
+        .run_all_for_bundle_checked(&zip, PolicyMode::STRICT, &|gate| {
+            seen.borrow_mut().push(gate.to_string());
+            if gate == "EVIDENCEOS.OUTPUTS_PRESENT_V1" {
+                token.cancel();
+            }
+            token.check(gate)
+        })
+        .unwrap_err();
+    assert!(
+        matches!(err, CoreError::Cancelled(ref step) if step == "EVIDENCEOS.OUTPUTS_PRESENT_V1")
+    );
+    assert_eq!(seen.borrow().len(), 3);
+}
@@ -1,6 +1,15 @@
+mod common;
+
 use aigc_core::adapters::pinning::PinningLevel;
-use aigc_core::policy::export_gate::{evaluate_export_gate, ExportBlockReason, ExportGateInputs};
+use aigc_core::audit::log::AuditLog;
+use aigc_core::eval::runner::{GateRunResult, GateStatus};
+use aigc_core::policy::export_gate::{
+    evaluate_export_gate, evaluate_export_gate_all, ExportBlockReason, ExportGateInputs,
+};
+use aigc_core::policy::model_catalog::{CatalogViolation, CatalogViolationReason, ModelUse};
 use aigc_core::policy::types::{NetworkMode, PolicyMode, ProofLevel};
+use aigc_core::run::manager::{ExportOutcome, ExportStatus, RunManager, RunState};
+use aigc_core::validator::checklist::Severity;
 
 #[test]
 fn strict_blocks_name_only_pinning() {
@@ -13,6 +22,8 @@ fn strict_blocks_name_only_pinning() {
         determinism_passed: true,
         network_mode: NetworkMode::OFFLINE,
         proof_level: ProofLevel::OFFLINE_STRICT,
+        model_catalog_violations: vec![],
+        model_catalog_error: None,
     });
     assert_eq!(r.err(), Some(ExportBlockReason::INSUFFICIENT_PINNING));
 }
@@ -28,6 +39,8 @@ fn strict_requires_offline_strict_proof() {
         determinism_passed: true,
         network_mode: NetworkMode::ONLINE_ALLOWLISTED,
         proof_level: ProofLevel::ONLINE_ALLOWLIST_CORE_ONLY,
+        model_catalog_violations: vec![],
+        model_catalog_error: None,
     });
     assert_eq!(r.err(), Some(ExportBlockReason::OFFLINE_PROOF_INSUFFICIENT));
 }
@@ -43,6 +56,184 @@ fn balanced_passes_when_requirements_met() {
         determinism_passed: true,
         network_mode: NetworkMode::ONLINE_ALLOWLISTED,
         proof_level: ProofLevel::ONLINE_ALLOWLIST_CORE_ONLY,
+        model_catalog_violations: vec![],
+        model_catalog_error: None,
     });
     assert!(r.is_ok());
 }
+
+#[test]
+fn gate_collects_every_failing_condition_in_precedence_order() {
+    let inputs = ExportGateInputs {
+        policy_mode: PolicyMode::STRICT,
+        pinning_level: PinningLevel::NAME_ONLY,
+        citations_required_passed: false,
+        redactions_required_passed: true,
+        blocker_gate_failures: vec!["CITATIONS.STRICT_ENFORCED_V1".to_string()],
+        determinism_passed: true,
+        network_mode: NetworkMode::OFFLINE,
+        proof_level: ProofLevel::OFFLINE_STRICT,
+        model_catalog_violations: vec![],
+        model_catalog_error: None,
+    };
+    let blocks = evaluate_export_gate_all(&inputs);
+    let reasons: Vec<_> = blocks.iter().map(|b| b.reason.clone()).collect();
+    assert_eq!(
+        reasons,
+        vec![
+            ExportBlockReason::EVAL_FAILED,
+            ExportBlockReason::INSUFFICIENT_PINNING,
+            ExportBlockReason::MISSING_CITATIONS,
+        ]
+    );
+    assert_eq!(
+        blocks[0].inputs["blocker_gate_failures"][0],
+        "CITATIONS.STRICT_ENFORCED_V1"
+    );
+    assert_eq!(blocks[1].inputs["pinning_level"], "NAME_ONLY");
+    assert_eq!(
+        evaluate_export_gate(&inputs).err(),
+        Some(ExportBlockReason::EVAL_FAILED)
+    );
+}
+
+#[test]
+fn catalog_violations_block_apart_from_pinning() {
+    let model_use = |model_id: &str, pinning_level| ModelUse {
+        source: "model_snapshot".to_string(),
+        model_id: model_id.to_string(),
+        task_type: None,
+        pinning_level: Some(pinning_level),
+    };
+    let inputs = ExportGateInputs {
+        policy_mode: PolicyMode::BALANCED,
+        pinning_level: PinningLevel::VERSION_PINNED,
+        citations_required_passed: true,
+        redactions_required_passed: true,
+        blocker_gate_failures: vec![],
+        determinism_passed: true,
+        network_mode: NetworkMode::OFFLINE,
+        proof_level: ProofLevel::OFFLINE_STRICT,
+        model_catalog_violations: vec![
+            CatalogViolation {
+                model_use: model_use("model-x", PinningLevel::VERSION_PINNED),
+                reason: CatalogViolationReason::MODEL_NOT_IN_CATALOG,
+            },
+            CatalogViolation {
+                model_use: model_use("model-a", PinningLevel::VERSION_PINNED),
+                reason: CatalogViolationReason::PINNING_BELOW_CATALOG,
+            },
+        ],
+        model_catalog_error: None,
+    };
+    let blocks = evaluate_export_gate_all(&inputs);
+    let reasons: Vec<_> = blocks.iter().map(|b| b.reason.clone()).collect();
+    assert_eq!(
+        reasons,
+        [
+            ExportBlockReason::MODEL_CATALOG_VIOLATION,
+            ExportBlockReason::INSUFFICIENT_PINNING,
+        ]
+    );
+    assert_eq!(
+        blocks[0].inputs["model_catalog_violations"][0]["reason"],
+        "MODEL_NOT_IN_CATALOG"
+    );
+    assert_eq!(
+        blocks[1].inputs["model_catalog_violations"][0]["reason"],
+        "PINNING_BELOW_CATALOG"
+    );
+
+    // An untrusted catalog is a catalog violation too, not a failed eval gate.
+    let untrusted = ExportGateInputs {
+        model_catalog_violations: vec![],
+        model_catalog_error: Some("model catalog hash mismatch".to_string()),
+        ..inputs
+    };
+    assert_eq!(
+        evaluate_export_gate(&untrusted).err(),
+        Some(ExportBlockReason::MODEL_CATALOG_VIOLATION)
+    );
+}
+
+#[test]
+fn blocked_export_audits_and_returns_every_reason() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+    let mut req = common::strict_export_request(&run_id, "v_0001");
+    req.pinning_level = PinningLevel::NAME_ONLY;
+    req.network_mode = NetworkMode::ONLINE_ALLOWLISTED;
+
+    let outcome = mgr
+        .export_run(
+            &req,
+            &inputs,
+            &dir.path().join("bundle"),
+            &dir.path().join("bundle.zip"),
+        )
+        .unwrap();
+    assert_eq!(outcome.status, ExportStatus::BLOCKED);
+    assert_eq!(mgr.state, RunState::FAILED);
+    assert_eq!(
+        outcome.block_reason,
+        Some(ExportBlockReason::INSUFFICIENT_PINNING)
+    );
+    let reasons: Vec<_> = outcome
+        .block_reasons
+        .iter()
+        .map(|b| b.reason.clone())
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            ExportBlockReason::INSUFFICIENT_PINNING,
+            ExportBlockReason::OFFLINE_PROOF_INSUFFICIENT,
+        ]
+    );
+
+    let blocked: serde_json::Value = std::fs::read_to_string(&audit_path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+        .find(|e| e["event_type"] == "EXPORT_BLOCKED")
+        .unwrap();
+    assert_eq!(blocked["details"]["block_reason"], "INSUFFICIENT_PINNING");
+    let audited = blocked["details"]["meta"]["block_reasons"]
+        .as_array()
+        .unwrap();
+    assert_eq!(audited.len(), 2);
+    assert_eq!(audited[1]["reason"], "OFFLINE_PROOF_INSUFFICIENT");
+    assert_eq!(audited[1]["inputs"]["network_mode"], "ONLINE_ALLOWLISTED");
+}
+
+#[test]
+fn status_enums_keep_on_disk_json_strings() {
+    let outcome: ExportOutcome = serde_json::from_value(serde_json::json!({
+        "status": "BLOCKED",
+        "bundle_path": null,
+        "bundle_sha256": null,
+        "block_reason": "INSUFFICIENT_PINNING"
+    }))
+    .unwrap();
+    assert_eq!(outcome.status, ExportStatus::BLOCKED);
+    assert_eq!(serde_json::to_value(&outcome).unwrap()["status"], "BLOCKED");
+
+    let gate: GateRunResult = serde_json::from_value(serde_json::json!({
+        "gate_id": "DETERMINISM.PDF_CAPABLE_V1",
+        "result": "NOT_APPLICABLE",
+        "severity": "MAJOR",
+        "message": "n/a",
+        "evidence_pointers": []
+    }))
+    .unwrap();
+    assert_eq!(gate.result, GateStatus::NOT_APPLICABLE);
+    assert_eq!(gate.severity, Severity::MAJOR);
+    let v = serde_json::to_value(&gate).unwrap();
+    assert_eq!(v["result"], "NOT_APPLICABLE");
+    assert_eq!(v["severity"], "MAJOR");
+
+    assert!(serde_json::from_str::<Severity>("\"BLOCKR\"").is_err());
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use aigc_core::adapters::pinning::PinningLevel;
+use aigc_core::audit::log::AuditLog;
+use aigc_core::eval::runner::GateStatus;
+use aigc_core::policy::export_gate::ExportBlockReason;
+use aigc_core::run::manager::{PreviewStatus, RunManager, RunState};
+
+#[test]
+fn preview_reports_gate_decision_without_side_effects() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let audit_path = dir.path().join("audit.ndjson");
+    let scratch = dir.path().join("scratch");
+    std::fs::create_dir_all(&scratch).unwrap();
+    let mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+
+    let preview = mgr
+        .preview_export(
+            &common::strict_export_request(&run_id, "v_0001"),
+            &inputs,
+            &scratch,
+        )
+        .unwrap();
+    assert_eq!(preview.status, PreviewStatus::EXPORTABLE);
+    assert!(preview.block_reason.is_none());
+    assert!(preview
+        .gate_results
+        .iter()
+        .any(|g| g.gate_id == "EVIDENCEOS.OUTPUTS_PRESENT_V1" && g.result == GateStatus::PASS));
+
+    let mut req = common::strict_export_request(&run_id, "v_0001");
+    req.pinning_level = PinningLevel::NAME_ONLY;
+    let preview = mgr.preview_export(&req, &inputs, &scratch).unwrap();
+    assert_eq!(preview.status, PreviewStatus::BLOCKED);
+    assert_eq!(
+        preview.block_reason,
+        Some(ExportBlockReason::INSUFFICIENT_PINNING)
+    );
+    assert!(preview.blocker_gate_failures.is_empty());
+
+    assert_eq!(mgr.state, RunState::READY);
+    assert_eq!(std::fs::read_to_string(&audit_path).unwrap(), "");
+    assert_eq!(std::fs::read_dir(&scratch).unwrap().count(), 0);
+}
@@ -0,0 +1,240 @@
+mod common;
+
+use aigc_core::audit::log::AuditLog;
+use aigc_core::error::CoreResult;
+use aigc_core::policy::types::{NetworkMode, PolicyMode};
+use aigc_core::run::checkpoint::load_checkpoint;
+use aigc_core::run::manager::{ExportStatus, RunManager, RunState};
+use aigc_core::run::registry::{RunRecord, RunRegistry};
+use aigc_core::run::sink::{CompletedBundle, DeliveryStatus, ExportSink};
+use aigc_core::storage::crypto::EncryptionAlgorithm;
+use aigc_core::storage::vault::{VaultConfig, VaultStorage};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Counts deliveries; a crashing sink panics the way a killed process stops mid-delivery.
+struct CountingSink {
+    id: &'static str,
+    calls: Arc<AtomicUsize>,
+    crash: bool,
+}
+
+impl ExportSink for CountingSink {
+    fn sink_id(&self) -> &str {
+        self.id
+    }
+
+    fn deliver(&self, bundle: &CompletedBundle, _audit: &mut AuditLog) -> CoreResult<String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.crash {
+            panic!("process killed during delivery");
+        }
+        Ok(format!("{}:{}", self.id, bundle.bundle_sha256))
+    }
+}
+
+fn registered_manager(vault_root: &Path, audit_path: &Path, run_id: &str) -> RunManager {
+    let vault = VaultStorage::create(
+        vault_root,
+        VaultConfig {
+            vault_id: "v_0001".to_string(),
+            encryption_algorithm: EncryptionAlgorithm::XCHACHA20_POLY1305,
+            encryption_at_rest: true,
+        },
+    )
+    .unwrap();
+    let mut mgr = RunManager::new(AuditLog::open_or_create(audit_path).unwrap());
+    mgr.attach_registry(
+        RunRegistry::open(vault).unwrap(),
+        RunRecord {
+            run_id: run_id.to_string(),
+            vault_id: "v_0001".to_string(),
+            pack_id: "evidenceos".to_string(),
+            pack_version: "1.0.0".to_string(),
+            state: RunState::READY,
+            // Placeholders until an export request records the modes it runs under.
+            policy_mode: PolicyMode::DRAFT_ONLY,
+            network_mode: NetworkMode::ONLINE_ALLOWLISTED,
+            determinism_enabled: false,
+            audit_log_path: audit_path.to_string_lossy().to_string(),
+            artifact_count: 0,
+            bundle_dir: None,
+            bundle_zip: None,
+        },
+    )
+    .unwrap();
+    mgr
+}
+
+fn read_events(path: &Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+fn count(events: &[serde_json::Value], event_type: &str) -> usize {
+    events
+        .iter()
+        .filter(|e| e["event_type"] == event_type)
+        .count()
+}
+
+#[test]
+fn export_completes_and_leaves_no_checkpoint_or_scratch() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let out = dir.path().join("out");
+    std::fs::create_dir_all(&out).unwrap();
+    let mut mgr = registered_manager(
+        &dir.path().join("vault"),
+        &dir.path().join("audit.ndjson"),
+        &run_id,
+    );
+
+    let outcome = mgr
+        .export_run(
+            &common::strict_export_request(&run_id, "v_0001"),
+            &inputs,
+            &out.join("bundle"),
+            &out.join("bundle.zip"),
+        )
+        .unwrap();
+    assert_eq!(outcome.status, ExportStatus::COMPLETED);
+    assert_eq!(mgr.state, RunState::COMPLETED);
+
+    let record = mgr.registry().unwrap().get(&run_id).unwrap();
+    assert_eq!(record.policy_mode, PolicyMode::STRICT);
+    assert_eq!(record.network_mode, NetworkMode::OFFLINE);
+    let vault = mgr.registry().unwrap().vault();
+    assert!(load_checkpoint(vault, &run_id).unwrap().is_none());
+    let mut leftovers: Vec<String> = std::fs::read_dir(&out)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    leftovers.sort();
+    assert_eq!(leftovers, vec!["bundle", "bundle.zip"]);
+}
+
+#[test]
+fn interrupted_export_resumes_after_last_checkpointed_step() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let vault_root = dir.path().join("vault");
+    let audit_path = dir.path().join("audit.ndjson");
+    let bundle_dir = dir.path().join("bundle");
+    let bundle_zip = dir.path().join("bundle.zip");
+    let req = common::strict_export_request(&run_id, "v_0001");
+
+    // A file squatting on the bundle dir makes the final build fail after the gate decision.
+    std::fs::write(&bundle_dir, b"not a directory").unwrap();
+    {
+        let mut mgr = registered_manager(&vault_root, &audit_path, &run_id);
+        assert!(mgr
+            .export_run(&req, &inputs, &bundle_dir, &bundle_zip)
+            .is_err());
+        assert_eq!(mgr.state, RunState::EXPORTING);
+    }
+    let before = read_events(&audit_path);
+    let gates_evaluated = count(&before, "EVAL_GATE_RESULT");
+    assert!(gates_evaluated > 0);
+    assert_eq!(count(&before, "BUNDLE_GENERATION_STARTED"), 1);
+
+    std::fs::remove_file(&bundle_dir).unwrap();
+    let registry = RunRegistry::open(VaultStorage::open(&vault_root).unwrap()).unwrap();
+    let cp = load_checkpoint(registry.vault(), &run_id).unwrap().unwrap();
+    assert_eq!(cp.gate_results.len(), gates_evaluated);
+    let mut mgr = RunManager::resume(registry, &run_id).unwrap();
+    let outcome = mgr
+        .export_run(&req, &inputs, &bundle_dir, &bundle_zip)
+        .unwrap();
+    assert_eq!(outcome.status, ExportStatus::COMPLETED);
+
+    let events = read_events(&audit_path);
+    let marker = &events[before.len()];
+    assert_eq!(marker["event_type"], "EXPORT_RESUMED");
+    assert_eq!(marker["details"]["resumed_from_step"], "FINAL_BUILD");
+    assert_eq!(
+        marker["details"]["requested_event_hash"],
+        cp.requested_event_hash.as_str()
+    );
+    assert_eq!(
+        marker["details"]["checkpoint_event_hash"],
+        cp.last_event_hash()
+    );
+    // Finished steps are not replayed into the chain.
+    assert_eq!(count(&events, "EXPORT_REQUESTED"), 1);
+    assert_eq!(count(&events, "EVAL_STARTED"), 1);
+    assert_eq!(count(&events, "EVAL_COMPLETED"), 1);
+    assert_eq!(count(&events, "EVAL_GATE_RESULT"), gates_evaluated);
+    assert_eq!(count(&events, "EXPORT_COMPLETED"), 1);
+    let registry = mgr.registry().unwrap();
+    assert_eq!(registry.get(&run_id).unwrap().state, RunState::COMPLETED);
+    assert!(load_checkpoint(registry.vault(), &run_id)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn resumed_export_does_not_redeliver_to_sinks() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let vault_root = dir.path().join("vault");
+    let audit_path = dir.path().join("audit.ndjson");
+    let bundle_dir = dir.path().join("bundle");
+    let bundle_zip = dir.path().join("bundle.zip");
+    let req = common::strict_export_request(&run_id, "v_0001");
+    let archive_calls = Arc::new(AtomicUsize::new(0));
+    let share_calls = Arc::new(AtomicUsize::new(0));
+    let sink = |id, calls: &Arc<AtomicUsize>, crash| {
+        Box::new(CountingSink {
+            id,
+            calls: calls.clone(),
+            crash,
+        })
+    };
+
+    let crashed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let mut mgr = registered_manager(&vault_root, &audit_path, &run_id)
+            .with_export_sink(sink("archive", &archive_calls, false))
+            .with_export_sink(sink("share", &share_calls, true));
+        mgr.export_run(&req, &inputs, &bundle_dir, &bundle_zip)
+    }));
+    assert!(crashed.is_err());
+    assert_eq!(count(&read_events(&audit_path), "EXPORT_COMPLETED"), 0);
+
+    let registry = RunRegistry::open(VaultStorage::open(&vault_root).unwrap()).unwrap();
+    let cp = load_checkpoint(registry.vault(), &run_id).unwrap().unwrap();
+    assert_eq!(cp.deliveries.len(), 1);
+    let mut mgr = RunManager::resume(registry, &run_id)
+        .unwrap()
+        .with_export_sink(sink("archive", &archive_calls, false))
+        .with_export_sink(sink("share", &share_calls, false));
+    let outcome = mgr
+        .export_run(&req, &inputs, &bundle_dir, &bundle_zip)
+        .unwrap();
+    assert_eq!(outcome.status, ExportStatus::COMPLETED);
+    assert_eq!(archive_calls.load(Ordering::SeqCst), 1);
+    assert_eq!(share_calls.load(Ordering::SeqCst), 2);
+    assert_eq!(outcome.deliveries.len(), 2);
+    assert!(outcome
+        .deliveries
+        .iter()
+        .all(|d| d.status == DeliveryStatus::DELIVERED));
+
+    let events = read_events(&audit_path);
+    assert_eq!(count(&events, "EXPORT_COMPLETED"), 1);
+    let completed = events
+        .iter()
+        .find(|e| e["event_type"] == "EXPORT_COMPLETED")
+        .unwrap();
+    assert_eq!(
+        completed["details"]["meta"]["deliveries"][0]["sink_id"],
+        "archive"
+    );
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use aigc_core::audit::log::AuditLog;
+use aigc_core::determinism::run_id::sha256_hex;
+use aigc_core::policy::allowlist::AllowlistEntry;
+use aigc_core::policy::egress::EgressPolicy;
+use aigc_core::policy::types::{NetworkMode, ProofLevel};
+use aigc_core::run::manager::{ExportStatus, RunManager, RunState};
+use aigc_core::run::sink::{ContentAddressedSink, DeliveryStatus, LocalDirSink, LoopbackHttpSink};
+use common::http_stub::{StubResponse, StubServer};
+use std::path::Path;
+
+fn events(path: &Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+fn loopback_policy(port: u16) -> EgressPolicy {
+    EgressPolicy {
+        network_mode: NetworkMode::ONLINE_ALLOWLISTED,
+        proof_level: ProofLevel::OFFLINE_STRICT,
+        allowlist: vec![AllowlistEntry {
+            scheme: "http".to_string(),
+            host: "127.0.0.1".to_string(),
+            port,
+            path_prefix: Some("/bundles".to_string()),
+            purpose: "evidence archive".to_string(),
+            policy_pack_id: "default".to_string(),
+            policy_pack_version: "1.0.0".to_string(),
+            max_requests_per_run: None,
+            max_bytes_per_run: None,
+        }
+        .canonicalize()
+        .unwrap()],
+    }
+}
+
+#[test]
+fn completed_export_is_delivered_to_local_and_content_addressed_sinks() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let audit_path = dir.path().join("audit.ndjson");
+    let share = dir.path().join("share");
+    let archive = dir.path().join("archive");
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap())
+        .with_export_sink(Box::new(LocalDirSink::new(&share)))
+        .with_export_sink(Box::new(ContentAddressedSink::new(&archive)));
+
+    let outcome = mgr
+        .export_run(
+            &common::strict_export_request(&run_id, "v_0001"),
+            &inputs,
+            &dir.path().join("bundle"),
+            &dir.path().join("bundle.zip"),
+        )
+        .unwrap();
+    assert_eq!(outcome.status, ExportStatus::COMPLETED);
+    let sha = outcome.bundle_sha256.clone().unwrap();
+    let zip_bytes = std::fs::read(dir.path().join("bundle.zip")).unwrap();
+
+    assert_eq!(outcome.deliveries.len(), 2);
+    assert!(outcome
+        .deliveries
+        .iter()
+        .all(|d| d.status == DeliveryStatus::DELIVERED));
+    assert_eq!(std::fs::read(share.join("bundle.zip")).unwrap(), zip_bytes);
+    let stored = ContentAddressedSink::new(&archive).path_for(&sha);
+    assert_eq!(sha256_hex(&std::fs::read(&stored).unwrap()), sha);
+    assert_eq!(
+        outcome.deliveries[1].location.as_deref(),
+        Some(stored.to_string_lossy().as_ref())
+    );
+
+    let evs = events(&audit_path);
+    let completed: Vec<_> = evs
+        .iter()
+        .filter(|e| e["event_type"] == "EXPORT_COMPLETED")
+        .collect();
+    assert_eq!(completed.len(), 1);
+    let recorded = &completed[0]["details"]["meta"]["deliveries"];
+    assert_eq!(recorded[0]["sink_id"], "local_dir");
+    assert_eq!(recorded[1]["sink_id"], "content_addressed");
+    assert_eq!(completed[0]["details"]["bundle_sha256"], sha.as_str());
+    // Deliveries happen before the run is closed.
+    assert_eq!(evs.last().unwrap()["event_type"], "RUN_COMPLETED");
+}
+
+#[test]
+fn http_sink_posts_through_egress_and_failures_do_not_fail_the_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let audit_path = dir.path().join("audit.ndjson");
+    let server = StubServer::start(|_| StubResponse::json(201, serde_json::json!({})));
+    let port: u16 = server.endpoint.rsplit(':').next().unwrap().parse().unwrap();
+
+    let accepted = format!("{}/bundles/upload", server.endpoint);
+    let unlisted = format!("{}/other", server.endpoint);
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap())
+        .with_export_sink(Box::new(
+            LoopbackHttpSink::new(&accepted, loopback_policy(port)).unwrap(),
+        ))
+        .with_export_sink(Box::new(
+            LoopbackHttpSink::new(&unlisted, loopback_policy(port)).unwrap(),
+        ));
+
+    let outcome = mgr
+        .export_run(
+            &common::strict_export_request(&run_id, "v_0001"),
+            &inputs,
+            &dir.path().join("bundle"),
+            &dir.path().join("bundle.zip"),
+        )
+        .unwrap();
+    assert_eq!(outcome.status, ExportStatus::COMPLETED);
+    assert_eq!(mgr.state, RunState::COMPLETED);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(
+        requests[0].body,
+        std::fs::read(dir.path().join("bundle.zip")).unwrap()
+    );
+    assert_eq!(outcome.deliveries[0].status, DeliveryStatus::DELIVERED);
+    assert_eq!(outcome.deliveries[1].status, DeliveryStatus::FAILED);
+    assert!(outcome.deliveries[1]
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("NOT_ALLOWLISTED"));
+
+    let types: Vec<String> = events(&audit_path)
+        .iter()
+        .map(|e| e["event_type"].as_str().unwrap().to_string())
+        .collect();
+    let tail = &types[types.len() - 5..];
+    assert_eq!(
+        tail,
+        [
+            "EGRESS_REQUEST_ALLOWED",
+            "EGRESS_REQUEST_BLOCKED",
+            "EXPORT_COMPLETED",
+            "RUN_STATE_CHANGED",
+            "RUN_COMPLETED",
+        ]
+    );
+    assert!(!types.iter().any(|t| t == "EXPORT_FAILED"));
+
+    assert!(LoopbackHttpSink::new("http://10.0.0.5:8080/bundles", loopback_policy(8080)).is_err());
+}
@@ -0,0 +1,437 @@
+mod common;
+
+use aigc_core::adapters::calls::ModelCallScope;
+use aigc_core::adapters::http::HttpAdapterClient;
+use aigc_core::adapters::interface::{
+    ChatMessage, EmbedRequest, GenerateRequest, SafetyProfile, StreamOptions, TranscribeRequest,
+};
+use aigc_core::audit::log::AuditLog;
+use aigc_core::determinism::run_id::sha256_hex;
+use aigc_core::error::CoreError;
+use aigc_core::run::cancel::CancellationToken;
+use aigc_core::run::manager::{ExportStatus, RunManager};
+use common::http_stub::{StubResponse, StubServer};
+use serde_json::json;
+use std::path::Path;
+use std::time::Duration;
+
+fn events(path: &Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+fn scope(run_id: &str) -> ModelCallScope {
+    ModelCallScope {
+        run_id: run_id.to_string(),
+        vault_id: "v_0001".to_string(),
+        adapter_version: "0.3.1".to_string(),
+        input_artifact_refs: vec!["a_ev_0002".to_string(), "a_ev_0001".to_string()],
+    }
+}
+
+fn generate_request(call_id: &str) -> GenerateRequest {
+    GenerateRequest {
+        call_id: call_id.to_string(),
+        model_id: "llama3-8b".to_string(),
+        messages: vec![ChatMessage {
+            role: "user".to_string(),
+            content: "Summarise the evidence.".to_string(),
+        }],
+        response_mode: "TEXT".to_string(),
+        json_schema: None,
+        temperature: Some(0.0),
+        top_p: None,
+        max_tokens: Some(256),
+        seed: Some(7),
+        context_chunks: vec![],
+        safety_profile: SafetyProfile::default(),
+    }
+}
+
+fn stub_adapter() -> StubServer {
+    StubServer::start(|req| match req.path.as_str() {
+        "/v1/capabilities" => StubResponse::json(
+            200,
+            json!({"adapter_type": "LLM", "features": ["streaming"], "limits": {}, "models": []}),
+        ),
+        "/v1/llm/generate" => {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+            match body["call_id"].as_str().unwrap() {
+                "c_refused" => StubResponse::json(200, json!({"status": "refused"})),
+                "c_echo" => StubResponse::json(
+                    500,
+                    json!({"detail": "bad prompt: Summarise the evidence."}),
+                ),
+                "c_slow" => StubResponse::json(200, json!({"status": "ok", "output_text": "late"}))
+                    .delayed(Duration::from_millis(500)),
+                "c_blocked" => StubResponse::json(
+                    400,
+                    json!({"error": {
+                        "code": "SAFETY_BLOCK",
+                        "message": "prompt blocked",
+                        "retryable": false,
+                        "category": "SAFETY_REFUSAL",
+                        "details": {}
+                    }}),
+                ),
+                "c_too_large" => StubResponse::json(413, json!({"detail": "request too large"})),
+                _ => StubResponse::json(
+                    200,
+                    json!({
+                        "status": "ok",
+                        "output_text": "The evidence is complete.",
+                        "usage": {"tokens_in": 12, "tokens_out": 5},
+                        "timing_ms": 3
+                    }),
+                ),
+            }
+        }
+        "/v1/llm/generate/stream" => {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+            let gap = if body["call_id"]
+                .as_str()
+                .unwrap()
+                .starts_with("c_stream_slow")
+            {
+                Duration::from_millis(300)
+            } else {
+                Duration::ZERO
+            };
+            StubResponse::ndjson_stream(
+                vec![
+                    json!({"delta": "The evidence "}),
+                    json!({"delta": "is complete."}),
+                    json!({"done": true, "status": "ok", "usage": {"tokens_out": 5}}),
+                ],
+                gap,
+            )
+        }
+        "/v1/emb/embed" => {
+            StubResponse::json(200, json!({"vectors": [[0.5, 0.25], [1.0, 0.0]], "dim": 2}))
+        }
+        "/v1/stt/transcribe" => StubResponse::json(
+            200,
+            json!({
+                "segments": [{"start_ms": 0, "end_ms": 900, "speaker": "S1", "text": "hello"}],
+                "full_text": "hello"
+            }),
+        ),
+        _ => StubResponse::json(404, json!({"detail": "route not found"})),
+    })
+}
+
+#[test]
+fn model_calls_are_audited_and_listed_in_the_run_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let audit_path = dir.path().join("audit.ndjson");
+    let server = stub_adapter();
+    let client = HttpAdapterClient::new(&server.endpoint)
+        .unwrap()
+        .with_timeout(Duration::from_millis(2000));
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+    let scope = scope(&run_id);
+
+    let generated = mgr
+        .generate(&client, &scope, &generate_request("c_gen_1"))
+        .unwrap();
+    assert_eq!(
+        generated.output_text.as_deref(),
+        Some("The evidence is complete.")
+    );
+    let embedded = mgr
+        .embed(
+            &client,
+            &scope,
+            &EmbedRequest {
+                call_id: "c_emb_1".to_string(),
+                model_id: "bge-small".to_string(),
+                texts: vec!["a".to_string(), "b".to_string()],
+                normalize: true,
+            },
+        )
+        .unwrap();
+    assert_eq!(embedded.dim, 2);
+    let transcript = mgr
+        .transcribe(
+            &client,
+            &scope,
+            &TranscribeRequest {
+                call_id: "c_stt_1".to_string(),
+                model_id: "whisper-small".to_string(),
+                audio_uri: "vault://a_ev_0002".to_string(),
+                language: Some("en".to_string()),
+                timestamps: true,
+                diarization: true,
+            },
+        )
+        .unwrap();
+    assert_eq!(transcript.full_text, "hello");
+
+    let evs = events(&audit_path);
+    let types: Vec<&str> = evs
+        .iter()
+        .map(|e| e["event_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        types,
+        [
+            "MODEL_CALL_STARTED",
+            "MODEL_CALL_COMPLETED",
+            "MODEL_CALL_STARTED",
+            "MODEL_CALL_COMPLETED",
+            "MODEL_CALL_STARTED",
+            "MODEL_CALL_COMPLETED",
+        ]
+    );
+    let started = &evs[0]["details"];
+    assert_eq!(started["task_type"], "LLM");
+    assert_eq!(started["timeout_ms"], 2000);
+    assert_eq!(
+        started["input_artifact_refs"],
+        json!(["a_ev_0001", "a_ev_0002"])
+    );
+    // The request hash covers exactly the body the adapter received.
+    let sent = &server.requests()[0].body;
+    assert_eq!(started["request_hash_sha256"], sha256_hex(sent).as_str());
+    assert_eq!(
+        evs[1]["details"]["response_hash_sha256"],
+        sha256_hex(b"The evidence is complete.").as_str()
+    );
+    assert_eq!(evs[2]["details"]["task_type"], "EMBED");
+    assert_eq!(evs[4]["details"]["task_type"], "STT");
+
+    let calls = mgr.model_calls().calls();
+    assert_eq!(calls.len(), 3);
+    assert_eq!(calls[0].usage["tokens_out"], 5);
+    assert_eq!(calls[0].adapter_version, "0.3.1");
+
+    let bundle_dir = dir.path().join("bundle");
+    let outcome = mgr
+        .export_run(
+            &common::strict_export_request(&run_id, "v_0001"),
+            &inputs,
+            &bundle_dir,
+            &dir.path().join("bundle.zip"),
+        )
+        .unwrap();
+    assert_eq!(outcome.status, ExportStatus::COMPLETED);
+    let manifest: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(bundle_dir.join("run_manifest.json")).unwrap())
+            .unwrap();
+    let listed: Vec<&str> = manifest["model_calls"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["call_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(listed, ["c_gen_1", "c_emb_1", "c_stt_1"]);
+    assert_eq!(
+        manifest["model_calls"][0]["output_hash"],
+        sha256_hex(b"The evidence is complete.").as_str()
+    );
+    assert_eq!(manifest["model_calls"][0]["status"], "ok");
+}
+
+#[test]
+fn failed_model_calls_record_error_category() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let server = stub_adapter();
+    let client = HttpAdapterClient::new(&server.endpoint)
+        .unwrap()
+        .with_timeout(Duration::from_millis(100));
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+    let scope = scope("r_calls");
+
+    assert!(matches!(
+        mgr.generate(&client, &scope, &generate_request("c_slow")),
+        Err(CoreError::Adapter(_))
+    ));
+    assert!(mgr
+        .generate(&client, &scope, &generate_request("c_refused"))
+        .is_err());
+    let echoed = mgr
+        .generate(&client, &scope, &generate_request("c_echo"))
+        .unwrap_err();
+    assert!(echoed.to_string().contains("Summarise the evidence."));
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let closed = HttpAdapterClient::new(&format!("http://127.0.0.1:{}", port)).unwrap();
+    assert!(mgr
+        .generate(&closed, &scope, &generate_request("c_down"))
+        .is_err());
+    for call_id in ["c_blocked", "c_too_large"] {
+        assert!(mgr
+            .generate(&client, &scope, &generate_request(call_id))
+            .is_err());
+    }
+    // Reusing a call_id is rejected before anything is sent or audited.
+    assert!(matches!(
+        mgr.generate(&client, &scope, &generate_request("c_slow")),
+        Err(CoreError::InvalidInput(_))
+    ));
+
+    let failed: Vec<serde_json::Value> = events(&audit_path)
+        .into_iter()
+        .filter(|e| e["event_type"] == "MODEL_CALL_FAILED")
+        .map(|e| e["details"].clone())
+        .collect();
+    let categories: Vec<(&str, &str)> = failed
+        .iter()
+        .map(|d| {
+            (
+                d["error_category"].as_str().unwrap(),
+                d["error_code"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        categories,
+        [
+            ("TIMEOUT", "ADAPTER_TIMEOUT"),
+            ("MODEL_ERROR", "SAFETY_REFUSAL"),
+            ("MODEL_ERROR", "RUNTIME_ERROR"),
+            ("ADAPTER_UNAVAILABLE", "ADAPTER_UNAVAILABLE"),
+            ("MODEL_ERROR", "SAFETY_BLOCK"),
+            ("INVALID_REQUEST", "INVALID_INPUT"),
+        ]
+    );
+    assert_eq!(failed[0]["meta"]["retryable"], true);
+    // Only category and code reach the log, never the adapter's response body.
+    assert_eq!(
+        failed[2]["error_message_redacted"],
+        "MODEL_ERROR (RUNTIME_ERROR); adapter message withheld"
+    );
+    assert!(!std::fs::read_to_string(&audit_path)
+        .unwrap()
+        .contains("Summarise the evidence."));
+
+    let statuses: Vec<&str> = mgr
+        .model_calls()
+        .calls()
+        .iter()
+        .map(|c| c.status.as_str())
+        .collect();
+    // Refusals are recognised by category, whatever code the adapter chose.
+    assert_eq!(
+        statuses,
+        ["error", "refused", "error", "error", "refused", "error"]
+    );
+    assert!(mgr
+        .model_calls()
+        .calls()
+        .iter()
+        .all(|c| c.output_hash.is_empty()));
+}
+
+fn stream_options(timeout_ms: u64) -> StreamOptions {
+    StreamOptions {
+        timeout: Duration::from_millis(timeout_ms),
+        cancel: CancellationToken::new(),
+    }
+}
+
+#[test]
+fn streamed_generate_hashes_to_the_full_response() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let server = stub_adapter();
+    let client = HttpAdapterClient::new(&server.endpoint).unwrap();
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+    let scope = scope("r_stream");
+
+    let mut deltas = Vec::new();
+    let streamed = mgr
+        .generate_stream(
+            &client,
+            &scope,
+            &generate_request("c_stream"),
+            &stream_options(5000),
+            &mut |d| deltas.push(d.to_string()),
+        )
+        .unwrap();
+    assert_eq!(deltas, ["The evidence ", "is complete."]);
+    assert_eq!(
+        streamed.output_text.as_deref(),
+        Some("The evidence is complete.")
+    );
+    mgr.generate(&client, &scope, &generate_request("c_whole"))
+        .unwrap();
+
+    let calls = mgr.model_calls().calls();
+    assert_eq!(calls[0].output_hash, calls[1].output_hash);
+    assert_eq!(calls[0].usage["tokens_out"], 5);
+    let evs = events(&audit_path);
+    assert_eq!(evs[0]["details"]["timeout_ms"], 5000);
+    assert_eq!(
+        evs[1]["details"]["response_hash_sha256"],
+        sha256_hex(b"The evidence is complete.").as_str()
+    );
+    // JSON responses cannot be streamed.
+    let mut json_req = generate_request("c_stream_json");
+    json_req.response_mode = "JSON".to_string();
+    assert!(mgr
+        .generate_stream(
+            &client,
+            &scope,
+            &json_req,
+            &stream_options(5000),
+            &mut |_| {}
+        )
+        .is_err());
+}
+
+#[test]
+fn aborted_streams_record_partial_output_hash() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let server = stub_adapter();
+    let client = HttpAdapterClient::new(&server.endpoint).unwrap();
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&audit_path).unwrap());
+    let scope = scope("r_stream");
+
+    // Cancelled from the UI after the first delta, while waiting for the next one.
+    let opts = stream_options(5000);
+    let token = opts.cancel.clone();
+    let mut req = generate_request("c_stream_slow");
+    let err = mgr
+        .generate_stream(&client, &scope, &req, &opts, &mut |_| token.cancel())
+        .unwrap_err();
+    assert!(matches!(err, CoreError::Cancelled(_)), "{:?}", err);
+
+    // Per-stream deadline shorter than the gap between chunks.
+    req.call_id = "c_stream_slow_2".to_string();
+    let started = std::time::Instant::now();
+    let err = mgr
+        .generate_stream(&client, &scope, &req, &stream_options(150), &mut |_| {})
+        .unwrap_err();
+    assert!(started.elapsed() < Duration::from_millis(1000));
+    assert!(matches!(err, CoreError::Adapter(_)));
+
+    let failed: Vec<serde_json::Value> = events(&audit_path)
+        .into_iter()
+        .filter(|e| e["event_type"] == "MODEL_CALL_FAILED")
+        .map(|e| e["details"].clone())
+        .collect();
+    assert_eq!(failed.len(), 2);
+    assert_eq!(failed[0]["error_code"], "CANCELLED");
+    assert_eq!(failed[1]["error_category"], "TIMEOUT");
+    assert_eq!(failed[1]["error_code"], "STREAM_TIMEOUT");
+    let partial = sha256_hex(b"The evidence ");
+    for d in &failed {
+        assert_eq!(d["meta"]["partial_output_hash_sha256"], partial.as_str());
+        assert_eq!(d["meta"]["partial_output_chunks"], 1);
+    }
+    let calls = mgr.model_calls().calls();
+    assert_eq!(calls[0].status, "cancelled");
+    assert_eq!(calls[1].status, "error");
+    assert_eq!(calls[1].output_hash, partial);
+}
@@ -0,0 +1,359 @@
+mod common;
+
+use aigc_core::adapters::interface::AdapterModel;
+use aigc_core::adapters::pinning::PinningLevel;
+use aigc_core::adapters::routing::{
+    select_model, ModelConstraints, RejectReason, RoutingRequest, TaskType,
+};
+use aigc_core::adapters::runtime::RegisteredAdapter;
+use aigc_core::audit::log::AuditLog;
+use aigc_core::error::CoreError;
+use aigc_core::eval::runner::GateStatus;
+use aigc_core::policy::export_gate::{ExportBlock, ExportBlockReason};
+use aigc_core::policy::model_catalog::{
+    collect_model_uses, CatalogViolationReason, ModelCatalog, ModelUse,
+};
+use aigc_core::policy::types::PolicyMode;
+use aigc_core::run::manager::{ExportStatus, RunManager};
+use serde_json::json;
+
+const GATE: &str = "MODEL_CATALOG.ALLOWED_MODELS_V1";
+
+fn catalog() -> ModelCatalog {
+    ModelCatalog::from_json(
+        json!({
+            "catalog_version": "model_catalog_v1",
+            "policy_pack_id": "pp_healthcare",
+            "policy_pack_version": "1.2.0",
+            "denied_model_ids": ["leaky-7b"],
+            "packs": [
+                {
+                    "pack_id": "healthcareos",
+                    "policy_modes": ["STRICT", "BALANCED"],
+                    "models": [
+                        {
+                            "model_id": "whisper-med-v3",
+                            "task_types": ["STT"],
+                            "min_pinning_level": "CRYPTO_PINNED"
+                        },
+                        {
+                            "model_id": "llama3-8b",
+                            "task_types": ["LLM"],
+                            "min_pinning_level": "VERSION_PINNED"
+                        }
+                    ]
+                }
+            ]
+        })
+        .to_string()
+        .as_bytes(),
+    )
+    .unwrap()
+}
+
+fn model_use(model_id: &str, task_type: TaskType, level: PinningLevel) -> ModelUse {
+    ModelUse {
+        source: "test".to_string(),
+        model_id: model_id.to_string(),
+        task_type: Some(task_type),
+        pinning_level: Some(level),
+    }
+}
+
+fn stt_adapter(models: &[(&str, bool)]) -> RegisteredAdapter {
+    RegisteredAdapter {
+        adapter_id: "stt_local".to_string(),
+        endpoint: "http://127.0.0.1:11435".to_string(),
+        adapter_type: "STT".to_string(),
+        adapter_version: "1.0.0".to_string(),
+        features: vec![],
+        models: models
+            .iter()
+            .map(|(id, sha)| AdapterModel {
+                model_id: id.to_string(),
+                model_sha256: sha.then(|| "ab".repeat(32)),
+                quantization: None,
+                context_window: None,
+                notes: None,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn catalog_checks_models_per_pack_mode_and_task() {
+    let c = catalog();
+    let check = |pack: &str, mode: PolicyMode, u: ModelUse| c.check(pack, mode, &u);
+    let strict = PolicyMode::STRICT;
+    let crypto = PinningLevel::CRYPTO_PINNED;
+
+    assert!(check(
+        "healthcareos",
+        strict,
+        model_use("whisper-med-v3", TaskType::STT, crypto)
+    )
+    .is_empty());
+    assert_eq!(
+        check(
+            "healthcareos",
+            strict,
+            model_use("whisper-tiny", TaskType::STT, crypto)
+        ),
+        [CatalogViolationReason::MODEL_NOT_IN_CATALOG]
+    );
+    assert_eq!(
+        check(
+            "healthcareos",
+            strict,
+            model_use("llama3-8b", TaskType::STT, crypto)
+        ),
+        [CatalogViolationReason::TASK_TYPE_NOT_IN_CATALOG]
+    );
+    assert_eq!(
+        check(
+            "healthcareos",
+            strict,
+            model_use(
+                "whisper-med-v3",
+                TaskType::STT,
+                PinningLevel::VERSION_PINNED
+            )
+        ),
+        [CatalogViolationReason::PINNING_BELOW_CATALOG]
+    );
+    // DRAFT_ONLY has no section for the pack, and other packs are not restricted...
+    let draft = PolicyMode::DRAFT_ONLY;
+    assert!(check(
+        "healthcareos",
+        draft,
+        model_use("whisper-tiny", TaskType::STT, crypto)
+    )
+    .is_empty());
+    assert!(check(
+        "evidenceos",
+        strict,
+        model_use("whisper-tiny", TaskType::STT, crypto)
+    )
+    .is_empty());
+    // ...but denied models are refused everywhere.
+    assert_eq!(
+        check(
+            "evidenceos",
+            draft,
+            model_use("leaky-7b", TaskType::LLM, crypto)
+        ),
+        [CatalogViolationReason::MODEL_DENIED]
+    );
+}
+
+#[test]
+fn catalog_hash_ignores_list_order_and_rejects_bad_files() {
+    let mut reordered = catalog();
+    reordered.packs[0].models.reverse();
+    reordered.packs[0].policy_modes.reverse();
+    assert_eq!(
+        reordered.hash_sha256().unwrap(),
+        catalog().hash_sha256().unwrap()
+    );
+    let snapshot = catalog().snapshot().unwrap();
+    assert_eq!(snapshot.model_catalog_hash_sha256.len(), 64);
+    snapshot.verify().unwrap();
+
+    let mut tampered = snapshot.clone();
+    tampered.catalog.denied_model_ids.clear();
+    assert!(tampered.verify().is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model_catalog.json");
+    std::fs::write(&path, serde_json::to_vec(&catalog()).unwrap()).unwrap();
+    assert_eq!(ModelCatalog::load(&path).unwrap(), catalog());
+
+    let mut bad = serde_json::to_value(catalog()).unwrap();
+    bad["catalog_version"] = json!("model_catalog_v0");
+    assert!(ModelCatalog::from_json(bad.to_string().as_bytes()).is_err());
+    let mut dup = serde_json::to_value(catalog()).unwrap();
+    let first = dup["packs"][0]["models"][0].clone();
+    dup["packs"][0]["models"]
+        .as_array_mut()
+        .unwrap()
+        .push(first);
+    assert!(ModelCatalog::from_json(dup.to_string().as_bytes()).is_err());
+}
+
+#[test]
+fn routing_skips_models_outside_the_catalog() {
+    let adapters = vec![stt_adapter(&[
+        ("leaky-7b", true),
+        ("whisper-med-v3", true),
+        ("whisper-tiny", true),
+    ])];
+    let req = RoutingRequest {
+        task_type: TaskType::STT,
+        policy_mode: PolicyMode::STRICT,
+        preferred_model: Some("whisper-tiny".to_string()),
+        constraints: ModelConstraints::default(),
+        catalog: None,
+    };
+    let unrestricted = select_model(&adapters, &req).unwrap();
+    assert_eq!(unrestricted.model.model_id, "whisper-tiny");
+
+    let req = req.with_catalog(catalog(), "healthcareos");
+    let sel = select_model(&adapters, &req).unwrap();
+    assert_eq!(sel.model.model_id, "whisper-med-v3");
+    assert_eq!(
+        sel.rationale.model_catalog_hash_sha256,
+        Some(catalog().hash_sha256().unwrap())
+    );
+    let rejected: Vec<(&str, &[RejectReason])> = sel
+        .rationale
+        .candidates
+        .iter()
+        .map(|c| (c.model_id.as_str(), c.rejected.as_slice()))
+        .collect();
+    assert_eq!(
+        rejected,
+        [
+            ("leaky-7b", &[RejectReason::MODEL_DENIED_BY_CATALOG][..]),
+            ("whisper-med-v3", &[][..]),
+            ("whisper-tiny", &[RejectReason::MODEL_NOT_IN_CATALOG][..]),
+        ]
+    );
+
+    // An approved model without a weight hash is below the catalog minimum.
+    let unpinned = vec![stt_adapter(&[("whisper-med-v3", false)])];
+    let err = select_model(&unpinned, &req).unwrap_err();
+    assert!(matches!(err, CoreError::PolicyBlocked(_)), "{:?}", err);
+}
+
+#[test]
+fn model_uses_come_from_snapshot_and_audited_events() {
+    let ndjson = [
+        json!({"event_type": "MODEL_SELECTION_RESOLVED", "details": {
+            "task_type": "STT", "selected_model_id": "whisper-med-v3",
+            "pinning_level": "CRYPTO_PINNED", "adapter_id": "stt_local",
+            "adapter_endpoint": "http://127.0.0.1:11435"}}),
+        json!({"event_type": "MODEL_CALL_STARTED", "details": {
+            "call_id": "c_1", "task_type": "LLM", "meta": {"model_id": "llama3-8b"}}}),
+        json!({"event_type": "MODEL_CALL_STARTED", "details": {
+            "call_id": "c_2", "task_type": "LLM", "meta": {"model_id": "llama3-8b"}}}),
+        json!({"event_type": "RUN_CREATED", "details": {}}),
+    ]
+    .iter()
+    .map(|v| v.to_string())
+    .collect::<Vec<_>>()
+    .join("\n");
+    let uses = collect_model_uses(None, &ndjson).unwrap();
+    let ids: Vec<(&str, &str)> = uses
+        .iter()
+        .map(|u| (u.source.as_str(), u.model_id.as_str()))
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            ("MODEL_SELECTION_RESOLVED", "whisper-med-v3"),
+            ("MODEL_CALL_STARTED", "llama3-8b"),
+        ]
+    );
+    assert_eq!(uses[1].task_type, Some(TaskType::LLM));
+    assert_eq!(uses[1].pinning_level, None);
+}
+
+#[test]
+fn export_is_blocked_and_gate_fails_when_bundle_uses_uncatalogued_model() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut inputs = common::evidenceos_inputs(dir.path()).unwrap();
+    let run_id = inputs.run_manifest.run_id.clone();
+    let req = common::strict_export_request(&run_id, "v_0001");
+    let mut restricted = catalog();
+    restricted.packs[0].pack_id = "evidenceos".to_string();
+    inputs.policy_snapshot.model_catalog = Some(restricted.snapshot().unwrap());
+
+    let preview = RunManager::new(AuditLog::open_or_create(&dir.path().join("p.ndjson")).unwrap())
+        .preview_export(&req, &inputs, dir.path())
+        .unwrap();
+    let gate = preview
+        .gate_results
+        .iter()
+        .find(|g| g.gate_id == GATE)
+        .unwrap();
+    assert_eq!(gate.result, GateStatus::FAIL);
+    assert!(gate.message.contains("model-a MODEL_NOT_IN_CATALOG"));
+
+    let mut mgr = RunManager::new(AuditLog::open_or_create(&dir.path().join("a.ndjson")).unwrap());
+    let outcome = mgr
+        .export_run(
+            &req,
+            &inputs,
+            &dir.path().join("bundle"),
+            &dir.path().join("bundle.zip"),
+        )
+        .unwrap();
+    assert_eq!(outcome.status, ExportStatus::BLOCKED);
+    assert_eq!(
+        outcome.block_reason,
+        Some(ExportBlockReason::MODEL_CATALOG_VIOLATION)
+    );
+    // Blocked once, by the export gate; the reporting gate adds no blocker failure.
+    assert_eq!(
+        outcome.block_reasons,
+        [ExportBlock {
+            reason: ExportBlockReason::MODEL_CATALOG_VIOLATION,
+            inputs: json!({
+                "policy_mode": "STRICT",
+                "model_catalog_violations": [{
+                    "model_use": {
+                        "source": "model_snapshot",
+                        "model_id": "model-a",
+                        "task_type": null,
+                        "pinning_level": "CRYPTO_PINNED"
+                    },
+                    "reason": "MODEL_NOT_IN_CATALOG"
+                }]
+            }),
+        }]
+    );
+
+    // Approving the fixture's model lets the same bundle through.
+    restricted.packs[0].models[0].model_id = "model-a".to_string();
+    restricted.packs[0].models[0].task_types = vec![TaskType::LLM];
+    inputs.policy_snapshot.model_catalog = Some(restricted.snapshot().unwrap());
+    let preview = mgr.preview_export(&req, &inputs, dir.path()).unwrap();
+    let gate = preview
+        .gate_results
+        .iter()
+        .find(|g| g.gate_id == GATE)
+        .unwrap();
+    assert_eq!(gate.result, GateStatus::PASS, "{}", gate.message);
+    assert!(
+        preview.block_reasons.is_empty(),
+        "{:?}",
+        preview.block_reasons
+    );
+
+    // A catalog edited after hashing no longer counts.
+    let mut tampered = restricted.snapshot().unwrap();
+    tampered.catalog.denied_model_ids.push("other".to_string());
+    inputs.policy_snapshot.model_catalog = Some(tampered);
+    let preview = mgr.preview_export(&req, &inputs, dir.path()).unwrap();
+    let gate = preview
+        .gate_results
+        .iter()
+        .find(|g| g.gate_id == GATE)
+        .unwrap();
+    assert_eq!(gate.result, GateStatus::FAIL);
+    assert!(gate.message.contains("hash mismatch"));
+    assert_eq!(
+        preview.block_reasons.len(),
+        1,
+        "{:?}",
+        preview.block_reasons
+    );
+    assert_eq!(
+        preview.block_reasons[0].reason,
+        ExportBlockReason::MODEL_CATALOG_VIOLATION
+    );
+    assert!(preview.block_reasons[0].inputs["model_catalog_error"]
+        .as_str()
+        .unwrap()
+        .contains("hash mismatch"));
+}
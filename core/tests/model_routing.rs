@@ -0,0 +1,215 @@
+mod common;
+
+use aigc_core::adapters::http::HttpAdapterClient;
+use aigc_core::adapters::interface::{AdapterModel, ResolveModelRequest};
+use aigc_core::adapters::pinning::PinningLevel;
+use aigc_core::adapters::routing::{
+    route_model, select_model, ModelConstraints, RejectReason, RoutingRequest, TaskType,
+};
+use aigc_core::adapters::runtime::{AdapterRuntime, RegisteredAdapter};
+use aigc_core::adapters::verification::NoDigestSources;
+use aigc_core::audit::log::AuditLog;
+use aigc_core::error::CoreError;
+use aigc_core::policy::types::PolicyMode;
+use common::http_stub::{StubResponse, StubServer};
+use serde_json::json;
+
+fn model(id: &str, sha: bool, quant: Option<&str>, ctx: u64) -> AdapterModel {
+    AdapterModel {
+        model_id: id.to_string(),
+        model_sha256: sha.then(|| "ab".repeat(32)),
+        quantization: quant.map(str::to_string),
+        context_window: Some(ctx),
+        notes: None,
+    }
+}
+
+fn adapter(
+    id: &str,
+    adapter_type: &str,
+    version: &str,
+    models: Vec<AdapterModel>,
+) -> RegisteredAdapter {
+    RegisteredAdapter {
+        adapter_id: id.to_string(),
+        endpoint: "http://127.0.0.1:11434".to_string(),
+        adapter_type: adapter_type.to_string(),
+        adapter_version: version.to_string(),
+        features: vec![],
+        models,
+    }
+}
+
+fn request(
+    policy_mode: PolicyMode,
+    preferred: Option<&str>,
+    constraints: serde_json::Value,
+) -> RoutingRequest {
+    RoutingRequest {
+        task_type: TaskType::LLM,
+        policy_mode,
+        preferred_model: preferred.map(str::to_string),
+        constraints: ModelConstraints::from_value(&constraints).unwrap(),
+        catalog: None,
+    }
+}
+
+#[test]
+fn routing_filters_by_constraints_and_ranks_candidates() {
+    let adapters = vec![
+        adapter(
+            "llm_a",
+            "LLM",
+            "0.3.1",
+            vec![
+                model("llama3-8b", false, Some("Q4_K_M"), 8192),
+                model("llama3-70b", true, Some("Q4_K_M"), 8192),
+            ],
+        ),
+        adapter(
+            "llm_b",
+            "LLM",
+            "1.0.0",
+            vec![model("phi3-mini", true, Some("Q8_0"), 4096)],
+        ),
+        adapter(
+            "emb_a",
+            "EMB",
+            "1.0.0",
+            vec![model("bge-small", true, None, 512)],
+        ),
+    ];
+
+    // No preference: the crypto-pinned model with the larger context window wins.
+    let sel = select_model(&adapters, &request(PolicyMode::STRICT, None, json!({}))).unwrap();
+    assert_eq!(sel.model.model_id, "llama3-70b");
+    assert_eq!(sel.pinning_level, PinningLevel::CRYPTO_PINNED);
+    assert_eq!(sel.rationale.candidates.len(), 3);
+
+    // A preferred model wins over pinning strength when it is eligible.
+    let sel = select_model(
+        &adapters,
+        &request(
+            PolicyMode::STRICT,
+            Some("llama3-8b"),
+            json!({"min_context_window": 4096}),
+        ),
+    )
+    .unwrap();
+    assert_eq!(sel.model.model_id, "llama3-8b");
+    assert_eq!(sel.pinning_level, PinningLevel::VERSION_PINNED);
+    assert!(sel.rationale.preferred_model_selected);
+
+    let sel = select_model(
+        &adapters,
+        &request(
+            PolicyMode::STRICT,
+            Some("llama3-8b"),
+            json!({"allowed_quantizations": ["Q8_0"], "require_model_sha256": true}),
+        ),
+    )
+    .unwrap();
+    assert_eq!(sel.adapter.adapter_id, "llm_b");
+    assert!(!sel.rationale.preferred_model_selected);
+    let rejected = &sel.rationale.candidates[1];
+    assert_eq!(rejected.model_id, "llama3-8b");
+    assert_eq!(
+        rejected.rejected,
+        [
+            RejectReason::QUANTIZATION_NOT_ALLOWED,
+            RejectReason::MODEL_SHA256_MISSING
+        ]
+    );
+
+    let err = select_model(
+        &adapters,
+        &request(
+            PolicyMode::STRICT,
+            None,
+            json!({"min_context_window": 32768}),
+        ),
+    )
+    .unwrap_err();
+    assert!(matches!(err, CoreError::InvalidInput(_)));
+    assert!(ModelConstraints::from_value(&json!({"min_context": 1})).is_err());
+}
+
+#[test]
+fn routing_refuses_models_pinned_below_policy() {
+    // No adapter_version recorded: the model can only be NAME_ONLY pinned.
+    let adapters = vec![adapter(
+        "llm_a",
+        "LLM",
+        "",
+        vec![model("llama3-8b", false, None, 8192)],
+    )];
+
+    let err = select_model(&adapters, &request(PolicyMode::BALANCED, None, json!({}))).unwrap_err();
+    assert!(matches!(err, CoreError::PolicyBlocked(_)), "{:?}", err);
+    let sel = select_model(&adapters, &request(PolicyMode::DRAFT_ONLY, None, json!({}))).unwrap();
+    assert_eq!(sel.pinning_level, PinningLevel::NAME_ONLY);
+    assert!(PinningLevel::NAME_ONLY.satisfies(PolicyMode::DRAFT_ONLY));
+    assert!(!PinningLevel::NAME_ONLY.satisfies(PolicyMode::STRICT));
+}
+
+#[test]
+fn route_model_records_selection_with_rationale() {
+    let server = StubServer::start(|req| match req.path.as_str() {
+        "/v1/health" => StubResponse::json(
+            200,
+            json!({"status": "ok", "adapter_id": "llm_local", "adapter_version": "0.3.1", "uptime_ms": 1}),
+        ),
+        _ => StubResponse::json(
+            200,
+            json!({
+                "adapter_type": "LLM",
+                "features": [],
+                "limits": {},
+                "models": [{"model_id": "llama3-8b", "model_sha256": "cd".repeat(32), "context_window": 8192}]
+            }),
+        ),
+    });
+    let runtime = AdapterRuntime::new(vec![HttpAdapterClient::new(&server.endpoint).unwrap()]);
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+
+    let req = RoutingRequest::from_resolve_request(
+        TaskType::LLM,
+        PolicyMode::STRICT,
+        &ResolveModelRequest {
+            preferred_model: "llama3-8b".to_string(),
+            constraints: json!({"min_context_window": 4096}),
+        },
+    )
+    .unwrap();
+    let sel = route_model(
+        &runtime,
+        &req,
+        &NoDigestSources,
+        &mut audit,
+        "r_route",
+        "v_0001",
+    )
+    .unwrap();
+    // The adapter lists a model_sha256 but nothing verified it.
+    assert_eq!(sel.snapshot().pinning_level, PinningLevel::VERSION_PINNED);
+
+    let line = std::fs::read_to_string(&audit_path).unwrap();
+    let ev: serde_json::Value = serde_json::from_str(line.lines().last().unwrap()).unwrap();
+    assert_eq!(ev["event_type"], "MODEL_SELECTION_RESOLVED");
+    let d = &ev["details"];
+    assert_eq!(d["task_type"], "LLM");
+    assert_eq!(d["selected_model_id"], "llama3-8b");
+    assert_eq!(d["pinning_level"], "VERSION_PINNED");
+    assert_eq!(d["adapter_id"], "llm_local");
+    assert_eq!(d["adapter_endpoint"], server.endpoint.as_str());
+    let rationale = &d["meta"]["rationale"];
+    assert_eq!(rationale["min_pinning_level"], "VERSION_PINNED");
+    assert_eq!(rationale["constraints"]["min_context_window"], 4096);
+    assert_eq!(rationale["candidates"][0]["rejected"], json!([]));
+    assert_eq!(
+        d["meta"]["pinning_verifications"][0]["outcome"],
+        "UNVERIFIABLE"
+    );
+}
@@ -0,0 +1,306 @@
+mod common;
+
+use aigc_core::adapters::http::HttpAdapterClient;
+use aigc_core::adapters::interface::ResolveModelRequest;
+use aigc_core::adapters::pinning::{ModelSnapshot, PinningLevel};
+use aigc_core::adapters::routing::{route_model, RoutingRequest, TaskType};
+use aigc_core::adapters::runtime::AdapterRuntime;
+use aigc_core::adapters::verification::{
+    hash_model_files, sign_model_digest, verify_model_pinning, DigestMethod, DigestSource,
+    LocalWeights, NoDigestSources, SignedAdapterDigests, VerificationOutcome,
+};
+use aigc_core::audit::log::AuditLog;
+use aigc_core::determinism::run_id::sha256_hex;
+use aigc_core::policy::types::PolicyMode;
+use common::http_stub::{StubResponse, StubServer};
+use serde_json::json;
+
+const KEY: &[u8] = b"adapter-provisioned-key";
+
+fn snapshot(model_sha256: &str) -> ModelSnapshot {
+    ModelSnapshot {
+        adapter_id: "llm_local".to_string(),
+        adapter_version: "0.3.1".to_string(),
+        adapter_endpoint: "http://127.0.0.1:11434".to_string(),
+        model_id: "llama3-8b".to_string(),
+        model_sha256: Some(model_sha256.to_string()),
+        pinning_level: PinningLevel::CRYPTO_PINNED,
+    }
+}
+
+fn events(path: &std::path::Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+#[test]
+fn weight_directories_hash_as_a_sorted_listing() {
+    let dir = tempfile::tempdir().unwrap();
+    let weights = dir.path().join("llama3-8b");
+    std::fs::create_dir_all(weights.join("shards")).unwrap();
+    std::fs::write(weights.join("shards/b.bin"), b"bbbb").unwrap();
+    std::fs::write(weights.join("config.json"), b"{}").unwrap();
+
+    let listing = format!(
+        "{}  config.json\n{}  shards/b.bin\n",
+        sha256_hex(b"{}"),
+        sha256_hex(b"bbbb")
+    );
+    assert_eq!(
+        hash_model_files(&weights).unwrap(),
+        sha256_hex(listing.as_bytes())
+    );
+    assert_eq!(
+        hash_model_files(&weights.join("config.json")).unwrap(),
+        sha256_hex(b"{}")
+    );
+    std::fs::create_dir_all(dir.path().join("empty")).unwrap();
+    assert!(hash_model_files(&dir.path().join("empty")).is_err());
+}
+
+// One LLM adapter listing `llama3-8b` with `model_sha256`, signing digests of `loaded`.
+fn adapter_stub(model_sha256: String, loaded: String) -> StubServer {
+    StubServer::start(move |req| match req.path.as_str() {
+        "/v1/health" => StubResponse::json(
+            200,
+            json!({"status": "ok", "adapter_id": "llm_local", "adapter_version": "0.3.1", "uptime_ms": 1}),
+        ),
+        "/v1/models/digest" => {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+            let model_id = body["model_id"].as_str().unwrap();
+            let nonce = body["nonce"].as_str().unwrap();
+            StubResponse::json(
+                200,
+                json!({
+                    "model_id": model_id,
+                    "model_sha256": loaded,
+                    "nonce": nonce,
+                    "signature": sign_model_digest(KEY, model_id, &loaded, nonce)
+                }),
+            )
+        }
+        _ => StubResponse::json(
+            200,
+            json!({
+                "adapter_type": "LLM",
+                "features": [],
+                "limits": {},
+                "models": [{"model_id": "llama3-8b", "model_sha256": model_sha256}]
+            }),
+        ),
+    })
+}
+
+fn llm_request() -> RoutingRequest {
+    RoutingRequest::from_resolve_request(
+        TaskType::LLM,
+        PolicyMode::STRICT,
+        &ResolveModelRequest {
+            preferred_model: String::new(),
+            constraints: json!(null),
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn routing_keeps_crypto_pinning_only_when_local_weights_match() {
+    let dir = tempfile::tempdir().unwrap();
+    let weights = dir.path().join("model.gguf");
+    std::fs::write(&weights, b"weights v1").unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let local = LocalWeights {
+        paths: [("llama3-8b".to_string(), weights.clone())].into(),
+    };
+
+    let good = adapter_stub(sha256_hex(b"weights v1"), String::new());
+    let runtime = AdapterRuntime::new(vec![HttpAdapterClient::new(&good.endpoint).unwrap()]);
+    let sel = route_model(
+        &runtime,
+        &llm_request(),
+        &local,
+        &mut audit,
+        "r_pin",
+        "v_0001",
+    )
+    .unwrap();
+    assert_eq!(sel.pinning_level, PinningLevel::CRYPTO_PINNED);
+    assert!(sel.snapshot().model_sha256.is_some());
+
+    // The weights on disk are not the ones the adapter claims to serve.
+    let stale = adapter_stub(sha256_hex(b"weights v0"), String::new());
+    let runtime = AdapterRuntime::new(vec![HttpAdapterClient::new(&stale.endpoint).unwrap()]);
+    let sel = route_model(
+        &runtime,
+        &llm_request(),
+        &local,
+        &mut audit,
+        "r_pin",
+        "v_0001",
+    )
+    .unwrap();
+    assert_eq!(sel.pinning_level, PinningLevel::VERSION_PINNED);
+    assert_eq!(sel.snapshot().model_sha256, None);
+
+    // One selection event per routing, with the checks under its meta.
+    let evs = events(&audit_path);
+    assert_eq!(evs.len(), 2);
+    assert!(evs
+        .iter()
+        .all(|e| e["event_type"] == "MODEL_SELECTION_RESOLVED"));
+    assert_eq!(evs[0]["details"]["pinning_level"], "CRYPTO_PINNED");
+    assert_eq!(
+        evs[0]["details"]["meta"]["pinning_verifications"][0]["outcome"],
+        "VERIFIED"
+    );
+    let mismatch = &evs[1]["details"];
+    assert_eq!(mismatch["pinning_level"], "VERSION_PINNED");
+    assert_eq!(mismatch["meta"]["model_sha256"], json!(null));
+    let checks = mismatch["meta"]["pinning_verifications"]
+        .as_array()
+        .unwrap();
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0]["outcome"], "MISMATCH");
+    assert_eq!(
+        checks[0]["observed_sha256"],
+        sha256_hex(b"weights v1").as_str()
+    );
+    assert_eq!(checks[0]["claimed_level"], "CRYPTO_PINNED");
+    assert_eq!(
+        mismatch["meta"]["rationale"]["candidates"][0]["pinning_level"],
+        "VERSION_PINNED"
+    );
+
+    // Missing weights cannot prove anything either.
+    let missing = dir.path().join("gone.gguf");
+    let v = verify_model_pinning(
+        &snapshot(&sha256_hex(b"weights v1")),
+        Some(&DigestSource::LocalFiles(&missing)),
+    )
+    .unwrap();
+    assert_eq!(v.outcome, VerificationOutcome::UNVERIFIABLE);
+    assert_eq!(v.effective_level, PinningLevel::VERSION_PINNED);
+}
+
+#[test]
+fn failed_reselection_still_records_pinning_verifications() {
+    let dir = tempfile::tempdir().unwrap();
+    let weights = dir.path().join("model.gguf");
+    std::fs::write(&weights, b"weights v1").unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let mut audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let local = LocalWeights {
+        paths: [("llama3-8b".to_string(), weights)].into(),
+    };
+    // Only a hashed model is acceptable, and the only hash on offer does not match.
+    let req = RoutingRequest::from_resolve_request(
+        TaskType::LLM,
+        PolicyMode::STRICT,
+        &ResolveModelRequest {
+            preferred_model: String::new(),
+            constraints: json!({"require_model_sha256": true}),
+        },
+    )
+    .unwrap();
+    let stale = adapter_stub(sha256_hex(b"weights v0"), String::new());
+    let runtime = AdapterRuntime::new(vec![HttpAdapterClient::new(&stale.endpoint).unwrap()]);
+
+    assert!(route_model(&runtime, &req, &local, &mut audit, "r_pin", "v_0001").is_err());
+    let evs = events(&audit_path);
+    assert_eq!(evs.len(), 1);
+    assert_eq!(evs[0]["event_type"], "MODEL_SELECTION_FAILED");
+    let d = &evs[0]["details"];
+    assert_eq!(d["task_type"], "LLM");
+    assert_eq!(d["policy_mode"], "STRICT");
+    assert!(d["reason"]
+        .as_str()
+        .unwrap()
+        .contains("MODEL_SHA256_MISSING"));
+    let checks = d["meta"]["pinning_verifications"].as_array().unwrap();
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0]["outcome"], "MISMATCH");
+}
+
+#[test]
+fn routing_without_a_digest_source_does_not_claim_crypto_pinning() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut audit = AuditLog::open_or_create(&dir.path().join("audit.ndjson")).unwrap();
+    let server = adapter_stub("cd".repeat(32), "cd".repeat(32));
+    let runtime = AdapterRuntime::new(vec![HttpAdapterClient::new(&server.endpoint).unwrap()]);
+
+    let sel = route_model(
+        &runtime,
+        &llm_request(),
+        &NoDigestSources,
+        &mut audit,
+        "r_pin",
+        "v_0001",
+    )
+    .unwrap();
+    assert_eq!(sel.pinning_level, PinningLevel::VERSION_PINNED);
+
+    let signed = SignedAdapterDigests {
+        runtime: &runtime,
+        key: KEY.to_vec(),
+    };
+    let sel = route_model(
+        &runtime,
+        &llm_request(),
+        &signed,
+        &mut audit,
+        "r_pin",
+        "v_0001",
+    )
+    .unwrap();
+    assert_eq!(sel.pinning_level, PinningLevel::CRYPTO_PINNED);
+    assert!(server
+        .requests()
+        .iter()
+        .any(|r| r.path == "/v1/models/digest"));
+}
+
+#[test]
+fn adapter_signed_digests_are_checked_against_the_challenge() {
+    let loaded = "cd".repeat(32);
+    let server = StubServer::start(move |req| {
+        let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+        let model_id = body["model_id"].as_str().unwrap();
+        let nonce = body["nonce"].as_str().unwrap();
+        StubResponse::json(
+            200,
+            json!({
+                "model_id": model_id,
+                "model_sha256": loaded,
+                "nonce": nonce,
+                "signature": sign_model_digest(KEY, model_id, &loaded, nonce)
+            }),
+        )
+    });
+    let client = HttpAdapterClient::new(&server.endpoint).unwrap();
+    let signed = |key: &'static [u8]| DigestSource::Adapter {
+        client: &client,
+        key,
+    };
+
+    let v = verify_model_pinning(&snapshot(&"cd".repeat(32)), Some(&signed(KEY))).unwrap();
+    assert_eq!(v.outcome, VerificationOutcome::VERIFIED);
+    assert_eq!(v.method, DigestMethod::ADAPTER_SIGNED_DIGEST);
+    assert_eq!(server.requests()[0].path, "/v1/models/digest");
+
+    let v = verify_model_pinning(&snapshot(&"ab".repeat(32)), Some(&signed(KEY))).unwrap();
+    assert_eq!(v.outcome, VerificationOutcome::MISMATCH);
+
+    // A digest signed with another key is not trusted, whatever it says.
+    let v = verify_model_pinning(&snapshot(&"cd".repeat(32)), Some(&signed(b"other-key"))).unwrap();
+    assert_eq!(v.outcome, VerificationOutcome::UNVERIFIABLE);
+    assert!(v.detail.contains("signature"));
+    assert_eq!(v.effective_level, PinningLevel::VERSION_PINNED);
+
+    let mut unpinned = snapshot("");
+    unpinned.model_sha256 = None;
+    assert!(verify_model_pinning(&unpinned, Some(&signed(KEY))).is_err());
+}
@@ -1,5 +1,5 @@
 use aigc_core::audit::log::AuditLog;
-use aigc_core::run::manager::{RunManager, RunState};
+use aigc_core::run::manager::{CreateRunRequest, IngestArtifactRequest, RunManager, RunState};
 
 #[test]
 fn run_manager_starts_ready() {
@@ -8,3 +8,86 @@ fn run_manager_starts_ready() {
     let mgr = RunManager::new(audit);
     assert_eq!(mgr.state, RunState::READY);
 }
+
+#[test]
+fn run_lifecycle_drives_created_through_executing_and_cancel() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let mut mgr = RunManager::create_run(audit, &create_req()).unwrap();
+    assert_eq!(mgr.state, RunState::CREATED);
+
+    mgr.ingest_artifact(&IngestArtifactRequest {
+        run_id: "r_1".to_string(),
+        vault_id: "v_1".to_string(),
+        source_type: "FILE_PICKER".to_string(),
+        source_ref: "picker:1".to_string(),
+        artifact_id: "a_1".to_string(),
+        artifact_sha256: "a".repeat(64),
+        content_type: "text/plain".to_string(),
+        size_bytes: 5,
+        origin_path: "notes.txt".to_string(),
+        ingest_transformations: vec!["NORMALIZE_NEWLINES".to_string()],
+    })
+    .unwrap();
+    assert_eq!(mgr.state, RunState::INGESTING);
+    mgr.complete_ingest("r_1", "v_1").unwrap();
+    assert_eq!(mgr.state, RunState::READY);
+    mgr.start_execution("r_1", "v_1").unwrap();
+    assert_eq!(mgr.state, RunState::EXECUTING);
+    mgr.cancel("r_1", "v_1", "user cancelled").unwrap();
+    assert_eq!(mgr.state, RunState::CANCELLED);
+    assert!(mgr.start_execution("r_1", "v_1").is_err());
+
+    let event_types = read_event_types(&audit_path);
+    assert_eq!(
+        event_types,
+        vec![
+            "RUN_CREATED",
+            "RUN_STATE_CHANGED",
+            "ARTIFACT_INGEST_STARTED",
+            "ARTIFACT_INGESTED",
+            "ARTIFACT_INGEST_COMPLETED",
+            "RUN_STATE_CHANGED",
+            "RUN_STATE_CHANGED",
+            "RUN_STATE_CHANGED",
+            "RUN_CANCELLED",
+        ]
+    );
+}
+
+#[test]
+fn run_failure_is_terminal_and_audited() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let audit = AuditLog::open_or_create(&audit_path).unwrap();
+    let mut mgr = RunManager::create_run(audit, &create_req()).unwrap();
+    mgr.complete_ingest("r_1", "v_1").unwrap();
+    mgr.fail("r_1", "v_1", "adapter unavailable").unwrap();
+    assert_eq!(mgr.state, RunState::FAILED);
+    assert!(mgr.cancel("r_1", "v_1", "too late").is_err());
+    assert_eq!(read_event_types(&audit_path).last().unwrap(), "RUN_FAILED");
+}
+
+fn create_req() -> CreateRunRequest {
+    CreateRunRequest {
+        run_id: "r_1".to_string(),
+        vault_id: "v_1".to_string(),
+        pack_id: "evidenceos".to_string(),
+        pack_version: "1.0.0".to_string(),
+        policy_pack_id: "default".to_string(),
+        policy_pack_version: "1".to_string(),
+        determinism_enabled: true,
+    }
+}
+
+fn read_event_types(path: &std::path::Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|l| {
+            let v: serde_json::Value = serde_json::from_str(l).unwrap();
+            v["event_type"].as_str().unwrap().to_string()
+        })
+        .collect()
+}
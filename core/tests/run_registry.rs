@@ -0,0 +1,157 @@
+use aigc_core::audit::log::AuditLog;
+use aigc_core::determinism::clock::clock_for_profile;
+use aigc_core::policy::types::{NetworkMode, PolicyMode};
+use aigc_core::run::manager::{CreateRunRequest, RunManager, RunState};
+use aigc_core::run::registry::{RunRecord, RunRegistry};
+use aigc_core::storage::crypto::EncryptionAlgorithm;
+use aigc_core::storage::vault::{VaultConfig, VaultStorage};
+
+#[test]
+fn registered_run_resumes_from_last_state_after_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault_root = dir.path().join("vault");
+    let audit_path = dir.path().join("audit.ndjson");
+    let vault = VaultStorage::create(
+        &vault_root,
+        VaultConfig {
+            vault_id: "v_reg".to_string(),
+            encryption_algorithm: EncryptionAlgorithm::XCHACHA20_POLY1305,
+            encryption_at_rest: true,
+        },
+    )
+    .unwrap();
+
+    {
+        let audit = AuditLog::open_or_create(&audit_path)
+            .unwrap()
+            .with_clock(clock_for_profile(true));
+        let mut mgr = RunManager::create_run(
+            audit,
+            &CreateRunRequest {
+                run_id: "r_reg".to_string(),
+                vault_id: "v_reg".to_string(),
+                pack_id: "evidenceos".to_string(),
+                pack_version: "1.0.0".to_string(),
+                policy_pack_id: "default".to_string(),
+                policy_pack_version: "1".to_string(),
+                determinism_enabled: true,
+            },
+        )
+        .unwrap();
+        mgr.attach_registry(
+            RunRegistry::open(vault).unwrap(),
+            RunRecord {
+                run_id: "r_reg".to_string(),
+                vault_id: "v_reg".to_string(),
+                pack_id: "evidenceos".to_string(),
+                pack_version: "1.0.0".to_string(),
+                state: RunState::CREATED,
+                policy_mode: PolicyMode::STRICT,
+                network_mode: NetworkMode::OFFLINE,
+                determinism_enabled: true,
+                audit_log_path: audit_path.to_string_lossy().to_string(),
+                artifact_count: 0,
+                bundle_dir: None,
+                bundle_zip: None,
+            },
+        )
+        .unwrap();
+        mgr.complete_ingest("r_reg", "v_reg").unwrap();
+    }
+
+    let registry = RunRegistry::open(VaultStorage::open(&vault_root).unwrap()).unwrap();
+    assert_eq!(registry.list().len(), 1);
+    assert_eq!(registry.get("r_reg").unwrap().state, RunState::READY);
+
+    let lines_before = std::fs::read_to_string(&audit_path)
+        .unwrap()
+        .lines()
+        .count();
+    let mut mgr = RunManager::resume(registry, "r_reg").unwrap();
+    assert_eq!(mgr.state, RunState::READY);
+    mgr.start_execution("r_reg", "v_reg").unwrap();
+    assert_eq!(
+        mgr.registry().unwrap().get("r_reg").unwrap().state,
+        RunState::EXECUTING
+    );
+
+    // The resumed run keeps appending to the same hash chain.
+    let text = std::fs::read_to_string(&audit_path).unwrap();
+    let lines: Vec<serde_json::Value> = text
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), lines_before + 1);
+    assert_eq!(
+        lines[lines_before]["prev_event_hash"],
+        lines[lines_before - 1]["event_hash"]
+    );
+    // The deterministic clock picks up after the last recorded tick rather than the epoch.
+    assert_eq!(
+        lines[lines_before - 1]["ts_utc"],
+        format!("2026-02-10T00:00:{:02}Z", lines_before - 1)
+    );
+    assert_eq!(
+        lines[lines_before]["ts_utc"],
+        format!("2026-02-10T00:00:{:02}Z", lines_before)
+    );
+}
+
+#[test]
+fn resume_rejects_unknown_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = VaultStorage::create(
+        dir.path(),
+        VaultConfig {
+            vault_id: "v_empty".to_string(),
+            encryption_algorithm: EncryptionAlgorithm::AES_256_GCM,
+            encryption_at_rest: true,
+        },
+    )
+    .unwrap();
+    let registry = RunRegistry::open(vault).unwrap();
+    assert!(RunManager::resume(registry, "r_missing").is_err());
+}
+
+#[test]
+fn run_ids_must_be_safe_blob_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.ndjson");
+    let vault = VaultStorage::create(
+        &dir.path().join("vault"),
+        VaultConfig {
+            vault_id: "v_ids".to_string(),
+            encryption_algorithm: EncryptionAlgorithm::AES_256_GCM,
+            encryption_at_rest: true,
+        },
+    )
+    .unwrap();
+    for run_id in ["", "../r_escape", "r/1", "r.1"] {
+        let created = RunManager::create_run(
+            AuditLog::open_or_create(&audit_path).unwrap(),
+            &CreateRunRequest {
+                run_id: run_id.to_string(),
+                vault_id: "v_ids".to_string(),
+                pack_id: "evidenceos".to_string(),
+                pack_version: "1.0.0".to_string(),
+                policy_pack_id: "default".to_string(),
+                policy_pack_version: "1".to_string(),
+                determinism_enabled: true,
+            },
+        );
+        assert!(created.is_err(), "{:?}", run_id);
+    }
+    assert_eq!(std::fs::read_to_string(&audit_path).unwrap(), "");
+
+    let registry = RunRegistry::open(vault).unwrap();
+    assert!(RunManager::resume(registry, "../r_escape").is_err());
+    // Blob writes go through a temporary file renamed into place.
+    let vault = VaultStorage::open(&dir.path().join("vault")).unwrap();
+    vault.write_blob("b_atomic", b"payload").unwrap();
+    let names: Vec<String> = std::fs::read_dir(dir.path().join("vault").join("blobs"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(names, ["b_atomic.bin"]);
+    assert_eq!(vault.read_blob("b_atomic").unwrap(), b"payload");
+}
@@ -0,0 +1,246 @@
+mod common;
+
+use aigc_core::error::CoreError;
+use aigc_core::policy::types::PolicyMode;
+use aigc_core::run::manager::{CreateRunRequest, RunState};
+use aigc_core::run::registry::RunRegistry;
+use aigc_core::run::scheduler::{RunJob, RunScheduler, RunStatus, SchedulerSummary};
+use aigc_core::storage::crypto::EncryptionAlgorithm;
+use aigc_core::storage::vault::{VaultConfig, VaultStorage};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn create_request(run_id: &str) -> CreateRunRequest {
+    CreateRunRequest {
+        run_id: run_id.to_string(),
+        vault_id: "v_0001".to_string(),
+        pack_id: "evidenceos".to_string(),
+        pack_version: "1.0.0".to_string(),
+        policy_pack_id: "default".to_string(),
+        policy_pack_version: "1".to_string(),
+        determinism_enabled: true,
+    }
+}
+
+#[test]
+fn scheduler_runs_jobs_in_parallel_within_worker_bound() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixture_dir = dir.path().join("fixture");
+    std::fs::create_dir_all(&fixture_dir).unwrap();
+    let inputs = common::evidenceos_inputs(&fixture_dir).unwrap();
+    let scheduler = RunScheduler::new(dir.path().join("runs"), 2).unwrap();
+
+    let active = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let run_ids = ["r_q1", "r_q2", "r_q3", "r_q4", "r_q5"];
+    for run_id in run_ids {
+        let inputs = inputs.clone();
+        let active = active.clone();
+        let peak = peak.clone();
+        scheduler
+            .submit(RunJob {
+                create: create_request(run_id),
+                export: common::strict_export_request(run_id, "v_0001"),
+                execute: Box::new(move |ws| {
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    assert!(ws.root.ends_with(run_id));
+                    std::thread::sleep(Duration::from_millis(50));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    Ok(inputs)
+                }),
+            })
+            .unwrap();
+    }
+    assert!(scheduler
+        .submit(RunJob {
+            create: create_request("r_q1"),
+            export: common::strict_export_request("r_q1", "v_0001"),
+            execute: Box::new(|_| Err(CoreError::InvalidInput("unused".to_string()))),
+        })
+        .is_err());
+
+    let progress = scheduler.shutdown();
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+    assert_eq!(progress.len(), run_ids.len());
+    for p in &progress {
+        assert_eq!(p.status, RunStatus::COMPLETED, "{:?}", p.error);
+        assert_eq!(p.state, Some(RunState::COMPLETED));
+        // Each run writes only its own audit log.
+        let text = std::fs::read_to_string(&p.audit_log_path).unwrap();
+        assert!(text
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+            .all(|e| e["run_id"] == p.run_id.as_str()));
+        assert!(dir
+            .path()
+            .join("runs")
+            .join(&p.run_id)
+            .join("bundle.zip")
+            .exists());
+    }
+}
+
+#[test]
+fn scheduler_reports_failed_runs_and_queue() {
+    let dir = tempfile::tempdir().unwrap();
+    let scheduler = RunScheduler::new(dir.path(), 1).unwrap();
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+    scheduler
+        .submit(RunJob {
+            create: create_request("r_slow"),
+            export: common::strict_export_request("r_slow", "v_0001"),
+            execute: Box::new(move |_| {
+                release_rx.recv().unwrap();
+                Err(CoreError::InvalidInput("pack inputs missing".to_string()))
+            }),
+        })
+        .unwrap();
+    scheduler
+        .submit(RunJob {
+            create: create_request("r_next"),
+            export: common::strict_export_request("r_next", "v_0001"),
+            execute: Box::new(|_| panic!("pack crashed")),
+        })
+        .unwrap();
+    assert!(scheduler
+        .submit(RunJob {
+            create: create_request("../escape"),
+            export: common::strict_export_request("../escape", "v_0001"),
+            execute: Box::new(|_| Err(CoreError::InvalidInput("unused".to_string()))),
+        })
+        .is_err());
+
+    // Only one worker: the second run waits in the queue until the first finishes.
+    while scheduler.run_progress("r_slow").unwrap().status != RunStatus::RUNNING {
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(scheduler.queued(), vec!["r_next".to_string()]);
+    assert_eq!(
+        scheduler.summary(),
+        SchedulerSummary {
+            queued: 1,
+            running: 1,
+            ..Default::default()
+        }
+    );
+
+    release_tx.send(()).unwrap();
+    scheduler.wait_idle();
+    let slow = scheduler.run_progress("r_slow").unwrap();
+    assert_eq!(slow.status, RunStatus::FAILED);
+    assert_eq!(slow.state, Some(RunState::FAILED));
+    assert!(slow.error.unwrap().contains("pack inputs missing"));
+    assert_eq!(
+        scheduler.run_progress("r_next").unwrap().status,
+        RunStatus::FAILED
+    );
+    assert_eq!(scheduler.summary().failed, 2);
+}
+
+#[test]
+fn scheduler_cancels_queued_and_running_runs() {
+    let dir = tempfile::tempdir().unwrap();
+    let scheduler = RunScheduler::new(dir.path(), 1).unwrap();
+    let (started_tx, started_rx) = std::sync::mpsc::channel::<()>();
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+    let fixture = dir.path().to_path_buf();
+    scheduler
+        .submit(RunJob {
+            create: create_request("r_running"),
+            export: common::strict_export_request("r_running", "v_0001"),
+            execute: Box::new(move |_| {
+                started_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+                common::evidenceos_inputs(&fixture)
+                    .map_err(|e| CoreError::InvalidInput(e.to_string()))
+            }),
+        })
+        .unwrap();
+    scheduler
+        .submit(RunJob {
+            create: create_request("r_waiting"),
+            export: common::strict_export_request("r_waiting", "v_0001"),
+            execute: Box::new(|_| panic!("cancelled runs must not execute")),
+        })
+        .unwrap();
+
+    started_rx.recv().unwrap();
+    assert!(scheduler.cancel("r_waiting"));
+    assert!(scheduler.queued().is_empty());
+    assert!(scheduler.cancel("r_running"));
+    assert!(!scheduler.cancel("r_unknown"));
+    release_tx.send(()).unwrap();
+    scheduler.wait_idle();
+
+    assert_eq!(scheduler.summary().cancelled, 2);
+    let running = scheduler.run_progress("r_running").unwrap();
+    assert_eq!(running.state, Some(RunState::CANCELLED));
+    let text = std::fs::read_to_string(&running.audit_log_path).unwrap();
+    let last: serde_json::Value = serde_json::from_str(text.lines().last().unwrap()).unwrap();
+    assert_eq!(last["event_type"], "RUN_CANCELLED");
+    assert!(!scheduler.cancel("r_running"));
+}
+
+#[test]
+fn scheduled_runs_are_created_and_registered_like_manual_runs() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault_root = dir.path().join("vault");
+    VaultStorage::create(
+        &vault_root,
+        VaultConfig {
+            vault_id: "v_0001".to_string(),
+            encryption_algorithm: EncryptionAlgorithm::XCHACHA20_POLY1305,
+            encryption_at_rest: true,
+        },
+    )
+    .unwrap();
+    let fixture_dir = dir.path().join("fixture");
+    std::fs::create_dir_all(&fixture_dir).unwrap();
+    let inputs = common::evidenceos_inputs(&fixture_dir).unwrap();
+    let scheduler = RunScheduler::with_registry(dir.path().join("runs"), 2, &vault_root).unwrap();
+
+    let run_ids = ["r_reg1", "r_reg2", "r_reg3"];
+    for run_id in run_ids {
+        let inputs = inputs.clone();
+        scheduler
+            .submit(RunJob {
+                create: create_request(run_id),
+                export: common::strict_export_request(run_id, "v_0001"),
+                execute: Box::new(move |_| Ok(inputs)),
+            })
+            .unwrap();
+    }
+    assert!(scheduler
+        .submit(RunJob {
+            create: create_request("r_other"),
+            export: common::strict_export_request("r_reg4", "v_0001"),
+            execute: Box::new(|_| Err(CoreError::InvalidInput("unused".to_string()))),
+        })
+        .is_err());
+
+    for p in scheduler.shutdown() {
+        assert_eq!(p.status, RunStatus::COMPLETED, "{:?}", p.error);
+        let text = std::fs::read_to_string(&p.audit_log_path).unwrap();
+        let first: serde_json::Value = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+        assert_eq!(first["event_type"], "RUN_CREATED");
+        assert_eq!(first["details"]["pack_id"], "evidenceos");
+    }
+
+    // Concurrent workers share the registry without losing each other's records.
+    let registry = RunRegistry::open(VaultStorage::open(&vault_root).unwrap()).unwrap();
+    let records = registry.list();
+    assert_eq!(
+        records
+            .iter()
+            .map(|r| r.run_id.as_str())
+            .collect::<Vec<_>>(),
+        run_ids
+    );
+    for r in records {
+        assert_eq!(r.state, RunState::COMPLETED);
+        assert_eq!(r.policy_mode, PolicyMode::STRICT);
+        assert!(r.bundle_zip.as_deref().unwrap().ends_with("bundle.zip"));
+    }
+}
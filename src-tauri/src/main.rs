@@ -3,8 +3,10 @@
 use aigc_core::adapters::pinning::{classify_pinning_level, PinningLevel};
 use aigc_core::audit::event::{Actor, AuditEvent};
 use aigc_core::audit::log::AuditLog;
+use aigc_core::determinism::clock::clock_for_profile;
 use aigc_core::determinism::json_canonical;
 use aigc_core::determinism::run_id::sha256_hex;
+use aigc_core::error::CoreError;
 use aigc_core::evidence_bundle::artifact_hashes::{render_artifact_hashes_csv, ArtifactHashRow};
 use aigc_core::evidence_bundle::schemas::*;
 use aigc_core::evidenceos::control_library::{controls_for_capabilities, ControlDefinition};
@@ -19,15 +21,19 @@ use aigc_core::healthcareos::workflow::HealthcareWorkflowState;
 use aigc_core::incidentos::model::IncidentOsInputV1;
 use aigc_core::incidentos::render::output_manifest as incident_output_manifest;
 use aigc_core::incidentos::workflow::IncidentWorkflowState;
+use aigc_core::policy::allowlist::allowlist_hash_sha256;
+use aigc_core::policy::egress::{EgressClient, EgressPolicy};
 use aigc_core::policy::network_snapshot::{AdapterEndpointSnapshot, NetworkSnapshot};
 use aigc_core::policy::types::{InputExportProfile, NetworkMode, PolicyMode, ProofLevel};
 use aigc_core::redlineos::model::RedlineOsInputV1;
 use aigc_core::redlineos::render::output_manifest as redline_output_manifest;
 use aigc_core::redlineos::workflow::{self, RedlineWorkflowState};
-use aigc_core::run::manager::{ExportRequest, RunManager};
+use aigc_core::run::cancel::CancellationToken;
+use aigc_core::run::manager::{ExportRequest, ExportStatus, RunManager};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Serialize)]
 struct PackCommandStatus {
@@ -35,6 +41,26 @@ struct PackCommandStatus {
     message: String,
 }
 
+/// Cancellation token of the export in flight, so the UI Cancel button can abort it.
+#[derive(Default)]
+struct ActiveExport(Mutex<Option<CancellationToken>>);
+
+impl ActiveExport {
+    fn begin(&self) -> CancellationToken {
+        let token = CancellationToken::new();
+        if let Ok(mut active) = self.0.lock() {
+            *active = Some(token.clone());
+        }
+        token
+    }
+
+    fn finish(&self) {
+        if let Ok(mut active) = self.0.lock() {
+            *active = None;
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct UiNetworkSnapshot {
     network_mode: &'static str,
@@ -44,7 +70,7 @@ struct UiNetworkSnapshot {
 
 #[derive(Debug, Serialize)]
 struct EvidenceOsRunResult {
-    status: String,
+    status: ExportStatus,
     bundle_path: String,
     bundle_sha256: String,
     missing_control_ids: Vec<String>,
@@ -74,8 +100,12 @@ fn list_control_library(enabled_capabilities: Option<Vec<String>>) -> Vec<Contro
     controls_for_capabilities(&enabled_capabilities.unwrap_or_default())
 }
 
-#[tauri::command]
-fn generate_evidenceos_bundle(input: EvidenceOsRunInput) -> Result<EvidenceOsRunResult, String> {
+// Long-running exports run off the main thread so cancel_export can be handled meanwhile.
+#[tauri::command(async)]
+fn generate_evidenceos_bundle(
+    input: EvidenceOsRunInput,
+    active: tauri::State<'_, ActiveExport>,
+) -> Result<EvidenceOsRunResult, String> {
     let runtime_dir = make_runtime_dir()?;
     let bundle_root = runtime_dir.join("bundle_root");
     let bundle_zip = runtime_dir.join("evidence_bundle_evidenceos_v1.zip");
@@ -95,7 +125,11 @@ fn generate_evidenceos_bundle(input: EvidenceOsRunInput) -> Result<EvidenceOsRun
     let pack_id = "evidenceos".to_string();
     let pack_version = "1.0.0".to_string();
 
-    let mut audit = AuditLog::open_or_create(&audit_path).map_err(|e| e.to_string())?;
+    let mut audit = AuditLog::open_or_create(&audit_path)
+        .map_err(|e| e.to_string())?
+        .with_clock(clock_for_profile(true));
+    // No rules are allowlisted; the bundle records the hash of the empty allowlist.
+    let allowlist_hash = allowlist_hash_sha256(&[]).map_err(|e| e.to_string())?;
     let events = vec![
         (
             "VAULT_ENCRYPTION_STATUS",
@@ -119,24 +153,15 @@ fn generate_evidenceos_bundle(input: EvidenceOsRunInput) -> Result<EvidenceOsRun
             "ALLOWLIST_UPDATED",
             Actor::System,
             json!({
-                "allowlist_hash_sha256": sha256_hex(b""),
+                "allowlist_hash_sha256": allowlist_hash,
                 "allowlist_count":0
             }),
         ),
-        (
-            "EGRESS_REQUEST_BLOCKED",
-            Actor::System,
-            json!({
-                "destination":{"scheme":"https","host":"example.invalid","port":443,"path":"/"},
-                "block_reason":"OFFLINE_MODE",
-                "request_hash_sha256": sha256_hex(b"blocked")
-            }),
-        ),
     ];
     for (event_type, actor, details) in events {
         audit
             .append(AuditEvent {
-                ts_utc: "2026-02-10T00:00:00Z".to_string(),
+                ts_utc: String::new(),
                 event_type: event_type.to_string(),
                 run_id: run_id.clone(),
                 vault_id: vault_id.clone(),
@@ -147,6 +172,28 @@ fn generate_evidenceos_bundle(input: EvidenceOsRunInput) -> Result<EvidenceOsRun
             })
             .map_err(|e| e.to_string())?;
     }
+    // The offline policy must refuse this; the refusal is what the bundle proves.
+    let mut egress = EgressClient::new(
+        EgressPolicy {
+            network_mode: NetworkMode::OFFLINE,
+            proof_level: ProofLevel::OFFLINE_STRICT,
+            allowlist: vec![],
+        },
+        &mut audit,
+        &run_id,
+        &vault_id,
+    );
+    match egress.send(
+        "POST",
+        "http://example.invalid/",
+        &[],
+        b"blocked",
+        Duration::from_secs(5),
+    ) {
+        Err(CoreError::PolicyBlocked(_)) => {}
+        Err(e) => return Err(e.to_string()),
+        Ok(_) => return Err("offline egress probe was not blocked".to_string()),
+    }
 
     let tags = csv_to_vec(&input.artifact_tags_csv);
     let control_families = csv_to_vec(&input.control_families_csv);
@@ -332,6 +379,7 @@ fn generate_evidenceos_bundle(input: EvidenceOsRunInput) -> Result<EvidenceOsRun
             },
             encryption_at_rest: true,
             encryption_algorithm: "XCHACHA20_POLY1305".to_string(),
+            model_catalog: None,
         },
         network_snapshot: NetworkSnapshot {
             network_mode: NetworkMode::OFFLINE,
@@ -365,7 +413,7 @@ fn generate_evidenceos_bundle(input: EvidenceOsRunInput) -> Result<EvidenceOsRun
         },
     };
 
-    let mut manager = RunManager::new(audit);
+    let mut manager = RunManager::new(audit).with_cancellation_token(active.begin());
     let export_req = ExportRequest {
         run_id,
         vault_id,
@@ -376,13 +424,18 @@ fn generate_evidenceos_bundle(input: EvidenceOsRunInput) -> Result<EvidenceOsRun
         requested_by: "user".to_string(),
     };
 
-    let outcome = manager
-        .export_run(&export_req, &bundle_inputs, &bundle_root, &bundle_zip)
-        .map_err(|e| format!("failed to export EvidenceOS bundle: {}", e))?;
-    if outcome.status != "COMPLETED" {
+    let outcome = manager.export_run(&export_req, &bundle_inputs, &bundle_root, &bundle_zip);
+    active.finish();
+    let outcome = outcome.map_err(|e| format!("failed to export EvidenceOS bundle: {}", e))?;
+    if outcome.status != ExportStatus::COMPLETED {
         return Err(format!(
-            "EvidenceOS export did not complete. status={} block_reason={:?}",
-            outcome.status, outcome.block_reason
+            "EvidenceOS export did not complete. status={:?} block_reasons={:?}",
+            outcome.status,
+            outcome
+                .block_reasons
+                .iter()
+                .map(|b| &b.reason)
+                .collect::<Vec<_>>()
         ));
     }
 
@@ -394,8 +447,11 @@ fn generate_evidenceos_bundle(input: EvidenceOsRunInput) -> Result<EvidenceOsRun
     })
 }
 
-#[tauri::command]
-fn run_redlineos(input: RedlineOsInputV1) -> Result<PackCommandStatus, String> {
+#[tauri::command(async)]
+fn run_redlineos(
+    input: RedlineOsInputV1,
+    active: tauri::State<'_, ActiveExport>,
+) -> Result<PackCommandStatus, String> {
     // Step 1: Validate input
     let _state = RedlineWorkflowState::ingest(input.clone()).map_err(|e| e.to_string())?;
 
@@ -472,14 +528,14 @@ fn run_redlineos(input: RedlineOsInputV1) -> Result<PackCommandStatus, String> {
     };
 
     // Step 8: Create RunManager and execute export pipeline
-    let mut run_manager = RunManager::new(audit);
-    let outcome = run_manager
-        .export_run(&export_request, &bundle_inputs, &bundle_root, &bundle_zip)
-        .map_err(|e| format!("Export failed: {}", e))?;
+    let mut run_manager = RunManager::new(audit).with_cancellation_token(active.begin());
+    let outcome = run_manager.export_run(&export_request, &bundle_inputs, &bundle_root, &bundle_zip);
+    active.finish();
+    let outcome = outcome.map_err(|e| format!("Export failed: {}", e))?;
 
     // Step 9: Return result
-    match outcome.status.as_str() {
-        "COMPLETED" => Ok(PackCommandStatus {
+    match outcome.status {
+        ExportStatus::COMPLETED => Ok(PackCommandStatus {
             status: "SUCCESS".to_string(),
             message: format!(
                 "RedlineOS bundle exported. Risk level: {} HIGH risks. Extraction confidence: {:.0}%",
@@ -487,11 +543,22 @@ fn run_redlineos(input: RedlineOsInputV1) -> Result<PackCommandStatus, String> {
                 workflow_output.extraction_confidence * 100.0
             ),
         }),
-        "BLOCKED" => Ok(PackCommandStatus {
+        ExportStatus::CANCELLED => Ok(PackCommandStatus {
+            status: "CANCELLED".to_string(),
+            message: "Export cancelled".to_string(),
+        }),
+        ExportStatus::BLOCKED => Ok(PackCommandStatus {
             status: "BLOCKED".to_string(),
-            message: format!("Export blocked: {:?}", outcome.block_reason),
+            message: format!(
+                "Export blocked: {:?}",
+                outcome
+                    .block_reasons
+                    .iter()
+                    .map(|b| &b.reason)
+                    .collect::<Vec<_>>()
+            ),
         }),
-        _ => Ok(PackCommandStatus {
+        ExportStatus::FAILED => Ok(PackCommandStatus {
             status: "FAILED".to_string(),
             message: "Export failed".to_string(),
         }),
@@ -540,6 +607,24 @@ fn run_healthcareos(input: HealthcareOsInputV1) -> Result<PackCommandStatus, Str
     })
 }
 
+#[tauri::command]
+fn cancel_export(active: tauri::State<'_, ActiveExport>) -> PackCommandStatus {
+    let token = active.0.lock().ok().and_then(|a| a.clone());
+    match token {
+        Some(token) => {
+            token.cancel();
+            PackCommandStatus {
+                status: "CANCELLING".to_string(),
+                message: "Cancellation requested; the export stops at its next step.".to_string(),
+            }
+        }
+        None => PackCommandStatus {
+            status: "IDLE".to_string(),
+            message: "No export is running.".to_string(),
+        },
+    }
+}
+
 fn make_runtime_dir() -> Result<std::path::PathBuf, String> {
     let ts = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -563,11 +648,13 @@ fn csv_to_vec(raw: &str) -> Vec<String> {
 
 fn main() {
     tauri::Builder::default()
+        .manage(ActiveExport::default())
         .invoke_handler(tauri::generate_handler![
             get_network_snapshot,
             list_control_library,
             generate_evidenceos_bundle,
             run_redlineos,
+            cancel_export,
             run_incidentos,
             run_financeos,
             run_healthcareos
@@ -125,6 +125,14 @@ export function App() {
     }
   };
 
+  const onCancelExport = async () => {
+    try {
+      await invoke<PackCommandStatus>("cancel_export");
+    } catch (error) {
+      setRunError(`Cancel failed: ${String(error)}`);
+    }
+  };
+
   const runFuturePack = async (command: string, input: unknown) => {
     setFuturePackRunning(command);
     setFuturePackError((prev) => ({ ...prev, [command]: "" }));
@@ -227,6 +235,11 @@ export function App() {
           <button type="button" disabled={running} onClick={onRunEvidenceOs}>
             {running ? "Generating EvidenceOS Bundle…" : "Generate EvidenceOS Bundle"}
           </button>
+          {running && (
+            <button type="button" onClick={onCancelExport}>
+              Cancel
+            </button>
+          )}
           {runError && <p className="error">Phase 3 run failed: {runError}</p>}
           {runResult && (
             <div className="result">
@@ -1,5 +1,5 @@
 use aigc_core::policy::types::PolicyMode;
-use aigc_core::validator::BundleValidator;
+use aigc_core::validator::{BundleValidator, CheckStatus};
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
@@ -24,7 +24,7 @@ fn main() {
     match v.validate_zip(path, policy) {
         Ok(summary) => {
             println!("{}", serde_json::to_string_pretty(&summary).unwrap());
-            if summary.overall == "PASS" {
+            if summary.overall == CheckStatus::PASS {
                 std::process::exit(0);
             } else {
                 std::process::exit(1);
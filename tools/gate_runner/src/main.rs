@@ -1,20 +1,24 @@
 use aigc_core::adapters::pinning::{classify_pinning_level, ModelSnapshot};
 use aigc_core::audit::event::{Actor, AuditEvent};
 use aigc_core::audit::log::AuditLog;
+use aigc_core::determinism::clock::clock_for_profile;
 use aigc_core::determinism::run_id::sha256_hex;
-use aigc_core::eval::runner::EvalRunner;
+use aigc_core::eval::runner::{EvalRunner, GateStatus};
+use aigc_core::error::CoreError;
 use aigc_core::evidence_bundle::artifact_hashes::{render_artifact_hashes_csv, ArtifactHashRow};
 use aigc_core::evidence_bundle::builder::EvidenceBundleBuilder;
 use aigc_core::evidence_bundle::schemas::*;
 use aigc_core::evidenceos::model::{CitationInput, EvidenceItem, NarrativeClaimInput};
 use aigc_core::evidenceos::workflow::{generate_evidenceos_artifacts, EvidenceOsRequest};
-use aigc_core::policy::allowlist::AllowlistEntry;
+use aigc_core::policy::allowlist::{allowlist_hash_sha256, AllowlistEntry};
+use aigc_core::policy::egress::{EgressClient, EgressPolicy};
 use aigc_core::policy::network_snapshot::{AdapterEndpointSnapshot, NetworkSnapshot};
 use aigc_core::policy::types::{InputExportProfile, NetworkMode, PolicyMode, ProofLevel};
 use aigc_core::run::lifecycle::{emit_vault_encryption_status, emit_vault_key_rotated};
 use aigc_core::storage::crypto::EncryptionAlgorithm;
 use aigc_core::storage::vault::{VaultConfig, VaultStorage};
-use aigc_core::validator::BundleValidator;
+use aigc_core::validator::checklist::Severity;
+use aigc_core::validator::{BundleValidator, CheckStatus};
 use serde_json::json;
 use std::path::PathBuf;
 
@@ -82,11 +86,11 @@ fn run_pack_validation_cycle(
         .validate_zip(bundle_zip, policy)
         .expect("validate zip");
     println!(
-        "{} BUNDLE_VALIDATOR overall={} sha256={}",
+        "{} BUNDLE_VALIDATOR overall={:?} sha256={}",
         label, summary.overall, zip_sha256
     );
     for c in &summary.checks {
-        println!("{} CHECK {} {} {}", label, c.check_id, c.result, c.message);
+        println!("{} CHECK {} {:?} {}", label, c.check_id, c.result, c.message);
     }
 
     let eval = EvalRunner::new_v3().expect("registry v3");
@@ -95,12 +99,12 @@ fn run_pack_validation_cycle(
         .expect("run gates");
     let mut any_blocker_fail = false;
     for g in &gate_results {
-        println!("{} GATE {} {} {}", label, g.gate_id, g.result, g.message);
-        if g.severity == "BLOCKER" && g.result != "PASS" && g.result != "NOT_APPLICABLE" {
+        println!("{} GATE {} {:?} {}", label, g.gate_id, g.result, g.message);
+        if g.severity == Severity::BLOCKER && g.result == GateStatus::FAIL {
             any_blocker_fail = true;
         }
     }
-    !any_blocker_fail && summary.overall == "PASS"
+    !any_blocker_fail && summary.overall == CheckStatus::PASS
 }
 
 fn make_self_audit_inputs(bundle_root: &PathBuf, policy_mode: PolicyMode) -> EvidenceBundleInputs {
@@ -145,8 +149,10 @@ fn make_self_audit_inputs(bundle_root: &PathBuf, policy_mode: PolicyMode) -> Evi
 
     // Audit log with required events.
     let audit_path = bundle_root.join("audit_log.ndjson");
-    let mut audit = AuditLog::open_or_create(&audit_path).expect("audit log open");
-    emit_vault_encryption_status(&mut audit, &run_id, &vault_id, &vault, &fixed_ts())
+    let mut audit = AuditLog::open_or_create(&audit_path)
+        .expect("audit log open")
+        .with_clock(clock_for_profile(determinism_enabled));
+    emit_vault_encryption_status(&mut audit, &run_id, &vault_id, &vault)
         .expect("emit vault encryption status");
     let rotation = vault.rotate_dek("kek_v2").expect("rotate dek");
     emit_vault_key_rotated(
@@ -161,13 +167,12 @@ fn make_self_audit_inputs(bundle_root: &PathBuf, policy_mode: PolicyMode) -> Evi
             .get("new_key_id")
             .and_then(|x| x.as_str())
             .unwrap_or("kek_v2"),
-        &fixed_ts(),
     )
     .expect("emit key rotated");
 
     let _ = audit
         .append(AuditEvent {
-            ts_utc: fixed_ts(),
+            ts_utc: String::new(),
             event_type: "NETWORK_MODE_SET".to_string(),
             run_id: run_id.clone(),
             vault_id: vault_id.clone(),
@@ -183,40 +188,20 @@ fn make_self_audit_inputs(bundle_root: &PathBuf, policy_mode: PolicyMode) -> Evi
         .unwrap();
     let _ = audit
         .append(AuditEvent {
-            ts_utc: fixed_ts(),
+            ts_utc: String::new(),
             event_type: "ALLOWLIST_UPDATED".to_string(),
             run_id: run_id.clone(),
             vault_id: vault_id.clone(),
             actor: Actor::System,
             details: json!({
-                "allowlist_hash_sha256": sha256_hex(b""),
+                "allowlist_hash_sha256": allowlist_hash_sha256(&[]).unwrap(),
                 "allowlist_count": 0
             }),
             prev_event_hash: "".to_string(),
             event_hash: "".to_string(),
         })
         .unwrap();
-    let _ = audit
-        .append(AuditEvent {
-            ts_utc: fixed_ts(),
-            event_type: "EGRESS_REQUEST_BLOCKED".to_string(),
-            run_id: run_id.clone(),
-            vault_id: vault_id.clone(),
-            actor: Actor::System,
-            details: json!({
-                "destination": {
-                    "scheme": "https",
-                    "host": "example.invalid",
-                    "port": 443,
-                    "path": "/blocked"
-                },
-                "block_reason": "OFFLINE_MODE",
-                "request_hash_sha256": sha256_hex(b"blocked_request")
-            }),
-            prev_event_hash: "".to_string(),
-            event_hash: "".to_string(),
-        })
-        .unwrap();
+    probe_offline_egress(&mut audit, &run_id, &vault_id);
 
     let audit_log_ndjson = std::fs::read_to_string(&audit_path).unwrap();
 
@@ -256,6 +241,7 @@ fn make_self_audit_inputs(bundle_root: &PathBuf, policy_mode: PolicyMode) -> Evi
         },
         encryption_at_rest: true,
         encryption_algorithm: "XCHACHA20_POLY1305".to_string(),
+        model_catalog: None,
     };
 
     let artifact_list = ArtifactList {
@@ -508,10 +494,12 @@ fn make_evidenceos_inputs(policy_mode: PolicyMode) -> EvidenceBundleInputs {
     let generated = generate_evidenceos_artifacts(&evidence_req).expect("generate evidenceos outputs");
 
     let audit_path = std::env::temp_dir().join(format!("audit_{}.ndjson", run_id));
-    let mut audit = AuditLog::open_or_create(&audit_path).expect("open audit");
+    let mut audit = AuditLog::open_or_create(&audit_path)
+        .expect("open audit")
+        .with_clock(clock_for_profile(determinism_enabled));
     let _ = audit
         .append(AuditEvent {
-            ts_utc: fixed_ts(),
+            ts_utc: String::new(),
             event_type: "VAULT_ENCRYPTION_STATUS".to_string(),
             run_id: run_id.clone(),
             vault_id: vault_id.clone(),
@@ -527,7 +515,7 @@ fn make_evidenceos_inputs(policy_mode: PolicyMode) -> EvidenceBundleInputs {
         .unwrap();
     let _ = audit
         .append(AuditEvent {
-            ts_utc: fixed_ts(),
+            ts_utc: String::new(),
             event_type: "NETWORK_MODE_SET".to_string(),
             run_id: run_id.clone(),
             vault_id: vault_id.clone(),
@@ -543,40 +531,20 @@ fn make_evidenceos_inputs(policy_mode: PolicyMode) -> EvidenceBundleInputs {
         .unwrap();
     let _ = audit
         .append(AuditEvent {
-            ts_utc: fixed_ts(),
+            ts_utc: String::new(),
             event_type: "ALLOWLIST_UPDATED".to_string(),
             run_id: run_id.clone(),
             vault_id: vault_id.clone(),
             actor: Actor::System,
             details: json!({
-                "allowlist_hash_sha256": sha256_hex(b""),
+                "allowlist_hash_sha256": allowlist_hash_sha256(&[]).unwrap(),
                 "allowlist_count": 0
             }),
             prev_event_hash: "".to_string(),
             event_hash: "".to_string(),
         })
         .unwrap();
-    let _ = audit
-        .append(AuditEvent {
-            ts_utc: fixed_ts(),
-            event_type: "EGRESS_REQUEST_BLOCKED".to_string(),
-            run_id: run_id.clone(),
-            vault_id: vault_id.clone(),
-            actor: Actor::System,
-            details: json!({
-                "destination": {
-                    "scheme": "https",
-                    "host": "example.invalid",
-                    "port": 443,
-                    "path": "/blocked"
-                },
-                "block_reason": "OFFLINE_MODE",
-                "request_hash_sha256": sha256_hex(b"blocked_request")
-            }),
-            prev_event_hash: "".to_string(),
-            event_hash: "".to_string(),
-        })
-        .unwrap();
+    probe_offline_egress(&mut audit, &run_id, &vault_id);
     let audit_log_ndjson = std::fs::read_to_string(&audit_path).expect("read audit");
     let _ = std::fs::remove_file(&audit_path);
 
@@ -718,6 +686,7 @@ fn make_evidenceos_inputs(policy_mode: PolicyMode) -> EvidenceBundleInputs {
             },
             encryption_at_rest: true,
             encryption_algorithm: "XCHACHA20_POLY1305".to_string(),
+            model_catalog: None,
         },
         network_snapshot: NetworkSnapshot {
             network_mode: NetworkMode::OFFLINE,
@@ -749,7 +718,27 @@ fn make_evidenceos_inputs(policy_mode: PolicyMode) -> EvidenceBundleInputs {
     }
 }
 
-fn fixed_ts() -> String {
-    // For deterministic self-audit, use a fixed timestamp.
-    "2026-02-10T00:00:00Z".to_string()
+// Attempts one request under the offline policy so the bundle records a real refusal.
+fn probe_offline_egress(audit: &mut AuditLog, run_id: &str, vault_id: &str) {
+    let mut egress = EgressClient::new(
+        EgressPolicy {
+            network_mode: NetworkMode::OFFLINE,
+            proof_level: ProofLevel::OFFLINE_STRICT,
+            allowlist: vec![],
+        },
+        audit,
+        run_id,
+        vault_id,
+    );
+    let sent = egress.send(
+        "POST",
+        "http://example.invalid/blocked",
+        &[],
+        b"blocked_request",
+        std::time::Duration::from_secs(5),
+    );
+    assert!(
+        matches!(sent, Err(CoreError::PolicyBlocked(_))),
+        "offline egress probe was not blocked"
+    );
 }